@@ -1,13 +1,72 @@
 use crate::app::event::AppEvent;
 use crate::app::mode::AppMode;
+use crate::app::notify::{Notifications, Severity};
+use crate::app::queue::{ReadingQueue, TITLE_CARD_MS};
 use crate::app::render_state::RenderState;
-use crate::engine::{tokenize_text, ReadingState};
-use crate::input::{clipboard, epub, pdf, LoadError, LoadedDocument};
+use crate::engine::config::TimingConfig;
+use crate::engine::error::{error_chain, SpeedyError};
+use crate::engine::{tokenize_text, wpm_to_milliseconds, ReadingState};
+use crate::input::background::{LoadJob, LoadProgress, LoadUpdate};
+use crate::input::clipboard::{ClipboardWatcher, Selection};
+use crate::reading::chunk::Chunker;
+use crate::reading::hyphenation::Hyphenator;
+use std::collections::VecDeque;
 use std::path::Path;
 
+use crate::input::{
+    clipboard, dispatch, queue, range, subtitle, Cue, LoadError, LoadOptions, LoadRange,
+    LoadedDocument, Section,
+};
+
 pub struct App {
     pub mode: AppMode,
     pub reading_state: Option<ReadingState>,
+    /// Footnote text currently flashed over the reading zone, if any.
+    pub active_note: Option<String>,
+    /// Loader options for files loaded without their own `#range` fragment.
+    pub load_options: LoadOptions,
+    /// Reading speed for newly loaded documents.
+    pub default_wpm: u32,
+    /// Timing and pacing model for newly loaded documents.
+    pub timing: TimingConfig,
+    /// Subtitle cues of the current document; empty for other formats.
+    pub cues: Vec<Cue>,
+    /// Headings of the current document, for `h`/`l` jumps and the deck.
+    pub sections: Vec<Section>,
+    /// Pace subtitle words by their cue timings instead of by WPM.
+    pub follow_cue_timing: bool,
+    /// Split long words into hyphenated fragments for the document language.
+    pub hyphenate: bool,
+    /// Show short words together in chunks of two or three.
+    pub chunk: bool,
+    /// Notices, warnings and errors for the command deck and `:messages`.
+    pub notifications: Notifications,
+    /// The `:messages` panel is shown over the reading zone.
+    pub messages_open: bool,
+    /// Documents opened from a directory or glob, if any.
+    pub queue: Option<ReadingQueue>,
+    /// Title flashed before the first word of a queued document.
+    pub title_card: Option<String>,
+    /// Selection watched by `@@watch`, if any.
+    pub clipboard_watch: Option<ClipboardWatcher>,
+    /// Copied passages waiting for the current one to finish.
+    pub pending_passages: VecDeque<LoadedDocument>,
+    /// File loading on a worker thread, if any.
+    pub load_job: Option<LoadJob>,
+    /// Latest progress of `load_job`; `None` until its loader reports.
+    pub load_progress: Option<LoadProgress>,
+    /// A partial document from `load_job` is being read.
+    pub reading_partial: bool,
+    /// `load_job` is loading the current item of `queue`.
+    queue_load: Option<QueueLoad>,
+}
+
+/// A reading queue item loading on the worker thread.
+struct QueueLoad {
+    /// Unreadable items are skipped this way (1 or -1).
+    direction: isize,
+    /// Queue to go back to if no item that way can be read.
+    previous: Option<ReadingQueue>,
 }
 
 impl App {
@@ -15,6 +74,25 @@ impl App {
         Self {
             mode: AppMode::default(),
             reading_state: None,
+            active_note: None,
+            load_options: LoadOptions::default(),
+            default_wpm: 300, // Default WPM per PRD Section 3.2
+            timing: TimingConfig::default(),
+            cues: Vec::new(),
+            sections: Vec::new(),
+            follow_cue_timing: false,
+            hyphenate: false,
+            chunk: false,
+            notifications: Notifications::default(),
+            messages_open: false,
+            queue: None,
+            title_card: None,
+            clipboard_watch: None,
+            pending_passages: VecDeque::new(),
+            load_job: None,
+            load_progress: None,
+            reading_partial: false,
+            queue_load: None,
         }
     }
 
@@ -26,14 +104,21 @@ impl App {
 
     /// Advances to the next word in the reading stream.
     ///
-    /// Used by TuiManager for auto-advancement in Reading mode.
+    /// Used by TuiManager for auto-advancement in Reading mode. A flashed
+    /// title card gives way to the first word, and the end of a queued
+    /// document or watched passage moves on to the next one.
     /// Returns `true` if advanced, `false` if at end or no reading state.
     pub fn advance_reading(&mut self) -> bool {
+        if self.title_card.take().is_some() {
+            return true;
+        }
         match self.reading_state.as_mut() {
             Some(state) => {
-                let before = state.current_index;
+                let before = (state.current_index, state.fragment);
                 state.advance();
-                state.current_index > before
+                (state.current_index, state.fragment) != before
+                    || (self.queue_load.is_none() && self.skip_document(1))
+                    || self.next_passage()
             }
             None => false,
         }
@@ -63,13 +148,22 @@ impl App {
                 self.handle_load_file(&path);
             }
             AppEvent::LoadClipboard => {
-                self.handle_load_clipboard();
+                self.handle_load_clipboard(Selection::Clipboard);
+            }
+            AppEvent::LoadPrimarySelection => {
+                self.handle_load_clipboard(Selection::Primary);
+            }
+            AppEvent::WatchClipboard(selection) => {
+                self.toggle_clipboard_watch(selection);
+            }
+            AppEvent::ShowMessages => {
+                self.messages_open = true;
             }
             AppEvent::Warning(msg) => {
-                eprintln!("Warning: {}", msg);
+                self.notify(Severity::Warning, msg);
             }
             AppEvent::InvalidCommand(cmd) => {
-                eprintln!("Unknown command: {}", cmd);
+                self.report(Severity::Error, &SpeedyError::UnknownCommand(cmd));
             }
             AppEvent::None => {
                 // No action required
@@ -77,53 +171,415 @@ impl App {
         }
     }
 
-    fn handle_load_file(&mut self, path: &str) {
-        let path = Path::new(path);
+    fn handle_load_file(&mut self, spec: &str) {
+        self.stop_clipboard_watch();
+        if let Err(e) = self.start_loading(spec) {
+            self.report_load_error(spec, e);
+        }
+    }
 
-        // Detect file extension
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|e| e.to_lowercase());
+    /// Loads a file by extension and switches to Reading mode.
+    ///
+    /// `spec` is a path with an optional `#p12-30` / `#ch3` range fragment,
+    /// which overrides the default range in `load_options`. A directory or
+    /// glob pattern opens a reading queue of the documents it matches.
+    pub fn open_file(&mut self, spec: &str) -> Result<(), LoadError> {
+        if let Some(items) = queue::expand(spec)? {
+            self.queue = Some(ReadingQueue::new(items));
+            return self.load_queue_item(1);
+        }
 
-        match ext.as_deref() {
-            Some("pdf") => match pdf::load(path.to_str().unwrap_or("")) {
-                Ok(doc) => self.apply_loaded_document(doc),
-                Err(e) => self.handle_load_error(&e),
-            },
-            Some("epub") => match epub::load(path.to_str().unwrap_or("")) {
-                Ok(doc) => self.apply_loaded_document(doc),
-                Err(e) => self.handle_load_error(&e),
-            },
-            Some(_) | None => {
-                let filename = path.file_name().map_or_else(
-                    || "unknown".to_string(),
-                    |n| n.to_string_lossy().to_string(),
+        let (path, options) = self.resolve_spec(spec);
+        let doc = dispatch::load_path(path, &options)?;
+        self.queue = None;
+        self.title_card = None;
+        self.apply_loaded_document(doc);
+        Ok(())
+    }
+
+    /// Opens the file named on the command line.
+    ///
+    /// A `--pages` / `--chapters` range applies to this file alone, as if it
+    /// were its fragment, so later `@file` loads read whole documents. A
+    /// fragment already in `spec` wins.
+    pub fn open_initial_file(
+        &mut self,
+        spec: &str,
+        range: Option<LoadRange>,
+    ) -> Result<(), LoadError> {
+        match range {
+            Some(range) if range::split_path(spec).1.is_none() => {
+                self.open_file(&format!("{}#{}", spec, range))
+            }
+            _ => self.open_file(spec),
+        }
+    }
+
+    /// Like [`App::open_file`], but loads on a worker thread.
+    ///
+    /// Used for `@file` so the TUI stays responsive; TuiManager calls
+    /// [`App::poll_loading`] to pick up progress and the document. Reading
+    /// queues load each item the same way.
+    pub fn start_loading(&mut self, spec: &str) -> Result<(), LoadError> {
+        self.stop_loading();
+        if let Some(items) = queue::expand(spec)? {
+            let previous = self.queue.replace(ReadingQueue::new(items));
+            self.start_queue_item(1, previous);
+            return Ok(());
+        }
+
+        let (path, options) = self.resolve_spec(spec);
+        self.load_job = Some(LoadJob::spawn(spec, path, options));
+        self.notify(Severity::Info, format!("Loading {} (Esc cancels)", spec));
+        Ok(())
+    }
+
+    /// Splits a range fragment off `spec`, returning the path and the
+    /// options to load it with.
+    fn resolve_spec<'a>(&self, spec: &'a str) -> (&'a str, LoadOptions) {
+        let (path, fragment_range) = range::split_path(spec);
+        let mut options = self.load_options.clone();
+        if fragment_range.is_some() {
+            options.range = fragment_range;
+        }
+        (path, options)
+    }
+
+    /// Applies whatever the background load has sent since the last call.
+    ///
+    /// Returns `true` if anything arrived.
+    pub fn poll_loading(&mut self) -> bool {
+        let mut received = false;
+        while let Some(update) = self.load_job.as_ref().and_then(LoadJob::try_next) {
+            received = true;
+            self.apply_load_update(update);
+        }
+        received
+    }
+
+    /// Handles one message from the background load.
+    ///
+    /// The first partial document starts reading; later partials and the
+    /// final document extend it without moving the reader.
+    pub fn apply_load_update(&mut self, update: LoadUpdate) {
+        match update {
+            LoadUpdate::Progress(progress) => self.load_progress = Some(progress),
+            LoadUpdate::Partial(doc) => self.show_loaded(doc, self.queue_load.is_some()),
+            LoadUpdate::Done(result) => {
+                let spec = self
+                    .load_job
+                    .take()
+                    .map(|job| job.spec().to_string())
+                    .unwrap_or_default();
+                self.load_progress = None;
+                match result {
+                    Ok(doc) => {
+                        let queue_load = self.queue_load.take();
+                        let notices = doc.notices.clone();
+                        let partial = self.reading_partial;
+                        self.show_loaded(doc, queue_load.is_some());
+                        if partial {
+                            for notice in notices {
+                                self.notify(Severity::Info, notice);
+                            }
+                        }
+                    }
+                    Err(e) if self.reading_partial => {
+                        self.notify(
+                            Severity::Warning,
+                            format!("Stopped loading {}: {}", spec, error_chain(&e)),
+                        );
+                    }
+                    Err(e) => {
+                        self.report_load_error(&spec, e);
+                        if let Some(load) = self.queue_load.take() {
+                            self.skip_unreadable(load);
+                        }
+                    }
+                }
+                self.reading_partial = false;
+            }
+        }
+    }
+
+    /// Starts reading a document from the background load, or swaps it in
+    /// for the partial one being read. A `queued` document keeps the queue.
+    fn show_loaded(&mut self, doc: LoadedDocument, queued: bool) {
+        match self.reading_state.as_mut() {
+            Some(state) if self.reading_partial => {
+                state.replace_tokens(doc.tokens);
+                self.cues = doc.cues;
+                self.sections = doc.sections;
+            }
+            _ if queued => {
+                let path = self.queue.as_ref().map(|q| q.current().to_path_buf());
+                self.show_queue_item(&path.unwrap_or_default(), doc);
+                self.reading_partial = self.load_job.is_some();
+            }
+            _ => {
+                self.queue = None;
+                self.title_card = None;
+                self.apply_loaded_document(doc);
+                self.reading_partial = self.load_job.is_some();
+            }
+        }
+    }
+
+    /// Cancels the background load (Esc while loading).
+    ///
+    /// Anything already being read stays. Returns `false` if nothing was
+    /// loading.
+    pub fn cancel_loading(&mut self) -> bool {
+        let Some(job) = self.load_job.as_ref() else {
+            return false;
+        };
+        let spec = job.spec().to_string();
+        let progress = self.load_progress;
+        let partial = self.reading_partial;
+        self.stop_loading();
+        let message = match progress {
+            Some(p) if partial => format!(
+                "Cancelled loading {}: reading the first {} of {} {}",
+                spec,
+                p.done,
+                p.total,
+                p.unit.name()
+            ),
+            _ => format!("Cancelled loading {}", spec),
+        };
+        self.notify(Severity::Warning, message);
+        true
+    }
+
+    fn stop_loading(&mut self) {
+        if let Some(job) = self.load_job.take() {
+            job.cancel();
+        }
+        // A queue item that never arrived leaves the queue where it was
+        if let Some(load) = self.queue_load.take() {
+            if !self.reading_partial {
+                self.queue = load.previous;
+            }
+        }
+        self.load_progress = None;
+        self.reading_partial = false;
+    }
+
+    /// Loads the current queue item directly, for [`App::open_file`].
+    ///
+    /// Unreadable items are reported and skipped in `direction` (1 or -1);
+    /// the error is returned only if no readable item is left that way.
+    fn load_queue_item(&mut self, direction: isize) -> Result<(), LoadError> {
+        loop {
+            let Some(path) = self.queue.as_ref().map(|q| q.current().to_path_buf()) else {
+                return Ok(());
+            };
+            match dispatch::load_path(&path.to_string_lossy(), &self.load_options) {
+                Ok(doc) => {
+                    self.show_queue_item(&path, doc);
+                    return Ok(());
+                }
+                Err(e) => {
+                    if !self.queue.as_mut().is_some_and(|q| q.step(direction)) {
+                        return Err(e);
+                    }
+                    self.report_load_error(&path.to_string_lossy(), e);
+                }
+            }
+        }
+    }
+
+    /// Starts reading a queue item and flashes its title. Reading speed
+    /// carries over from the previous document.
+    fn show_queue_item(&mut self, path: &Path, doc: LoadedDocument) {
+        let wpm = self.reading_state.as_ref().map(|state| state.wpm);
+        let title = doc.metadata.label().unwrap_or_else(|| {
+            path.file_name()
+                .map_or_else(String::new, |n| n.to_string_lossy().to_string())
+        });
+        self.apply_loaded_document(doc);
+        if let (Some(wpm), Some(state)) = (wpm, self.reading_state.as_mut()) {
+            state.wpm = wpm;
+        }
+        self.title_card = Some(title);
+    }
+
+    /// Loads the current queue item on the worker thread.
+    fn start_queue_item(&mut self, direction: isize, previous: Option<ReadingQueue>) {
+        let Some(path) = self.queue.as_ref().map(|q| q.current().to_path_buf()) else {
+            return;
+        };
+        let path = path.to_string_lossy();
+        self.load_job = Some(LoadJob::spawn(&path, &path, self.load_options.clone()));
+        self.queue_load = Some(QueueLoad {
+            direction,
+            previous,
+        });
+    }
+
+    /// Moves past a queue item that could not be loaded, or back to the
+    /// previous queue if no item is left that way.
+    fn skip_unreadable(&mut self, load: QueueLoad) {
+        if self.queue.as_mut().is_some_and(|q| q.step(load.direction)) {
+            self.start_queue_item(load.direction, load.previous);
+        } else {
+            self.queue = load.previous;
+        }
+    }
+
+    /// Moves `step` documents through the reading queue (`n` / `N`) and
+    /// starts loading that document.
+    ///
+    /// Returns `false` if there is no queue or no document that way. If no
+    /// readable document is found, the queue goes back to where it was.
+    pub fn skip_document(&mut self, step: isize) -> bool {
+        let Some(mut queue) = self.queue.clone() else {
+            return false;
+        };
+        if !queue.step(step) {
+            return false;
+        }
+        // Skipping again while an item loads steps on from that item
+        let previous = match self.queue_load.take() {
+            Some(load) => load.previous,
+            None => self.queue.clone(),
+        };
+        self.stop_loading();
+        self.queue = Some(queue);
+        self.start_queue_item(step.signum(), previous);
+        true
+    }
+
+    fn handle_load_clipboard(&mut self, selection: Selection) {
+        self.stop_clipboard_watch();
+        self.stop_loading();
+        match clipboard::load_selection(selection) {
+            Ok(doc) => {
+                self.queue = None;
+                self.title_card = None;
+                self.apply_loaded_document(doc);
+            }
+            Err(e) => self.report_load_error(&format!("the {}", selection.name()), e),
+        }
+    }
+
+    /// Starts watching `selection` for copied passages (`@@watch`), or stops
+    /// if it is already being watched.
+    fn toggle_clipboard_watch(&mut self, selection: Selection) {
+        let watching = self
+            .clipboard_watch
+            .as_ref()
+            .map(ClipboardWatcher::selection);
+        self.stop_clipboard_watch();
+        if watching == Some(selection) {
+            self.notify(
+                Severity::Info,
+                format!("Stopped watching the {}", selection.name()),
+            );
+            return;
+        }
+        match ClipboardWatcher::new(selection) {
+            Ok(watcher) => {
+                self.clipboard_watch = Some(watcher);
+                self.notify(
+                    Severity::Info,
+                    format!(
+                        "Watching the {}: copy text to read it, @@watch again to stop",
+                        selection.name()
+                    ),
                 );
-                eprintln!("Unsupported format: {}", filename);
-                eprintln!("Supported formats: .pdf, .epub");
-                eprintln!("For clipboard, use @@ command");
             }
+            Err(e) => self.report_load_error(&format!("the {}", selection.name()), e),
         }
     }
 
-    fn handle_load_clipboard(&mut self) {
-        match clipboard::load() {
-            Ok(doc) => self.apply_loaded_document(doc),
-            Err(e) => self.handle_load_error(&e),
+    fn stop_clipboard_watch(&mut self) {
+        self.clipboard_watch = None;
+        self.pending_passages.clear();
+    }
+
+    /// Checks the watched selection for a newly copied passage.
+    ///
+    /// Called by TuiManager every `clipboard::WATCH_INTERVAL_MS` while
+    /// watching. Returns `true` if a passage arrived.
+    pub fn poll_clipboard(&mut self) -> bool {
+        match self
+            .clipboard_watch
+            .as_mut()
+            .and_then(ClipboardWatcher::poll)
+        {
+            Some(doc) => {
+                self.receive_passage(doc);
+                true
+            }
+            None => false,
         }
     }
 
-    fn apply_loaded_document(&mut self, doc: LoadedDocument) {
-        self.reading_state = Some(ReadingState::new_with_default_config(
-            doc.tokens, 300, // Default WPM per PRD Section 3.2
-        ));
+    /// Reads a copied passage now, or after the one being read.
+    ///
+    /// A passage arriving before the current one's last word (even while
+    /// paused) waits its turn; otherwise it starts at once.
+    pub fn receive_passage(&mut self, doc: LoadedDocument) {
+        let mid_read = self
+            .reading_state
+            .as_ref()
+            .is_some_and(|state| state.current_index + 1 < state.tokens.len());
+        if mid_read {
+            self.pending_passages.push_back(doc);
+        } else {
+            self.start_passage(doc);
+        }
+    }
+
+    /// Starts the next waiting passage; returns `false` if there is none.
+    fn next_passage(&mut self) -> bool {
+        match self.pending_passages.pop_front() {
+            Some(doc) => {
+                self.start_passage(doc);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reads a watched passage at the current reading speed.
+    fn start_passage(&mut self, doc: LoadedDocument) {
+        let wpm = self.reading_state.as_ref().map(|state| state.wpm);
+        self.queue = None;
+        self.title_card = None;
+        self.apply_loaded_document(doc);
+        if let (Some(wpm), Some(state)) = (wpm, self.reading_state.as_mut()) {
+            state.wpm = wpm;
+        }
+    }
+
+    /// Starts reading a loaded document at `default_wpm`.
+    pub fn apply_loaded_document(&mut self, doc: LoadedDocument) {
+        let mut state = ReadingState::new(doc.tokens, self.default_wpm, self.timing.clone());
+        let language = doc.metadata.language.as_deref();
+        if self.hyphenate {
+            state.set_hyphenator(Some(Hyphenator::for_language(language)));
+        }
+        if self.chunk {
+            state.set_chunker(Some(Chunker::for_language(language)));
+        }
+        self.reading_state = Some(state);
+        self.cues = doc.cues;
+        self.sections = doc.sections;
         self.mode = AppMode::Reading;
-        eprintln!(
-            "Loaded: {} ({} words)",
-            doc.source,
-            self.reading_state.as_ref().map_or(0, |s| s.tokens.len())
-        );
+        let words = self.reading_state.as_ref().map_or(0, |s| s.tokens.len());
+        let loaded = match doc.metadata.label() {
+            Some(label) => format!("Loaded: {} ({}, {} words)", label, doc.source, words),
+            None => format!("Loaded: {} ({} words)", doc.source, words),
+        };
+        self.notify(Severity::Info, loaded);
+        if let Some(encoding) = doc.metadata.encoding.filter(|e| e != "UTF-8") {
+            self.notify(Severity::Info, format!("Decoded from {}", encoding));
+        }
+        for notice in doc.notices {
+            self.notify(Severity::Info, notice);
+        }
     }
 
     pub fn resume_reading(&mut self) -> Result<(), String> {
@@ -135,15 +591,79 @@ impl App {
         }
     }
 
-    pub fn get_render_state(&self) -> RenderState {
-        match &self.reading_state {
+    pub fn get_render_state(&self) -> RenderState<'_> {
+        let mut render_state = match &self.reading_state {
             Some(state) => RenderState::from_reading_state(
                 self.mode.clone(),
-                state.tokens.clone(),
+                &state.tokens,
                 state.current_index,
                 3, // context_window
             ),
             None => RenderState::empty(self.mode.clone()),
+        };
+        if let Some((fragment, hyphen)) = self
+            .reading_state
+            .as_ref()
+            .and_then(|s| s.current_fragment())
+        {
+            render_state.current_word = Some(fragment);
+            render_state.hyphen = hyphen;
+        }
+        if let Some(state) = self.reading_state.as_ref() {
+            let chunk = state.current_chunk();
+            if chunk.len() > 1 {
+                render_state.chunk = state.tokens.range(chunk).map(|t| t.text).collect();
+            }
+        }
+        render_state.note = self.active_note.clone();
+        render_state.notice = self.notifications.current();
+        render_state.messages = self
+            .messages_open
+            .then(|| self.notifications.history().collect());
+        render_state.title_card = self.title_card.clone();
+        render_state.queue_position = self.queue.as_ref().map(ReadingQueue::position);
+        render_state.section = self.reading_state.as_ref().and_then(|state| {
+            let count = self.sections.len();
+            (count > 0).then(|| (self.sections_started(state.current_index), count))
+        });
+        render_state.clipboard_watch = self
+            .clipboard_watch
+            .as_ref()
+            .map(|_| self.pending_passages.len());
+        render_state.loading = self.load_job.as_ref().map(|_| self.load_progress);
+        render_state
+    }
+
+    /// Pauses reading and flashes the note attached to the current word.
+    ///
+    /// Reading usually moves past the referencing word before the user reacts,
+    /// so the nearest note at or before the current word within the current
+    /// sentence is shown. Returns `false` if there is no such note.
+    pub fn show_note(&mut self) -> bool {
+        let Some(state) = self.reading_state.as_ref() else {
+            return false;
+        };
+
+        let note = state
+            .tokens
+            .range(0..state.current_index + 1)
+            .rev()
+            .scan(false, |passed_sentence_start, token| {
+                if *passed_sentence_start {
+                    return None;
+                }
+                *passed_sentence_start = token.is_sentence_start;
+                Some(token)
+            })
+            .find_map(|token| token.note.map(str::to_string));
+
+        match note {
+            Some(note) => {
+                self.active_note = Some(note);
+                self.mode = AppMode::Paused;
+                true
+            }
+            None => false,
         }
     }
 
@@ -159,27 +679,93 @@ impl App {
         self.reading_state
             .as_ref()
             .map(|state| state.wpm)
-            .unwrap_or(300) // Default 300 WPM
+            .unwrap_or(self.default_wpm)
     }
 
-    fn handle_load_error(&self, error: &LoadError) {
-        match error {
-            LoadError::FileNotFound(path) => {
-                eprintln!("Error: File not found: {}", path.display());
-            }
-            LoadError::PdfParse(msg) => {
-                eprintln!("Error: PDF parse error: {}", msg);
-            }
-            LoadError::EpubParse(msg) => {
-                eprintln!("Error: EPUB parse error: {}", msg);
-            }
-            LoadError::Clipboard(msg) => {
-                eprintln!("Error: Clipboard error: {}", msg);
-            }
-            LoadError::UnsupportedFormat(fmt) => {
-                eprintln!("Error: Unsupported format: {}", fmt);
-            }
+    /// Milliseconds to show the current word or chunk before advancing.
+    ///
+    /// Used by TuiManager as the tick interval. Follows the subtitle cue
+    /// window when `follow_cue_timing` is on, and the reading state's
+    /// timing otherwise.
+    pub fn current_delay_ms(&self) -> u64 {
+        if self.title_card.is_some() {
+            return TITLE_CARD_MS;
+        }
+        let cue_delay = match (&self.reading_state, self.follow_cue_timing) {
+            (Some(state), true) => subtitle::cue_delay_ms(&self.cues, state.current_index),
+            _ => None,
+        };
+        cue_delay.unwrap_or_else(|| match &self.reading_state {
+            Some(state) => state.current_duration(),
+            None => wpm_to_milliseconds(self.get_wpm()),
+        })
+    }
+
+    /// Switches between cue timing and WPM pacing for subtitle documents.
+    ///
+    /// Returns `false` when the document has no cues.
+    pub fn toggle_cue_timing(&mut self) -> bool {
+        if self.cues.is_empty() {
+            return false;
+        }
+        self.follow_cue_timing = !self.follow_cue_timing;
+        true
+    }
+
+    /// Posts a failed load of `spec` as an error notification with its
+    /// source chain and hint; a cancelled load is only a warning.
+    fn report_load_error(&mut self, spec: &str, error: LoadError) {
+        let severity = match error {
+            LoadError::Cancelled => Severity::Warning,
+            _ => Severity::Error,
+        };
+        self.report(severity, &SpeedyError::load(spec, error));
+    }
+
+    fn report(&mut self, severity: Severity, error: &SpeedyError) {
+        let message = match error.hint() {
+            Some(hint) => format!("{}. {}", error_chain(error), hint),
+            None => error_chain(error),
+        };
+        self.notify(severity, message);
+    }
+
+    pub fn notify(&mut self, severity: Severity, message: impl Into<String>) {
+        self.notifications.push(severity, message);
+    }
+
+    /// Number of sections starting at or before the word at `index`; the
+    /// current section is the last of them.
+    fn sections_started(&self, index: usize) -> usize {
+        self.sections.partition_point(|s| s.token_index <= index)
+    }
+
+    /// Moves to the start of the next section, or of the previous one
+    /// before the current word (`direction` -1). Returns `false` if there is
+    /// none that way.
+    fn jump_to_section(&mut self, direction: isize) -> bool {
+        let Some(state) = self.reading_state.as_ref() else {
+            return false;
+        };
+        let index = state.current_index;
+        let target = if direction > 0 {
+            self.sections.get(self.sections_started(index))
+        } else {
+            let before = self.sections.partition_point(|s| s.token_index < index);
+            before.checked_sub(1).and_then(|i| self.sections.get(i))
+        };
+        let Some(target) = target.map(|s| s.token_index) else {
+            return false;
+        };
+        if let Some(state) = self.reading_state.as_mut() {
+            state.seek_to(target);
         }
+        true
+    }
+
+    /// Closes the `:messages` panel; returns `false` if it was not open.
+    pub fn close_messages(&mut self) -> bool {
+        std::mem::take(&mut self.messages_open)
     }
 
     /// Handle keyboard input in Reading mode.
@@ -194,6 +780,11 @@ impl App {
             return false;
         }
 
+        // Any key dismisses a flashed note, notification or title card
+        self.active_note = None;
+        self.notifications.dismiss();
+        self.title_card = None;
+
         let reading_state = self.reading_state.as_mut().unwrap();
 
         match key {
@@ -207,6 +798,9 @@ impl App {
                 reading_state.jump_to_next_sentence();
                 true
             }
+            // Sections: h and l sit outside j and k, for the bigger jump
+            'h' | 'H' => self.jump_to_section(-1),
+            'l' | 'L' => self.jump_to_section(1),
             // WPM: Decrease (PRD Section 7.2)
             '[' => {
                 reading_state.adjust_wpm(-50);
@@ -222,6 +816,13 @@ impl App {
                 self.toggle_pause();
                 true
             }
+            // Flash the footnote attached to the current word
+            'f' | 'F' => self.show_note(),
+            // Subtitles: follow the original cue timing or read at WPM
+            't' | 'T' => self.toggle_cue_timing(),
+            // Reading queue: next / previous document
+            'n' => self.skip_document(1),
+            'N' => self.skip_document(-1),
             // Quit to REPL (PRD Section 7.2)
             'q' | 'Q' => {
                 self.mode = AppMode::Command;
@@ -235,26 +836,23 @@ impl App {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::background::ProgressUnit;
+    use crate::input::Metadata;
     use crate::reading::token::Token;
 
     #[test]
     fn test_apply_loaded_document() {
         let mut app = App::new();
-        let doc = LoadedDocument {
-            tokens: vec![
+        let doc = LoadedDocument::new(
+            vec![
                 Token {
-                    text: "hello".to_string(),
-                    punctuation: vec![],
                     is_sentence_start: true,
+                    ..Token::new("hello")
                 },
-                Token {
-                    text: "world".to_string(),
-                    punctuation: vec![],
-                    is_sentence_start: false,
-                },
+                Token::new("world"),
             ],
-            source: "test.pdf".to_string(),
-        };
+            "test.pdf",
+        );
 
         assert_eq!(app.mode, AppMode::Command);
         assert!(app.reading_state.is_none());
@@ -267,6 +865,88 @@ mod tests {
         assert_eq!(app.reading_state.as_ref().unwrap().current_index, 0);
     }
 
+    #[test]
+    fn test_hyphenate_shows_long_words_in_fragments() {
+        let mut app = App::new();
+        app.hyphenate = true;
+        app.apply_loaded_document(LoadedDocument {
+            metadata: Metadata {
+                language: Some("de".to_string()),
+                ..Metadata::default()
+            },
+            ..LoadedDocument::new(tokenize_text("Donaudampfschifffahrt fährt"), "test.txt")
+        });
+
+        let render = app.get_render_state();
+        assert_eq!(
+            (render.current_word, render.hyphen),
+            (Some("Donaudampf"), true)
+        );
+        assert!(app.advance_reading());
+        let render = app.get_render_state();
+        assert_eq!(
+            (render.current_word, render.hyphen),
+            (Some("schifffahrt"), false)
+        );
+        assert_eq!(render.current_index, 0);
+        assert!(app.advance_reading());
+        assert_eq!(app.get_render_state().current_word, Some("fährt"));
+    }
+
+    #[test]
+    fn test_sections_navigate_and_show_progress() {
+        let mut builder = crate::input::DocumentBuilder::new();
+        builder.paragraph("Before any heading.");
+        builder.heading(1, "One");
+        builder.paragraph("First part.");
+        builder.heading(1, "Two");
+        builder.paragraph("Second part.");
+        let mut app = App::new();
+        app.apply_loaded_document(builder.build("test".to_string(), &[]).unwrap());
+        let heading = |app: &App, n: usize| app.sections[n].token_index;
+
+        assert_eq!(app.get_render_state().section, Some((0, 2)));
+        assert!(app.handle_keypress('l'));
+        assert_eq!(
+            app.reading_state.as_ref().unwrap().current_index,
+            heading(&app, 0)
+        );
+        assert_eq!(app.get_render_state().section, Some((1, 2)));
+        assert!(app.handle_keypress('l'));
+        assert_eq!(app.get_render_state().section, Some((2, 2)));
+        assert!(!app.handle_keypress('l'));
+
+        app.advance_reading();
+        assert!(app.handle_keypress('h'));
+        assert_eq!(
+            app.reading_state.as_ref().unwrap().current_index,
+            heading(&app, 1)
+        );
+        assert!(app.handle_keypress('h'));
+        assert_eq!(app.get_render_state().section, Some((1, 2)));
+        assert!(!app.handle_keypress('h'));
+    }
+
+    #[test]
+    fn test_chunk_mode_shows_and_times_words_together() {
+        let mut app = App::new();
+        app.chunk = true;
+        app.apply_loaded_document(LoadedDocument::new(
+            tokenize_text("The cat sat on the mat"),
+            "test.txt",
+        ));
+
+        let render = app.get_render_state();
+        assert_eq!(render.chunk, ["The", "cat", "sat"]);
+        assert_eq!(render.current_index, 0);
+        assert_eq!(app.current_delay_ms(), 600);
+
+        assert!(app.advance_reading());
+        assert_eq!(app.get_render_state().chunk, ["on", "the", "mat"]);
+        assert_eq!(app.reading_state.as_ref().unwrap().current_index, 3);
+        assert!(!app.advance_reading());
+    }
+
     #[test]
     fn test_handle_load_nonexistent_pdf() {
         let mut app = App::new();
@@ -299,20 +979,24 @@ mod tests {
     #[test]
     fn test_get_render_state_reading() {
         let mut app = App::new();
-        let doc = LoadedDocument {
-            tokens: vec![Token {
-                text: "hello".to_string(),
-                punctuation: vec![],
+        let doc = LoadedDocument::new(
+            vec![Token {
                 is_sentence_start: true,
+                ..Token::new("hello")
             }],
-            source: "test.pdf".to_string(),
-        };
+            "test.pdf",
+        );
         app.apply_loaded_document(doc);
 
         let render = app.get_render_state();
 
         assert_eq!(render.mode, AppMode::Reading);
-        assert_eq!(render.current_word, Some("hello".to_string()));
+        assert_eq!(render.current_word, Some("hello"));
+        // Frames borrow the document rather than copying it
+        assert!(std::ptr::eq(
+            render.tokens,
+            &app.reading_state.as_ref().unwrap().tokens
+        ));
     }
 
     #[test]
@@ -334,31 +1018,27 @@ mod tests {
     fn test_keypress_j_backward_sentence() {
         let mut app = App::new();
         // Create document with multiple sentences
-        let doc = LoadedDocument {
-            tokens: vec![
+        let doc = LoadedDocument::new(
+            vec![
                 Token {
-                    text: "First".to_string(),
-                    punctuation: vec![],
                     is_sentence_start: true,
+                    ..Token::new("First")
                 },
                 Token {
-                    text: "sentence".to_string(),
                     punctuation: vec!['.'],
-                    is_sentence_start: false,
+                    ..Token::new("sentence")
                 },
                 Token {
-                    text: "Second".to_string(),
-                    punctuation: vec![],
                     is_sentence_start: true,
+                    ..Token::new("Second")
                 },
                 Token {
-                    text: "sentence".to_string(),
                     punctuation: vec!['.'],
-                    is_sentence_start: false,
+                    ..Token::new("sentence")
                 },
             ],
-            source: "test.pdf".to_string(),
-        };
+            "test.pdf",
+        );
         app.apply_loaded_document(doc);
 
         // Initially at index 0
@@ -379,26 +1059,23 @@ mod tests {
     fn test_keypress_k_forward_sentence() {
         let mut app = App::new();
         // Create document with multiple sentences
-        let doc = LoadedDocument {
-            tokens: vec![
+        let doc = LoadedDocument::new(
+            vec![
                 Token {
-                    text: "First".to_string(),
-                    punctuation: vec![],
                     is_sentence_start: true,
+                    ..Token::new("First")
                 },
                 Token {
-                    text: "sentence".to_string(),
                     punctuation: vec!['.'],
-                    is_sentence_start: false,
+                    ..Token::new("sentence")
                 },
                 Token {
-                    text: "Second".to_string(),
-                    punctuation: vec![],
                     is_sentence_start: true,
+                    ..Token::new("Second")
                 },
             ],
-            source: "test.pdf".to_string(),
-        };
+            "test.pdf",
+        );
         app.apply_loaded_document(doc);
 
         // Initially at index 0
@@ -413,14 +1090,13 @@ mod tests {
     #[test]
     fn test_keypress_bracket_increase_wpm() {
         let mut app = App::new();
-        let doc = LoadedDocument {
-            tokens: vec![Token {
-                text: "test".to_string(),
-                punctuation: vec![],
+        let doc = LoadedDocument::new(
+            vec![Token {
                 is_sentence_start: true,
+                ..Token::new("test")
             }],
-            source: "test.pdf".to_string(),
-        };
+            "test.pdf",
+        );
         app.apply_loaded_document(doc);
 
         let initial_wpm = app.reading_state.as_ref().unwrap().wpm;
@@ -434,14 +1110,13 @@ mod tests {
     #[test]
     fn test_keypress_bracket_decrease_wpm() {
         let mut app = App::new();
-        let doc = LoadedDocument {
-            tokens: vec![Token {
-                text: "test".to_string(),
-                punctuation: vec![],
+        let doc = LoadedDocument::new(
+            vec![Token {
                 is_sentence_start: true,
+                ..Token::new("test")
             }],
-            source: "test.pdf".to_string(),
-        };
+            "test.pdf",
+        );
         app.apply_loaded_document(doc);
 
         let initial_wpm = app.reading_state.as_ref().unwrap().wpm;
@@ -455,14 +1130,13 @@ mod tests {
     #[test]
     fn test_keypress_space_toggle_pause() {
         let mut app = App::new();
-        let doc = LoadedDocument {
-            tokens: vec![Token {
-                text: "test".to_string(),
-                punctuation: vec![],
+        let doc = LoadedDocument::new(
+            vec![Token {
                 is_sentence_start: true,
+                ..Token::new("test")
             }],
-            source: "test.pdf".to_string(),
-        };
+            "test.pdf",
+        );
         app.apply_loaded_document(doc);
 
         // Initially Reading
@@ -482,14 +1156,13 @@ mod tests {
     #[test]
     fn test_keypress_q_quit_to_repl() {
         let mut app = App::new();
-        let doc = LoadedDocument {
-            tokens: vec![Token {
-                text: "test".to_string(),
-                punctuation: vec![],
+        let doc = LoadedDocument::new(
+            vec![Token {
                 is_sentence_start: true,
+                ..Token::new("test")
             }],
-            source: "test.pdf".to_string(),
-        };
+            "test.pdf",
+        );
         app.apply_loaded_document(doc);
 
         // Initially Reading
@@ -549,4 +1222,345 @@ mod tests {
         let advanced = app.advance_reading();
         assert!(!advanced);
     }
+
+    #[test]
+    fn test_keypress_f_flashes_note_and_pauses() {
+        let mut app = App::new();
+        let doc = LoadedDocument::new(
+            vec![
+                Token {
+                    is_sentence_start: true,
+                    note: Some("See appendix.".to_string()),
+                    ..Token::new("claim")
+                },
+                Token {
+                    punctuation: vec!['.'],
+                    ..Token::new("stands")
+                },
+            ],
+            "test.epub",
+        );
+        app.apply_loaded_document(doc);
+        app.advance_reading();
+
+        // Reading has moved past the referencing word; the note is still found
+        assert!(app.handle_keypress('f'));
+        assert_eq!(app.mode, AppMode::Paused);
+        assert_eq!(
+            app.get_render_state().note,
+            Some("See appendix.".to_string())
+        );
+
+        // Any key dismisses the note; space resumes reading
+        assert!(app.handle_keypress(' '));
+        assert_eq!(app.mode, AppMode::Reading);
+        assert!(app.get_render_state().note.is_none());
+    }
+
+    #[test]
+    fn test_keypress_f_without_note() {
+        let mut app = App::new();
+        app.start_reading("First. Second sentence", 300);
+        assert!(!app.handle_keypress('f'));
+        assert_eq!(app.mode, AppMode::Reading);
+        assert!(app.active_note.is_none());
+    }
+
+    #[test]
+    fn test_keypress_t_toggles_cue_timing() {
+        let mut app = App::new();
+        app.start_reading("Hello there", 300);
+        assert!(!app.handle_keypress('t'));
+        assert_eq!(app.current_delay_ms(), 200);
+
+        app.cues = vec![Cue {
+            start_ms: 0,
+            end_ms: 1000,
+            tokens: 0..2,
+        }];
+        assert!(app.handle_keypress('t'));
+        assert!(app.follow_cue_timing);
+        assert_eq!(app.current_delay_ms(), 500);
+
+        assert!(app.handle_keypress('t'));
+        assert_eq!(app.current_delay_ms(), 200);
+    }
+
+    #[test]
+    fn test_loader_notice_shown_until_next_key() {
+        let mut app = App::new();
+        let doc = LoadedDocument {
+            notices: vec!["Removed Project Gutenberg licence".to_string()],
+            ..LoadedDocument::new(tokenize_text("Alice was beginning"), "text:alice.txt")
+        };
+        app.apply_loaded_document(doc);
+        assert_eq!(
+            app.get_render_state().notice.map(|n| n.message.as_str()),
+            Some("Removed Project Gutenberg licence")
+        );
+
+        app.handle_keypress(' ');
+        assert!(app.get_render_state().notice.is_none());
+        assert_eq!(app.notifications.history().len(), 2);
+    }
+
+    #[test]
+    fn test_errors_are_notified_and_listed_by_messages() {
+        let mut app = loading_app();
+        app.apply_load_update(LoadUpdate::Done(Err(LoadError::UnsupportedFormat(
+            "xyz".to_string(),
+        ))));
+        let notice = app.get_render_state().notice.unwrap();
+        assert_eq!(notice.severity, Severity::Error);
+        assert!(notice
+            .message
+            .starts_with("Could not open tests/test_files/analysis.org: Unsupported format: xyz."));
+        assert!(notice.message.contains("Supported formats: .pdf"));
+
+        app.handle_event(AppEvent::InvalidCommand(":bogus".to_string()));
+        assert!(app
+            .get_render_state()
+            .notice
+            .unwrap()
+            .message
+            .starts_with("Unknown command: :bogus"));
+
+        assert!(app.get_render_state().messages.is_none());
+        app.handle_event(AppEvent::ShowMessages);
+        assert_eq!(app.get_render_state().messages.unwrap().len(), 2);
+        assert!(app.close_messages());
+        assert!(!app.close_messages());
+        assert!(app.get_render_state().messages.is_none());
+    }
+
+    #[test]
+    fn test_directory_opens_reading_queue() {
+        let dir = std::env::temp_dir().join(format!("speedy-app-queue-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("ch1.txt"), "First chapter.").unwrap();
+        std::fs::write(dir.join("ch2.txt"), "Second chapter.").unwrap();
+
+        let mut app = App::new();
+        app.open_file(dir.to_str().unwrap()).unwrap();
+        let render_state = app.get_render_state();
+        assert_eq!(render_state.queue_position, Some((1, 2)));
+        assert_eq!(render_state.title_card.as_deref(), Some("ch1.txt"));
+        assert_eq!(app.current_delay_ms(), TITLE_CARD_MS);
+
+        // The title card gives way to the first word, then reading continues
+        // into the next document when this one ends
+        assert!(app.advance_reading());
+        assert!(app.title_card.is_none());
+        assert_eq!(app.get_render_state().current_word, Some("First"));
+        assert!(app.advance_reading());
+        assert!(app.advance_reading());
+        assert_eq!(app.get_render_state().queue_position, Some((2, 2)));
+        // The next document loads on the worker; the last word stays until then
+        assert!(app.load_job.is_some());
+        assert!(!app.advance_reading());
+        finish_loading(&mut app);
+        assert_eq!(app.get_render_state().queue_position, Some((2, 2)));
+        assert_eq!(app.title_card.as_deref(), Some("ch2.txt"));
+
+        // n at the last document stays put; N goes back
+        assert!(!app.handle_keypress('n'));
+        assert!(app.handle_keypress('N'));
+        finish_loading(&mut app);
+        assert_eq!(app.get_render_state().queue_position, Some((1, 2)));
+
+        // Opening a single file leaves the queue
+        app.open_file(dir.join("ch2.txt").to_str().unwrap())
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(app.queue.is_none());
+        assert!(app.title_card.is_none());
+    }
+
+    #[test]
+    fn test_cli_range_applies_to_initial_file_only() {
+        let dir = std::env::temp_dir().join(format!("speedy-app-range-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let book = crate::input::epub::test_support::build_epub(&[
+            ("one.xhtml", "<p>Chapter one.</p>"),
+            ("two.xhtml", "<p>Chapter two.</p>"),
+        ]);
+        std::fs::write(dir.join("a.epub"), &book).unwrap();
+        std::fs::write(dir.join("b.epub"), &book).unwrap();
+
+        let mut app = App::new();
+        app.open_initial_file(
+            dir.join("a.epub").to_str().unwrap(),
+            LoadRange::parse("ch2"),
+        )
+        .unwrap();
+        let tokens = &app.reading_state.as_ref().unwrap().tokens;
+        let words: Vec<&str> = tokens.iter().map(|t| t.text).collect();
+        assert_eq!(words, ["Chapter", "two"]);
+
+        // A later @file reads the whole book
+        app.handle_load_file(dir.join("b.epub").to_str().unwrap());
+        finish_loading(&mut app);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(app.load_options.range.is_none());
+        let tokens = &app.reading_state.as_ref().unwrap().tokens;
+        let words: Vec<&str> = tokens
+            .iter()
+            .map(|t| t.text)
+            .filter(|w| !w.is_empty())
+            .collect();
+        assert_eq!(words, ["Chapter", "one", "Chapter", "two"]);
+    }
+
+    #[test]
+    fn test_queue_keeps_reading_speed() {
+        let dir = std::env::temp_dir().join(format!("speedy-app-wpm-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.md"), "One.").unwrap();
+        std::fs::write(dir.join("b.md"), "Two.").unwrap();
+
+        let mut app = App::new();
+        app.open_file(&format!("{}/*.md", dir.display())).unwrap();
+        app.handle_keypress(']');
+        assert!(app.handle_keypress('n'));
+        finish_loading(&mut app);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(app.get_wpm(), 350);
+    }
+
+    #[test]
+    fn test_queue_skips_unreadable_documents() {
+        let dir = std::env::temp_dir().join(format!("speedy-app-skip-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.md"), "One.").unwrap();
+        std::fs::write(dir.join("b.pdf"), "not a pdf").unwrap();
+        std::fs::write(dir.join("c.md"), "Three.").unwrap();
+
+        let mut app = App::new();
+        app.start_loading(dir.to_str().unwrap()).unwrap();
+        finish_loading(&mut app);
+        assert_eq!(app.title_card.as_deref(), Some("a.md"));
+
+        // b.pdf fails and is reported; c.md is read instead
+        assert!(app.skip_document(1));
+        finish_loading(&mut app);
+        assert_eq!(app.get_render_state().queue_position, Some((3, 3)));
+        assert_eq!(app.title_card.as_deref(), Some("c.md"));
+        assert!(app
+            .notifications
+            .history()
+            .any(|n| n.severity == Severity::Error && n.message.contains("b.pdf")));
+
+        // Cancelling a skip before the document arrives keeps the position
+        assert!(app.skip_document(-1));
+        assert!(app.cancel_loading());
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(app.get_render_state().queue_position, Some((3, 3)));
+    }
+
+    fn finish_loading(app: &mut App) {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while app.load_job.is_some() && std::time::Instant::now() < deadline {
+            if !app.poll_loading() {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+            }
+        }
+    }
+
+    fn passage(text: &str) -> LoadedDocument {
+        LoadedDocument::new(tokenize_text(text), "clipboard")
+    }
+
+    #[test]
+    fn test_copied_passage_waits_for_current_one() {
+        let mut app = App::new();
+        app.receive_passage(passage("First copied words."));
+        assert_eq!(app.mode, AppMode::Reading);
+        app.handle_keypress(']');
+
+        // Arrives mid-read: read after the last word, at the same speed
+        app.receive_passage(passage("Second passage."));
+        assert_eq!(app.pending_passages.len(), 1);
+        assert!(app.advance_reading());
+        assert!(app.advance_reading());
+        assert!(app.advance_reading());
+        assert_eq!(app.get_render_state().current_word, Some("Second"));
+        assert!(app.pending_passages.is_empty());
+        assert_eq!(app.get_wpm(), 350);
+
+        // Arrives after the last word: starts at once
+        assert!(app.advance_reading());
+        assert!(!app.advance_reading());
+        app.receive_passage(passage("Third."));
+        assert_eq!(app.get_render_state().current_word, Some("Third"));
+    }
+
+    fn loading_app() -> App {
+        let mut app = App::new();
+        let path = "tests/test_files/analysis.org";
+        app.load_job = Some(LoadJob::spawn(path, path, LoadOptions::default()));
+        app
+    }
+
+    #[test]
+    fn test_partial_document_is_read_while_loading() {
+        let mut app = loading_app();
+        app.apply_load_update(LoadUpdate::Partial(passage("One two three.")));
+        assert_eq!(app.mode, AppMode::Reading);
+        app.advance_reading();
+        app.advance_reading();
+
+        app.apply_load_update(LoadUpdate::Partial(passage("One two three. Four five.")));
+        app.apply_load_update(LoadUpdate::Done(Ok(passage(
+            "One two three. Four five. Six.",
+        ))));
+        let state = app.reading_state.as_ref().unwrap();
+        assert_eq!(state.current_index, 2);
+        assert_eq!(state.tokens.len(), 6);
+        assert!(app.load_job.is_none());
+        assert!(!app.reading_partial);
+    }
+
+    #[test]
+    fn test_cancel_loading_keeps_partial_document() {
+        let mut app = loading_app();
+        app.apply_load_update(LoadUpdate::Progress(LoadProgress {
+            done: 8,
+            total: 40,
+            unit: ProgressUnit::Pages,
+        }));
+        assert_eq!(
+            app.get_render_state().loading,
+            Some(Some(LoadProgress {
+                done: 8,
+                total: 40,
+                unit: ProgressUnit::Pages
+            }))
+        );
+        app.apply_load_update(LoadUpdate::Partial(passage("One two three.")));
+
+        assert!(app.cancel_loading());
+        assert!(app.load_job.is_none());
+        assert_eq!(app.get_render_state().loading, None);
+        let notice = app.notifications.current().unwrap();
+        assert_eq!(notice.severity, Severity::Warning);
+        assert!(notice.message.ends_with("reading the first 8 of 40 pages"));
+        assert_eq!(app.reading_state.as_ref().unwrap().tokens.len(), 3);
+        assert!(!app.cancel_loading());
+    }
+
+    #[test]
+    fn test_start_loading_reads_file_in_background() {
+        let mut app = App::new();
+        app.start_loading("tests/test_files/analysis.org").unwrap();
+        assert!(app.load_job.is_some());
+
+        finish_loading(&mut app);
+        assert_eq!(app.mode, AppMode::Reading);
+        assert!(app
+            .notifications
+            .current()
+            .unwrap()
+            .message
+            .starts_with("Loaded: "));
+    }
 }
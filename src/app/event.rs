@@ -1,10 +1,17 @@
+use crate::input::clipboard::Selection;
+
 /// Application events
 #[derive(Debug, PartialEq, Clone)]
 pub enum AppEvent {
     LoadFile(String),
     LoadClipboard,
+    LoadPrimarySelection,
+    /// Start (or stop) reading each passage copied to a selection
+    WatchClipboard(Selection),
     Quit,
     Help,
+    /// Show recent notifications (`:messages`)
+    ShowMessages,
     Warning(String),
     InvalidCommand(String),
     None,
@@ -3,6 +3,8 @@ pub mod app;
 mod app_tests;
 pub mod event;
 pub mod mode;
+pub mod notify;
+pub mod queue;
 pub mod render_state;
 
 pub use app::App;
@@ -0,0 +1,158 @@
+//! Notifications shown in the command deck
+//!
+//! Loader notices, warnings and errors are posted here instead of being
+//! printed to the alternate screen. The most serious one still within its
+//! lifetime is shown in the command deck, the newest among equals, so an
+//! error is not hidden by a later info. A key dismisses everything posted so
+//! far; all of them stay in a short history listed by `:messages`.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Notifications kept for `:messages`; older ones are dropped.
+pub const HISTORY_LIMIT: usize = 100;
+
+/// How serious a notification is, which decides how long it is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+
+    /// Time the notification stays in the command deck.
+    pub fn lifetime(self) -> Duration {
+        match self {
+            Severity::Info => Duration::from_secs(5),
+            Severity::Warning => Duration::from_secs(10),
+            Severity::Error => Duration::from_secs(15),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub severity: Severity,
+    pub message: String,
+    pub posted: Instant,
+}
+
+impl Notification {
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now.duration_since(self.posted) >= self.severity.lifetime()
+    }
+}
+
+/// Recent notifications, newest last.
+#[derive(Debug, Default)]
+pub struct Notifications {
+    history: VecDeque<Notification>,
+    /// Index in `history` of the first notification not dismissed by a key.
+    visible_from: usize,
+}
+
+impl Notifications {
+    pub fn push(&mut self, severity: Severity, message: impl Into<String>) {
+        if self.history.len() == HISTORY_LIMIT {
+            self.history.pop_front();
+            self.visible_from = self.visible_from.saturating_sub(1);
+        }
+        self.history.push_back(Notification {
+            severity,
+            message: message.into(),
+            posted: Instant::now(),
+        });
+    }
+
+    /// Hides every notification posted so far; they stay in the history.
+    pub fn dismiss(&mut self) {
+        self.visible_from = self.history.len();
+    }
+
+    /// The notification to show in the command deck at `now`: the most
+    /// severe one not expired or dismissed, the newest among equals.
+    pub fn current_at(&self, now: Instant) -> Option<&Notification> {
+        self.history
+            .range(self.visible_from..)
+            .filter(|n| !n.is_expired(now))
+            .max_by_key(|n| n.severity)
+    }
+
+    pub fn current(&self) -> Option<&Notification> {
+        self.current_at(Instant::now())
+    }
+
+    /// All kept notifications, oldest first.
+    pub fn history(&self) -> impl DoubleEndedIterator<Item = &Notification> + ExactSizeIterator {
+        self.history.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newest_notification_shows_until_expiry_or_dismissal() {
+        let mut notifications = Notifications::default();
+        assert!(notifications.current().is_none());
+
+        notifications.push(Severity::Info, "Loaded");
+        notifications.push(Severity::Error, "Could not open a.pdf");
+        let shown = notifications.current().unwrap();
+        assert_eq!(shown.message, "Could not open a.pdf");
+
+        let posted = shown.posted;
+        assert!(notifications
+            .current_at(posted + Duration::from_secs(14))
+            .is_some());
+        assert!(notifications
+            .current_at(posted + Duration::from_secs(15))
+            .is_none());
+
+        notifications.dismiss();
+        assert!(notifications.current().is_none());
+        assert_eq!(notifications.history().len(), 2);
+    }
+
+    #[test]
+    fn test_errors_stay_shown_over_later_notices() {
+        let mut notifications = Notifications::default();
+        notifications.push(Severity::Warning, "Skipped b.pdf");
+        notifications.push(Severity::Error, "Could not open c.pdf");
+        notifications.push(Severity::Info, "Loaded d.md");
+        let posted = notifications.history().last().unwrap().posted;
+
+        let shown = |at| notifications.current_at(posted + Duration::from_secs(at));
+        assert_eq!(shown(0).unwrap().message, "Could not open c.pdf");
+        assert_eq!(shown(14).unwrap().severity, Severity::Error);
+        assert!(shown(15).is_none());
+
+        notifications.push(Severity::Warning, "Second warning");
+        notifications.push(Severity::Warning, "Third warning");
+        assert_eq!(notifications.current().unwrap().severity, Severity::Error);
+        notifications.dismiss();
+        assert!(notifications.current().is_none());
+        notifications.push(Severity::Info, "Loaded e.md");
+        assert_eq!(notifications.current().unwrap().message, "Loaded e.md");
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut notifications = Notifications::default();
+        for i in 0..HISTORY_LIMIT + 5 {
+            notifications.push(Severity::Info, format!("message {}", i));
+        }
+        assert_eq!(notifications.history().len(), HISTORY_LIMIT);
+        assert_eq!(notifications.history().next().unwrap().message, "message 5");
+    }
+}
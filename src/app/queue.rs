@@ -0,0 +1,63 @@
+//! Reading queue for `@dir/` and `@*.md` loads
+//!
+//! Holds the ordered documents and which one is being read. App loads the
+//! current item and moves through the queue with `n`/`N` or when a document
+//! ends.
+
+use std::path::{Path, PathBuf};
+
+/// How long a document's title is flashed before its first word, in ms.
+pub const TITLE_CARD_MS: u64 = 1500;
+
+/// Documents opened together, read one after another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadingQueue {
+    items: Vec<PathBuf>,
+    position: usize,
+}
+
+impl ReadingQueue {
+    /// Creates a queue positioned at the first item; `items` must not be empty.
+    pub fn new(items: Vec<PathBuf>) -> Self {
+        debug_assert!(!items.is_empty());
+        Self { items, position: 0 }
+    }
+
+    pub fn current(&self) -> &Path {
+        &self.items[self.position]
+    }
+
+    /// 1-based position and queue length, for the `[2/5]` indicator.
+    pub fn position(&self) -> (usize, usize) {
+        (self.position + 1, self.items.len())
+    }
+
+    /// Moves `step` items forward (or back if negative).
+    ///
+    /// Returns `false` without moving if that would leave the queue.
+    pub fn step(&mut self, step: isize) -> bool {
+        match self.position.checked_add_signed(step) {
+            Some(position) if position < self.items.len() => {
+                self.position = position;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_steps_within_bounds() {
+        let mut queue = ReadingQueue::new(vec!["a.md".into(), "b.md".into()]);
+        assert_eq!(queue.position(), (1, 2));
+        assert!(!queue.step(-1));
+        assert!(queue.step(1));
+        assert_eq!(queue.current(), Path::new("b.md"));
+        assert!(!queue.step(1));
+        assert_eq!(queue.position(), (2, 2));
+    }
+}
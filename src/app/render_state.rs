@@ -1,39 +1,77 @@
 use crate::app::mode::AppMode;
-use crate::engine::Token;
+use crate::app::notify::Notification;
+use crate::input::background::LoadProgress;
+use crate::reading::TokenStore;
+
+/// Words shown when no document is loaded.
+static NO_TOKENS: TokenStore = TokenStore::new();
 
 /// Render state for UI components
-pub struct RenderState {
+///
+/// Built for every frame, so it borrows the document's words from the
+/// reading state instead of copying them.
+pub struct RenderState<'a> {
     pub mode: AppMode,
-    pub current_word: Option<String>,
-    pub tokens: Vec<Token>,
+    pub current_word: Option<&'a str>,
+    /// The current word is split and continues after `current_word`.
+    pub hyphen: bool,
+    /// Words shown together in chunk mode, the current one first; empty
+    /// when one word is shown.
+    pub chunk: Vec<&'a str>,
+    pub tokens: &'a TokenStore,
     pub current_index: usize,
-    pub context_left: Vec<String>,
-    pub context_right: Vec<String>,
+    pub context_left: Vec<&'a str>,
+    pub context_right: Vec<&'a str>,
     pub progress: (usize, usize),
+    /// Footnote text to flash over the reading zone
+    pub note: Option<String>,
+    /// Notification shown in the command deck
+    pub notice: Option<&'a Notification>,
+    /// Recent notifications, oldest first, while `:messages` is open
+    pub messages: Option<Vec<&'a Notification>>,
+    /// Document title flashed between queued documents
+    pub title_card: Option<String>,
+    /// 1-based position and length of the reading queue
+    pub queue_position: Option<(usize, usize)>,
+    /// Current section (0 before the first heading) and number of sections
+    pub section: Option<(usize, usize)>,
+    /// Passages waiting while `@@watch` is on; `None` when not watching
+    pub clipboard_watch: Option<usize>,
+    /// Progress of a background load; `Some(None)` before the loader reports
+    pub loading: Option<Option<LoadProgress>>,
 }
 
-impl RenderState {
+impl<'a> RenderState<'a> {
     /// Create an empty render state for when no document is loaded
     pub fn empty(mode: AppMode) -> Self {
         Self {
             mode,
             current_word: None,
-            tokens: vec![],
+            hyphen: false,
+            chunk: vec![],
+            tokens: &NO_TOKENS,
             current_index: 0,
             context_left: vec![],
             context_right: vec![],
             progress: (0, 0),
+            note: None,
+            notice: None,
+            messages: None,
+            title_card: None,
+            queue_position: None,
+            section: None,
+            clipboard_watch: None,
+            loading: None,
         }
     }
 
     /// Create render state from reading state data
     pub fn from_reading_state(
         mode: AppMode,
-        tokens: Vec<Token>,
+        tokens: &'a TokenStore,
         current_index: usize,
         context_window: usize,
     ) -> Self {
-        // Calculate total before moving tokens
         let total = tokens.len();
 
         // Get context words before current
@@ -42,26 +80,33 @@ impl RenderState {
         } else {
             0
         };
-        let context_left: Vec<String> = tokens[start..current_index]
-            .iter()
-            .map(|t| t.text.clone())
-            .collect();
+        let context_left: Vec<&str> = tokens.range(start..current_index).map(|t| t.text).collect();
 
         // Get context words after current
         let end = std::cmp::min(current_index + context_window + 1, tokens.len());
-        let context_right: Vec<String> = tokens[current_index + 1..end]
-            .iter()
-            .map(|t| t.text.clone())
+        let context_right: Vec<&str> = tokens
+            .range(current_index + 1..end)
+            .map(|t| t.text)
             .collect();
 
         Self {
             mode: mode.clone(),
-            current_word: tokens.get(current_index).map(|t| t.text.clone()),
+            current_word: tokens.get(current_index).map(|t| t.text),
+            hyphen: false,
+            chunk: vec![],
             tokens,
             current_index,
             context_left,
             context_right,
             progress: (current_index, total),
+            note: None,
+            notice: None,
+            messages: None,
+            title_card: None,
+            queue_position: None,
+            section: None,
+            clipboard_watch: None,
+            loading: None,
         }
     }
 }
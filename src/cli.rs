@@ -0,0 +1,414 @@
+//! Command-line argument parsing
+//!
+//! Supports opening a document straight into Reading mode:
+//! - `speedy book.epub` → load a file (with optional `#p12-30` / `#ch3` range)
+//! - `cat notes.txt | speedy -` → read piped text from stdin
+//! - `speedy --wpm 450 --start 35% file.pdf` → initial speed and position
+//!
+//! Options accept their value either as the next argument or after `=`
+//! (`--wpm=450`). A lone `--` ends option parsing.
+
+use crate::engine::config::TimingConfig;
+use crate::input::{LoadRange, RangeUnit};
+use crate::reading::pacing::PacingModel;
+use encoding_rs::Encoding;
+use thiserror::Error;
+
+/// Help text printed for `-h` / `--help`.
+pub const USAGE: &str = "\
+Usage: speedy [OPTIONS] [FILE | -]
+
+Arguments:
+  FILE              Document to open (.pdf, .epub, .mobi, .azw3, .fb2,
+                    .html, .docx, .odt, .ipynb, .rst, .org, .eml,
+                    .mbox, .srt, .vtt, .txt, .md, or source code such
+                    as .rs or .py to read its comments), optionally
+                    compressed (.gz, .bz2, .zst);
+                    append #p12-30 or #ch3 to load a page or chapter range;
+                    a directory or quoted pattern ('notes/*.md') opens
+                    its documents as a reading queue (n/N skip)
+  -                 Read text from standard input
+
+Options:
+  --wpm <N>         Reading speed in words per minute (50-1000)
+  --start <POS>     Start position: percentage (35%) or word number (1200)
+  --pages <RANGE>   Page range for PDF files (12-30, 5, 100-)
+  --chapters <RANGE>
+                    Chapter range for EPUB and MOBI files (3, 3-5)
+  --follow-timing   Pace subtitles by their original cue timing (t toggles)
+  --encoding <NAME> Character encoding of text input (latin1, windows-1252,
+                    shift_jis, utf-16le, ...); detected when omitted
+  --keep-boilerplate
+                    Keep Project Gutenberg licence text and front matter
+  --hyphenate       Split long words into hyphenated fragments
+  --chunk           Show short words together in chunks of two or three
+  --pacing <MODEL>  Word pacing: length (default), frequency (rare, long and
+                    numeric words longer at the same WPM), syllable or
+                    constant
+  --force-kitty     Force Kitty graphics rendering
+  --force-tui       Force TUI fallback rendering
+  -h, --help        Print this help";
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CliError {
+    #[error("Unknown option: {0}")]
+    UnknownOption(String),
+
+    #[error("{0} requires a value")]
+    MissingValue(String),
+
+    #[error("Invalid value for {flag}: {value}")]
+    InvalidValue { flag: String, value: String },
+
+    #[error("{0} and {1} are mutually exclusive")]
+    Conflict(&'static str, &'static str),
+
+    #[error("{0} given more than once")]
+    Duplicate(String),
+
+    #[error("Unexpected argument: {0}")]
+    UnexpectedArgument(String),
+}
+
+/// Where the initial document comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    /// Piped text on standard input (`-`)
+    Stdin,
+    /// A file path, possibly with a range fragment
+    File(String),
+}
+
+/// Initial reading position given with `--start`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StartPosition {
+    /// Percentage of the document (`35%`)
+    Percent(f64),
+    /// 1-based word number (`1200`)
+    Word(usize),
+}
+
+/// Parsed command-line arguments.
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    pub input: Option<InputSource>,
+    pub wpm: Option<u32>,
+    pub start: Option<StartPosition>,
+    pub range: Option<LoadRange>,
+    pub follow_timing: bool,
+    /// Character encoding of text input, overriding detection.
+    pub encoding: Option<&'static Encoding>,
+    pub keep_boilerplate: bool,
+    /// Show long words in hyphenated fragments.
+    pub hyphenate: bool,
+    /// Show short words together in chunks.
+    pub chunk: bool,
+    /// How word complexity changes exposure.
+    pub pacing: Option<PacingModel>,
+    pub force_kitty: bool,
+    pub force_tui: bool,
+    pub help: bool,
+}
+
+/// Parses command-line arguments, excluding the program name.
+pub fn parse_args<I, S>(args: I) -> Result<CliArgs, CliError>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut cli = CliArgs::default();
+    let mut args = args.into_iter().map(Into::into);
+    let mut options_done = false;
+
+    while let Some(arg) = args.next() {
+        if options_done || arg == "-" || !arg.starts_with('-') {
+            if cli.input.is_some() {
+                return Err(CliError::UnexpectedArgument(arg));
+            }
+            cli.input = Some(if arg == "-" && !options_done {
+                InputSource::Stdin
+            } else {
+                InputSource::File(arg)
+            });
+            continue;
+        }
+
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError::MissingValue(flag.clone()))
+        };
+
+        match flag.as_str() {
+            "--" => options_done = true,
+            "-h" | "--help" => cli.help = true,
+            "--follow-timing" => cli.follow_timing = true,
+            "--keep-boilerplate" => cli.keep_boilerplate = true,
+            "--hyphenate" => cli.hyphenate = true,
+            "--chunk" => cli.chunk = true,
+            "--force-kitty" => cli.force_kitty = true,
+            "--force-tui" => cli.force_tui = true,
+            "--wpm" => {
+                let value = value()?;
+                cli.wpm = Some(parse_wpm(&value).ok_or_else(|| invalid(&flag, &value))?);
+            }
+            "--start" => {
+                let value = value()?;
+                cli.start = Some(parse_start(&value).ok_or_else(|| invalid(&flag, &value))?);
+            }
+            "--pacing" => {
+                let value = value()?;
+                cli.pacing =
+                    Some(PacingModel::from_name(&value).ok_or_else(|| invalid(&flag, &value))?);
+            }
+            "--encoding" => {
+                let value = value()?;
+                cli.encoding = Some(
+                    Encoding::for_label(value.trim().as_bytes())
+                        .ok_or_else(|| invalid(&flag, &value))?,
+                );
+            }
+            "--pages" | "--chapters" => {
+                let unit = if flag == "--pages" {
+                    RangeUnit::Page
+                } else {
+                    RangeUnit::Chapter
+                };
+                match cli.range {
+                    Some(range) if range.unit == unit => return Err(CliError::Duplicate(flag)),
+                    Some(_) => return Err(CliError::Conflict("--pages", "--chapters")),
+                    None => {}
+                }
+                let value = value()?;
+                cli.range = Some(
+                    LoadRange::parse_bounds(unit, &value).ok_or_else(|| invalid(&flag, &value))?,
+                );
+            }
+            _ => return Err(CliError::UnknownOption(arg)),
+        }
+    }
+
+    if cli.force_kitty && cli.force_tui {
+        return Err(CliError::Conflict("--force-kitty", "--force-tui"));
+    }
+
+    Ok(cli)
+}
+
+fn invalid(flag: &str, value: &str) -> CliError {
+    CliError::InvalidValue {
+        flag: flag.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn parse_wpm(value: &str) -> Option<u32> {
+    let wpm = value.trim().parse().ok()?;
+    TimingConfig::default()
+        .wpm_range
+        .contains(&wpm)
+        .then_some(wpm)
+}
+
+fn parse_start(value: &str) -> Option<StartPosition> {
+    let value = value.trim();
+    match value.strip_suffix('%') {
+        Some(percent) => {
+            let percent: f64 = percent.trim().parse().ok()?;
+            (0.0..=100.0)
+                .contains(&percent)
+                .then_some(StartPosition::Percent(percent))
+        }
+        None => {
+            let word: usize = value.parse().ok()?;
+            (word > 0).then_some(StartPosition::Word(word))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_arguments() {
+        assert_eq!(parse_args(Vec::<String>::new()), Ok(CliArgs::default()));
+    }
+
+    #[test]
+    fn test_positional_file() {
+        let cli = parse_args(["book.epub"]).unwrap();
+        assert_eq!(cli.input, Some(InputSource::File("book.epub".to_string())));
+    }
+
+    #[test]
+    fn test_dash_reads_stdin() {
+        let cli = parse_args(["-"]).unwrap();
+        assert_eq!(cli.input, Some(InputSource::Stdin));
+    }
+
+    #[test]
+    fn test_wpm_start_and_file() {
+        let cli = parse_args(["--wpm", "450", "--start", "35%", "file.pdf"]).unwrap();
+        assert_eq!(cli.wpm, Some(450));
+        assert_eq!(cli.start, Some(StartPosition::Percent(35.0)));
+        assert_eq!(cli.input, Some(InputSource::File("file.pdf".to_string())));
+    }
+
+    #[test]
+    fn test_inline_values() {
+        let cli = parse_args(["--wpm=500", "--start=1200", "--pages=12-30"]).unwrap();
+        assert_eq!(cli.wpm, Some(500));
+        assert_eq!(cli.start, Some(StartPosition::Word(1200)));
+        assert_eq!(cli.range, LoadRange::parse("p12-30"));
+    }
+
+    #[test]
+    fn test_chapters_flag() {
+        let cli = parse_args(["--chapters", "3", "book.epub"]).unwrap();
+        assert_eq!(cli.range, LoadRange::parse("ch3"));
+    }
+
+    #[test]
+    fn test_follow_timing_flag() {
+        let cli = parse_args(["talk.vtt", "--follow-timing"]).unwrap();
+        assert!(cli.follow_timing);
+        assert!(!parse_args(["talk.vtt"]).unwrap().follow_timing);
+    }
+
+    #[test]
+    fn test_keep_boilerplate_flag() {
+        assert!(
+            parse_args(["book.txt", "--keep-boilerplate"])
+                .unwrap()
+                .keep_boilerplate
+        );
+        assert!(!parse_args(["book.txt"]).unwrap().keep_boilerplate);
+    }
+
+    #[test]
+    fn test_hyphenate_flag() {
+        assert!(parse_args(["--hyphenate", "book.epub"]).unwrap().hyphenate);
+        assert!(!parse_args(["book.epub"]).unwrap().hyphenate);
+    }
+
+    #[test]
+    fn test_chunk_flag() {
+        let cli = parse_args(["--chunk", "--hyphenate", "book.epub"]).unwrap();
+        assert!(cli.chunk && cli.hyphenate);
+        assert!(!parse_args(["book.epub"]).unwrap().chunk);
+    }
+
+    #[test]
+    fn test_pacing_option() {
+        let cli = parse_args(["--pacing", "frequency", "book.epub"]).unwrap();
+        assert_eq!(cli.pacing, Some(PacingModel::Frequency));
+        assert_eq!(
+            parse_args(["--pacing=length"]).unwrap().pacing,
+            Some(PacingModel::Length)
+        );
+        assert_eq!(
+            parse_args(["--pacing=constant"]).unwrap().pacing,
+            Some(PacingModel::Constant)
+        );
+        assert!(matches!(
+            parse_args(["--pacing", "fast"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_encoding_option() {
+        let cli = parse_args(["notes.txt", "--encoding", "Latin1"]).unwrap();
+        assert_eq!(cli.encoding, Some(encoding_rs::WINDOWS_1252));
+        let cli = parse_args(["--encoding=shift_jis", "notes.txt"]).unwrap();
+        assert_eq!(cli.encoding, Some(encoding_rs::SHIFT_JIS));
+        assert!(matches!(
+            parse_args(["--encoding", "klingon"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(matches!(
+            parse_args(["--wpm", "fast"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_args(["--wpm", "5000"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_args(["--start", "135%"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_args(["--start", "0"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_args(["--pages", "30-12"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_missing_value() {
+        assert_eq!(
+            parse_args(["--wpm"]),
+            Err(CliError::MissingValue("--wpm".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_conflicts() {
+        assert_eq!(
+            parse_args(["--force-kitty", "--force-tui"]),
+            Err(CliError::Conflict("--force-kitty", "--force-tui"))
+        );
+        assert_eq!(
+            parse_args(["--pages", "1", "--chapters", "2"]),
+            Err(CliError::Conflict("--pages", "--chapters"))
+        );
+        assert_eq!(
+            parse_args(["--chapters", "1", "--pages", "2"]),
+            Err(CliError::Conflict("--pages", "--chapters"))
+        );
+        assert_eq!(
+            parse_args(["--pages", "1", "--pages=2"]),
+            Err(CliError::Duplicate("--pages".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_unknown_option_and_extra_argument() {
+        assert_eq!(
+            parse_args(["--fast"]),
+            Err(CliError::UnknownOption("--fast".to_string()))
+        );
+        assert_eq!(
+            parse_args(["a.txt", "b.txt"]),
+            Err(CliError::UnexpectedArgument("b.txt".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_double_dash_ends_options() {
+        let cli = parse_args(["--", "-notes.txt"]).unwrap();
+        assert_eq!(cli.input, Some(InputSource::File("-notes.txt".to_string())));
+    }
+
+    #[test]
+    fn test_help() {
+        assert!(parse_args(["--help"]).unwrap().help);
+        assert!(parse_args(["-h"]).unwrap().help);
+    }
+}
@@ -1,6 +1,7 @@
 // Configuration for Speedy engine and UI components
 // All values derived from PRD specifications with defaults as documented
 
+use crate::reading::pacing::{FrequencyTable, PacingModel};
 use std::ops::RangeInclusive;
 
 /// Timing configuration per PRD Section 3.2
@@ -24,6 +25,20 @@ pub struct TimingConfig {
     pub question_multiplier: f64,    // default 3.0x
     pub exclamation_multiplier: f64, // default 3.0x
     pub newline_multiplier: f64,     // default 4.0x
+    pub semicolon_multiplier: f64,   // default 2.0x
+    pub colon_multiplier: f64,       // default 2.0x
+    /// Em-dashes and en-dashes
+    pub dash_multiplier: f64, // default 2.0x
+    /// `…` or a run of periods; replaces the period multiplier
+    pub ellipsis_multiplier: f64, // default 3.5x
+    /// Closing quotes and brackets
+    pub closing_quote_multiplier: f64, // default 1.25x
+
+    /// How word complexity changes exposure (default: length penalty only)
+    pub pacing: PacingModel,
+
+    /// Word frequencies used by `PacingModel::Frequency` (default: English)
+    pub frequency_table: FrequencyTable,
 }
 
 impl Default for TimingConfig {
@@ -38,6 +53,13 @@ impl Default for TimingConfig {
             question_multiplier: 3.0,
             exclamation_multiplier: 3.0,
             newline_multiplier: 4.0,
+            semicolon_multiplier: 2.0,
+            colon_multiplier: 2.0,
+            dash_multiplier: 2.0,
+            ellipsis_multiplier: 3.5,
+            closing_quote_multiplier: 1.25,
+            pacing: PacingModel::Length,
+            frequency_table: FrequencyTable::english(),
         }
     }
 }
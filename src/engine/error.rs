@@ -1,38 +1,79 @@
-use std::fmt;
+//! Application errors
+//!
+//! [`SpeedyError`] is the top of the error hierarchy: loader failures are
+//! wrapped with what the user asked to open and keep the [`LoadError`] as
+//! their source, so [`error_chain`] can report the whole story in one line
+//! ("Could not open a.pdf: PDF parse error: ...").
+
+use crate::input::{encoding, LoadError};
+use std::error::Error;
 use std::io;
+use thiserror::Error;
 
+#[derive(Error, Debug)]
 pub enum SpeedyError {
-    IoError(io::Error),
+    #[error("I/O error")]
+    IoError(#[from] io::Error),
+
+    #[error("File is empty: {0}")]
     EmptyFile(String),
+
+    #[error("Invalid file encoding: {0}")]
     InvalidEncoding(String),
+
+    /// `spec` is what the user asked to open: a path, pattern or `@@`.
+    #[error("Could not open {spec}")]
+    Load {
+        spec: String,
+        #[source]
+        source: LoadError,
+    },
+
+    #[error("Unknown command: {0}")]
+    UnknownCommand(String),
 }
 
-impl fmt::Display for SpeedyError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            SpeedyError::IoError(err) => write!(f, "I/O error: {}", err),
-            SpeedyError::EmptyFile(path) => write!(f, "File is empty: {}", path),
-            SpeedyError::InvalidEncoding(path) => write!(f, "Invalid file encoding: {}", path),
+impl SpeedyError {
+    pub fn load(spec: &str, source: LoadError) -> Self {
+        SpeedyError::Load {
+            spec: spec.to_string(),
+            source,
         }
     }
-}
 
-impl fmt::Debug for SpeedyError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self)
+    /// Advice for fixing the error, shown after the message.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            SpeedyError::Load { source, .. } => source.hint(),
+            SpeedyError::UnknownCommand(_) => {
+                Some("Commands are @file, @@, @@primary, @@watch, :messages and :q")
+            }
+            _ => None,
+        }
     }
 }
 
-impl std::error::Error for SpeedyError {}
-
-impl From<io::Error> for SpeedyError {
-    fn from(err: io::Error) -> Self {
-        SpeedyError::IoError(err)
+/// Joins an error and its sources into one line: `outer: inner: innermost`.
+pub fn error_chain(error: &dyn Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(inner) = source {
+        message.push_str(": ");
+        message.push_str(&inner.to_string());
+        source = inner.source();
     }
+    message
 }
 
+/// Reads a text file in any common encoding, decoding it to UTF-8.
+///
+/// Fails with `InvalidEncoding` only if no encoding fits the bytes, which in
+/// practice means the file is binary.
 pub fn load_file_safe(path: &str) -> Result<String, SpeedyError> {
-    let content = std::fs::read_to_string(path)?;
+    let bytes = std::fs::read(path)?;
+    let content = encoding::decode(&bytes, None)
+        .ok_or_else(|| SpeedyError::InvalidEncoding(path.to_string()))?
+        .text;
 
     if content.trim().is_empty() {
         return Err(SpeedyError::EmptyFile(path.to_string()));
@@ -47,6 +88,19 @@ mod tests {
     use std::fs::{self, File};
     use std::io::Write;
 
+    #[test]
+    fn test_error_chain_includes_sources() {
+        let bad_header = io::Error::new(io::ErrorKind::InvalidData, "gzip: invalid header");
+        let error = SpeedyError::load("a.txt.gz", LoadError::Decompress(bad_header));
+        assert_eq!(
+            error_chain(&error),
+            "Could not open a.txt.gz: Decompression error: gzip: invalid header"
+        );
+
+        let error = SpeedyError::from(io::Error::new(io::ErrorKind::NotFound, "gone"));
+        assert_eq!(error_chain(&error), "I/O error: gone");
+    }
+
     #[test]
     fn test_empty_file_error() {
         let test_file = "test_empty.txt";
@@ -84,4 +138,35 @@ mod tests {
 
         fs::remove_file(test_file).unwrap();
     }
+
+    #[test]
+    fn test_legacy_encoding_loads() {
+        let test_file = "test_latin1.txt";
+        fs::write(
+            test_file,
+            b"Fa\xe7ade, na\xefve r\xe9sum\xe9 of the caf\xe9 men\xfa",
+        )
+        .unwrap();
+
+        let result = load_file_safe(test_file);
+        fs::remove_file(test_file).unwrap();
+        assert_eq!(result.unwrap(), "Façade, naïve résumé of the café menú");
+    }
+
+    #[test]
+    fn test_binary_file_invalid_encoding() {
+        let test_file = "test_binary.bin";
+        fs::write(
+            test_file,
+            b"\x7fELF\x02\x01\x01\x00\x00\x00\x00\x00\x80\xff",
+        )
+        .unwrap();
+
+        let result = load_file_safe(test_file);
+        fs::remove_file(test_file).unwrap();
+        match result {
+            Err(SpeedyError::InvalidEncoding(_)) => (),
+            _ => panic!("Expected InvalidEncoding error"),
+        }
+    }
 }
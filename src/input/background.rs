@@ -0,0 +1,213 @@
+//! Loading documents on a worker thread
+//!
+//! Extracting a large PDF or EPUB takes seconds, so the TUI hands the load
+//! to a [`LoadJob`] and keeps drawing. Loaders that work page by page or
+//! chapter by chapter take a [`Reporter`]: they report how far they are,
+//! check whether the load was cancelled, and offer partial documents so
+//! reading can start on the first chapters while the rest are extracted.
+
+use super::{dispatch, LoadError, LoadOptions, LoadedDocument};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+/// Pages or chapters extracted before the first partial document is sent.
+/// Later partials follow each time the count doubles, so rebuilding the
+/// document stays linear in its length overall.
+pub const FIRST_PARTIAL: usize = 8;
+
+/// What a loader counts its progress in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressUnit {
+    Pages,
+    Chapters,
+}
+
+impl ProgressUnit {
+    pub fn name(self) -> &'static str {
+        match self {
+            ProgressUnit::Pages => "pages",
+            ProgressUnit::Chapters => "chapters",
+        }
+    }
+}
+
+/// How far a load has got, e.g. 12 of 300 pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadProgress {
+    pub done: usize,
+    pub total: usize,
+    pub unit: ProgressUnit,
+}
+
+/// Messages from the worker thread, in the order they were sent.
+pub enum LoadUpdate {
+    Progress(LoadProgress),
+    /// The document extracted so far; a later partial or the final
+    /// document replaces it.
+    Partial(LoadedDocument),
+    Done(Result<LoadedDocument, LoadError>),
+}
+
+/// Progress and cancellation handle passed to loaders.
+///
+/// [`Reporter::silent`] is used for ordinary synchronous loads: it reports
+/// nowhere and is never cancelled.
+pub struct Reporter {
+    sender: Option<Sender<LoadUpdate>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Reporter {
+    pub fn silent() -> Self {
+        Self {
+            sender: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn progress(&self, done: usize, total: usize, unit: ProgressUnit) {
+        self.send(LoadUpdate::Progress(LoadProgress { done, total, unit }));
+    }
+
+    /// Returns true if a partial document should be built after `done` of
+    /// `total` items: at [`FIRST_PARTIAL`] and each doubling after it, and
+    /// only if anyone is listening.
+    pub fn partial_due(&self, done: usize, total: usize) -> bool {
+        self.sender.is_some()
+            && done < total
+            && done >= FIRST_PARTIAL
+            && (done / FIRST_PARTIAL).is_power_of_two()
+            && done.is_multiple_of(FIRST_PARTIAL)
+    }
+
+    pub fn partial(&self, doc: LoadedDocument) {
+        self.send(LoadUpdate::Partial(doc));
+    }
+
+    /// Fails with [`LoadError::Cancelled`] once the load has been cancelled.
+    pub fn check_cancelled(&self) -> Result<(), LoadError> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            Err(LoadError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn send(&self, update: LoadUpdate) {
+        if let Some(sender) = &self.sender {
+            // The job was dropped: the next cancellation check stops the load
+            if sender.send(update).is_err() {
+                self.cancelled.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// A document loading on a worker thread.
+pub struct LoadJob {
+    spec: String,
+    receiver: Receiver<LoadUpdate>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl LoadJob {
+    /// Starts loading `path` (a file, not a queue) with `options`.
+    ///
+    /// `spec` is what the user asked for, kept for messages.
+    pub fn spawn(spec: &str, path: &str, options: LoadOptions) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let reporter = Reporter {
+            sender: Some(sender.clone()),
+            cancelled: Arc::clone(&cancelled),
+        };
+        let path = path.to_string();
+        thread::spawn(move || {
+            let result = dispatch::load_path_reporting(&path, &options, &reporter);
+            let _ = sender.send(LoadUpdate::Done(result));
+        });
+
+        Self {
+            spec: spec.to_string(),
+            receiver,
+            cancelled,
+        }
+    }
+
+    pub fn spec(&self) -> &str {
+        &self.spec
+    }
+
+    /// Asks the loader to stop at its next page or chapter.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns the next update without waiting. A worker that died without
+    /// finishing is reported as a failed load.
+    pub fn try_next(&self) -> Option<LoadUpdate> {
+        match self.receiver.try_recv() {
+            Ok(update) => Some(update),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(LoadUpdate::Done(Err(LoadError::TextRead(
+                "loader stopped unexpectedly".to_string(),
+            )))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn finish(job: &LoadJob) -> Result<LoadedDocument, LoadError> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            match job.try_next() {
+                Some(LoadUpdate::Done(result)) => return result,
+                Some(_) => {}
+                None => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+        panic!("load did not finish");
+    }
+
+    #[test]
+    fn test_job_loads_on_worker_thread() {
+        let path = "tests/test_files/analysis.org";
+        let job = LoadJob::spawn(path, path, LoadOptions::default());
+        assert_eq!(job.spec(), path);
+        let doc = finish(&job).unwrap();
+        assert!(doc.source.starts_with("org:"));
+    }
+
+    #[test]
+    fn test_job_reports_load_errors() {
+        let job = LoadJob::spawn("missing.pdf", "missing.pdf", LoadOptions::default());
+        assert!(matches!(finish(&job), Err(LoadError::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_partials_double_and_cancel_stops() {
+        let (sender, _receiver) = mpsc::channel();
+        let reporter = Reporter {
+            sender: Some(sender),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        };
+        let due: Vec<usize> = (1..=100)
+            .filter(|&n| reporter.partial_due(n, 100))
+            .collect();
+        assert_eq!(due, [8, 16, 32, 64]);
+        assert!(!Reporter::silent().partial_due(8, 100));
+
+        assert!(reporter.check_cancelled().is_ok());
+        reporter.cancelled.store(true, Ordering::Relaxed);
+        assert!(matches!(
+            reporter.check_cancelled(),
+            Err(LoadError::Cancelled)
+        ));
+    }
+}
@@ -1,5 +1,35 @@
+//! Clipboard and primary selection input
+//!
+//! `@@` reads the clipboard once and `@@primary` reads the X11/Wayland
+//! primary selection, so highlighted text can be read without copying it.
+//! `@@watch` keeps a [`ClipboardWatcher`] that App polls for newly copied
+//! passages.
+
+use super::structure::tokenize_document;
 use super::{LoadError, LoadedDocument};
-use crate::engine::tokenize_text;
+
+/// How often a watched clipboard is checked for new text, in ms.
+pub const WATCH_INTERVAL_MS: u64 = 500;
+
+/// Which system selection to read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Selection {
+    /// The Ctrl+C / Ctrl+V clipboard.
+    #[default]
+    Clipboard,
+    /// The Linux primary selection: whatever text is highlighted.
+    Primary,
+}
+
+impl Selection {
+    /// Source label for loaded documents and messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            Selection::Clipboard => "clipboard",
+            Selection::Primary => "primary selection",
+        }
+    }
+}
 
 /// Load text from system clipboard using arboard crate.
 ///
@@ -8,18 +38,125 @@ use crate::engine::tokenize_text;
 /// PRD Reference: Section 2.2 (Clipboard support), Section 7.1 (@@ command)
 /// Connections: Depends on engine::tokenize_text() for tokenization.
 pub fn load() -> Result<LoadedDocument, LoadError> {
-    arboard::Clipboard::new()
-        .and_then(|mut clipboard| clipboard.get_text())
-        .map_err(|e| LoadError::Clipboard(e.to_string()))
-        .map(|text| LoadedDocument {
-            tokens: tokenize_text(&text),
-            source: "clipboard".to_string(),
+    load_selection(Selection::Clipboard)
+}
+
+/// Loads the clipboard or primary selection once.
+pub fn load_selection(selection: Selection) -> Result<LoadedDocument, LoadError> {
+    let mut clipboard = open()?;
+    read_text(&mut clipboard, selection)
+        .map_err(LoadError::from)
+        .and_then(|text| document(&text, selection))
+}
+
+/// Polls a selection for newly copied text (`@@watch`).
+///
+/// Text already in the selection when watching starts is not read; each
+/// later change is, once. Empty and whitespace-only selections are ignored.
+pub struct ClipboardWatcher {
+    clipboard: arboard::Clipboard,
+    selection: Selection,
+    changes: ChangeDetector,
+}
+
+impl ClipboardWatcher {
+    pub fn new(selection: Selection) -> Result<Self, LoadError> {
+        let mut clipboard = open()?;
+        let mut changes = ChangeDetector::default();
+        if let Ok(text) = read_text(&mut clipboard, selection) {
+            changes.is_new(&text);
+        }
+        Ok(Self {
+            clipboard,
+            selection,
+            changes,
         })
+    }
+
+    pub fn selection(&self) -> Selection {
+        self.selection
+    }
+
+    /// Returns the newly copied passage, if the selection changed.
+    ///
+    /// Read failures (no text on the clipboard, the clipboard briefly held
+    /// by another program) count as no change.
+    pub fn poll(&mut self) -> Option<LoadedDocument> {
+        let text = read_text(&mut self.clipboard, self.selection).ok()?;
+        if !self.changes.is_new(&text) {
+            return None;
+        }
+        document(&text, self.selection).ok()
+    }
+}
+
+/// Remembers the last passage seen so each copy is read once.
+#[derive(Debug, Default)]
+struct ChangeDetector {
+    last: Option<String>,
+}
+
+impl ChangeDetector {
+    /// Returns true for text that differs from the last passage seen.
+    ///
+    /// Surrounding whitespace is ignored, since some applications add a
+    /// trailing newline when the same text is copied again.
+    fn is_new(&mut self, text: &str) -> bool {
+        let text = text.trim();
+        if text.is_empty() || self.last.as_deref() == Some(text) {
+            return false;
+        }
+        self.last = Some(text.to_string());
+        true
+    }
+}
+
+fn open() -> Result<arboard::Clipboard, LoadError> {
+    arboard::Clipboard::new().map_err(LoadError::from)
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+))]
+fn read_text(
+    clipboard: &mut arboard::Clipboard,
+    selection: Selection,
+) -> Result<String, arboard::Error> {
+    use arboard::{GetExtLinux, LinuxClipboardKind};
+
+    let kind = match selection {
+        Selection::Clipboard => LinuxClipboardKind::Clipboard,
+        Selection::Primary => LinuxClipboardKind::Primary,
+    };
+    clipboard.get().clipboard(kind).text()
+}
+
+#[cfg(not(all(
+    unix,
+    not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+)))]
+fn read_text(
+    clipboard: &mut arboard::Clipboard,
+    selection: Selection,
+) -> Result<String, arboard::Error> {
+    match selection {
+        Selection::Clipboard => clipboard.get_text(),
+        Selection::Primary => Err(arboard::Error::ClipboardNotSupported),
+    }
+}
+
+fn document(text: &str, selection: Selection) -> Result<LoadedDocument, LoadError> {
+    Ok(LoadedDocument::new(
+        tokenize_document(text, None, &[])?,
+        selection.name(),
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::engine::tokenize_text;
     use crate::reading::token::Token;
 
     /// Test successful clipboard load with mocked arboard.
@@ -38,14 +175,13 @@ mod tests {
     /// Test that LoadedDocument has correct source field.
     #[test]
     fn test_loaded_document_source() {
-        let doc = LoadedDocument {
-            tokens: vec![Token {
-                text: "test".to_string(),
-                punctuation: vec![],
+        let doc = LoadedDocument::new(
+            vec![Token {
                 is_sentence_start: true,
+                ..Token::new("test")
             }],
-            source: "clipboard".to_string(),
-        };
+            "clipboard",
+        );
 
         assert_eq!(doc.source, "clipboard");
     }
@@ -53,19 +189,34 @@ mod tests {
     /// Test that LoadedDocument tokens preserve punctuation.
     #[test]
     fn test_loaded_document_tokens_preserve_punctuation() {
-        let doc = LoadedDocument {
-            tokens: tokenize_text("Hello, world!"),
-            source: "clipboard".to_string(),
-        };
+        let doc = LoadedDocument::new(tokenize_text("Hello, world!"), "clipboard");
 
         // First token should be "Hello" with comma punctuation
-        assert_eq!(doc.tokens[0].text, "Hello");
-        assert_eq!(doc.tokens[0].punctuation, vec![',']);
-        assert!(doc.tokens[0].is_sentence_start);
+        assert_eq!(doc.tokens.get(0).unwrap().text, "Hello");
+        assert_eq!(doc.tokens.get(0).unwrap().punctuation.as_str(), ",");
+        assert!(doc.tokens.get(0).unwrap().is_sentence_start);
 
         // Second token should be "world" with exclamation punctuation
-        assert_eq!(doc.tokens[1].text, "world");
-        assert_eq!(doc.tokens[1].punctuation, vec!['!']);
-        assert!(!doc.tokens[1].is_sentence_start);
+        assert_eq!(doc.tokens.get(1).unwrap().text, "world");
+        assert_eq!(doc.tokens.get(1).unwrap().punctuation.as_str(), "!");
+        assert!(!doc.tokens.get(1).unwrap().is_sentence_start);
+    }
+
+    #[test]
+    fn test_change_detector_reads_each_copy_once() {
+        let mut changes = ChangeDetector::default();
+        assert!(changes.is_new("First passage."));
+        assert!(!changes.is_new("First passage."));
+        assert!(!changes.is_new("First passage.\n"));
+        assert!(!changes.is_new("   "));
+        assert!(changes.is_new("Second passage."));
+        assert!(changes.is_new("First passage."));
+    }
+
+    #[test]
+    fn test_selection_documents_name_their_source() {
+        let doc = document("Highlighted words.", Selection::Primary).unwrap();
+        assert_eq!(doc.source, "primary selection");
+        assert_eq!(doc.tokens.len(), 2);
     }
 }
@@ -0,0 +1,1013 @@
+//! Source-code comment loader
+//!
+//! Reads only the prose of a source file: comments, docstrings and doc
+//! comments. Code is skipped. Before the comments of each item a single
+//! marker token (`§ fn load`) names the function, type or module they belong
+//! to, and each marker is recorded as a [`Section`].
+//!
+//! A comment belongs to the item it documents (`///`, `/** */`, a comment
+//! directly above a declaration) or else to the declaration enclosing it,
+//! found by indentation. Inner docs (`//!`, module docstrings) with no
+//! enclosing item belong to the file itself.
+
+use super::{encoding, LoadError, LoadOptions, LoadedDocument, Metadata, Section};
+use crate::reading::store::TokenStoreBuilder;
+use crate::reading::timing::{tokenize_into, TokenSink};
+use crate::reading::token::Token;
+use std::path::Path;
+
+/// Keywords that start a declaration in the supported languages.
+const DECLARATION_KEYWORDS: &[&str] = &[
+    "fn",
+    "struct",
+    "enum",
+    "trait",
+    "impl",
+    "mod",
+    "union",
+    "type",
+    "const",
+    "static",
+    "macro_rules!",
+    "class",
+    "interface",
+    "def",
+    "func",
+    "function",
+    "namespace",
+    "module",
+    "object",
+];
+
+/// Modifiers skipped when looking for a declaration keyword.
+const MODIFIERS: &[&str] = &[
+    "pub",
+    "async",
+    "unsafe",
+    "extern",
+    "\"C\"",
+    "default",
+    "export",
+    "public",
+    "private",
+    "protected",
+    "internal",
+    "abstract",
+    "final",
+];
+
+/// Words of a declaration kept in an item marker.
+const MAX_LABEL_WORDS: usize = 6;
+
+/// Placeholder delimiters for item markers, resolved after tokenization.
+const MARKER_START: char = '\u{E002}';
+const MARKER_END: char = '\u{E003}';
+
+/// Comment syntax of a source language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    /// `//`, nested `/* */`, `///` and `//!` doc comments
+    Rust,
+    /// `//` and `/* */` with `/** */` and `///` doc comments (C, Java, JS, Go...)
+    CLike,
+    /// `#` comments and docstrings
+    Python,
+    /// `#` comments (shell, Ruby)
+    Hash,
+}
+
+impl Syntax {
+    fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "rs" => Some(Self::Rust),
+            "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "java" | "js" | "jsx" | "mjs"
+            | "ts" | "tsx" | "go" | "swift" | "kt" | "kts" | "cs" | "scala" | "dart" => {
+                Some(Self::CLike)
+            }
+            "py" | "pyi" => Some(Self::Python),
+            "sh" | "bash" | "zsh" | "rb" => Some(Self::Hash),
+            _ => None,
+        }
+    }
+}
+
+/// Returns true for file extensions handled by this loader.
+pub fn is_source_extension(ext: &str) -> bool {
+    Syntax::from_extension(ext).is_some()
+}
+
+/// Load the comments and docstrings of a source file.
+///
+/// Purpose: Lets reviewers speed-read a module's documentation without code.
+/// Big Picture: Enables `@src/rendering/kitty.rs` in the command deck.
+/// Connections: Depends on engine::tokenize_text() for tokenization.
+pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let bytes = std::fs::read(path)?;
+    load_bytes(&bytes, path, path, &LoadOptions::default())
+}
+
+/// Load source already read into memory.
+///
+/// `name` is the file the source came from, which picks the language and
+/// titles the document; it differs from `path` for compressed files, where
+/// `path` is `lib.rs.gz` and `name` is `lib.rs`. The encoding is detected as
+/// for plain text unless `options.encoding` is given.
+pub fn load_bytes(
+    data: &[u8],
+    path: &Path,
+    name: &Path,
+    options: &LoadOptions,
+) -> Result<LoadedDocument, LoadError> {
+    let syntax = name
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(|e| Syntax::from_extension(&e.to_lowercase()))
+        .ok_or_else(|| LoadError::UnsupportedFormat(name.display().to_string()))?;
+    let file_name = name
+        .file_name()
+        .map_or_else(String::new, |n| n.to_string_lossy().to_string());
+
+    let source = format!("code:{}", path.display());
+    let decoded = encoding::decode(data, options.encoding)
+        .ok_or_else(|| LoadError::InvalidEncoding(source.clone()))?;
+
+    let mut doc = extract_prose(&decoded.text, syntax, &file_name, source)?;
+    doc.metadata.encoding = Some(decoded.encoding.name().to_string());
+    Ok(doc)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommentKind {
+    Plain,
+    /// Documents the item that follows (`///`, `/** */`)
+    Outer,
+    /// Documents the enclosing item (`//!`, `/*! */`, docstrings)
+    Inner,
+}
+
+#[derive(Debug)]
+struct Comment {
+    kind: CommentKind,
+    line: usize,
+    end_line: usize,
+    column: usize,
+    /// Code precedes the comment on its first line.
+    trailing: bool,
+    text: String,
+}
+
+/// Turns marker words into `§ label` tokens as they are tokenized, and
+/// records each as a section.
+struct ItemMarkers<'a> {
+    inner: TokenStoreBuilder,
+    markers: &'a [Owner],
+    sections: Vec<Section>,
+}
+
+impl TokenSink for ItemMarkers<'_> {
+    fn push(&mut self, mut token: Token) {
+        let marker = token
+            .text
+            .strip_prefix(MARKER_START)
+            .and_then(|rest| rest.strip_suffix(MARKER_END))
+            .and_then(|n| n.parse::<usize>().ok())
+            .and_then(|n| self.markers.get(n));
+        if let Some(marker) = marker {
+            token.text = format!("§ {}", marker.label);
+            token.is_sentence_start = true;
+            self.sections.push(Section {
+                title: marker.label.clone(),
+                level: (marker.depth + 1).min(6) as u8,
+                token_index: self.inner.len(),
+            });
+        }
+        self.inner.push(token);
+    }
+
+    fn last(&self) -> Option<&Token> {
+        self.inner.last()
+    }
+
+    fn last_mut(&mut self) -> Option<&mut Token> {
+        self.inner.last_mut()
+    }
+
+    fn last_word_mut(&mut self) -> Option<&mut Token> {
+        self.inner.last_word_mut()
+    }
+}
+
+/// The item a comment belongs to.
+#[derive(Debug, Clone, PartialEq)]
+struct Owner {
+    /// Line of the declaration; `usize::MAX` for the file itself.
+    line: usize,
+    label: String,
+    depth: usize,
+}
+
+fn extract_prose(
+    source: &str,
+    syntax: Syntax,
+    file_name: &str,
+    doc_source: String,
+) -> Result<LoadedDocument, LoadError> {
+    let lines: Vec<&str> = source.lines().collect();
+    let (comments, code_lines) = scan(source, syntax);
+    let blocks = merge_line_comments(comments);
+
+    let mut text = String::new();
+    let mut markers = Vec::new();
+    let mut current: Option<Owner> = None;
+    let mut scopes = ScopeTracker::default();
+
+    for block in &blocks {
+        let scope_end = if block.trailing {
+            block.line + 1
+        } else {
+            block.line
+        };
+        scopes.advance_to(scope_end, &lines, &code_lines);
+
+        let paragraphs = prose_paragraphs(&block.text);
+        if paragraphs.is_empty() {
+            continue;
+        }
+
+        let owner = resolve_owner(block, &lines, &code_lines, &scopes, file_name);
+        if let Some(owner) = owner.as_ref().filter(|o| current.as_ref() != Some(*o)) {
+            text.push_str(&format!(
+                "{}{}{}\n",
+                MARKER_START,
+                markers.len(),
+                MARKER_END
+            ));
+            markers.push(owner.clone());
+        }
+        current = owner;
+
+        for paragraph in paragraphs {
+            text.push_str(&paragraph);
+            text.push('\n');
+        }
+    }
+
+    if text.trim().is_empty() {
+        return Err(LoadError::TextRead(format!(
+            "No comments or docstrings in {}",
+            doc_source
+        )));
+    }
+
+    let mut tokens = ItemMarkers {
+        inner: TokenStoreBuilder::new(),
+        markers: &markers,
+        sections: Vec::new(),
+    };
+    tokenize_into(&text, None, &mut tokens);
+
+    Ok(LoadedDocument {
+        tokens: tokens.inner.finish()?,
+        source: doc_source,
+        sections: tokens.sections,
+        metadata: Metadata {
+            title: Some(file_name.to_string()).filter(|n| !n.is_empty()),
+            ..Metadata::default()
+        },
+        cues: Vec::new(),
+        notices: Vec::new(),
+    })
+}
+
+/// Finds the item a comment block belongs to.
+fn resolve_owner(
+    block: &Comment,
+    lines: &[&str],
+    code_lines: &[bool],
+    scopes: &ScopeTracker,
+    file_name: &str,
+) -> Option<Owner> {
+    let next = next_item_line(block.end_line, lines, code_lines);
+
+    match block.kind {
+        CommentKind::Outer => next.map(|line| scopes.owner_at(line, lines)),
+        CommentKind::Plain if !block.trailing => next
+            .filter(|&line| {
+                line == block.end_line + 1 + attribute_lines(block.end_line, line, lines)
+                    && declaration_label(lines[line]).is_some()
+            })
+            .map(|line| scopes.owner_at(line, lines))
+            .or_else(|| scopes.enclosing(block.column)),
+        CommentKind::Plain => scopes.enclosing(usize::MAX),
+        CommentKind::Inner => scopes.enclosing(block.column).or_else(|| {
+            Some(Owner {
+                line: usize::MAX,
+                label: file_name.to_string(),
+                depth: 0,
+            })
+        }),
+    }
+}
+
+/// Next line with code after `line`, skipping attributes and decorators.
+fn next_item_line(line: usize, lines: &[&str], code_lines: &[bool]) -> Option<usize> {
+    (line + 1..lines.len())
+        .filter(|&l| code_lines.get(l).copied().unwrap_or(false))
+        .find(|&l| !is_attribute(lines[l]))
+}
+
+/// Number of attribute lines between a comment and the item below it.
+fn attribute_lines(end_line: usize, item_line: usize, lines: &[&str]) -> usize {
+    (end_line + 1..item_line)
+        .filter(|&l| is_attribute(lines[l]))
+        .count()
+}
+
+fn is_attribute(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("#[") || line.starts_with('@')
+}
+
+fn indent_of(line: &str) -> usize {
+    line.chars().take_while(|c| c.is_whitespace()).count()
+}
+
+/// Tracks the declarations enclosing the current line by indentation.
+#[derive(Debug, Default)]
+struct ScopeTracker {
+    /// (indent, line, label) of open declarations, outermost first
+    stack: Vec<(usize, usize, String)>,
+    next_line: usize,
+}
+
+impl ScopeTracker {
+    /// Processes code lines before `line`: a line closes every declaration
+    /// indented as deep or deeper, and opens one if it is a declaration.
+    fn advance_to(&mut self, line: usize, lines: &[&str], code_lines: &[bool]) {
+        while self.next_line < line.min(lines.len()) {
+            let l = self.next_line;
+            self.next_line += 1;
+            if !code_lines.get(l).copied().unwrap_or(false) || is_attribute(lines[l]) {
+                continue;
+            }
+            let indent = indent_of(lines[l]);
+            self.stack.retain(|(i, _, _)| *i < indent);
+            if let Some(label) = declaration_label(lines[l]) {
+                self.stack.push((indent, l, label));
+            }
+        }
+    }
+
+    /// The innermost open declaration indented less than `column`.
+    fn enclosing(&self, column: usize) -> Option<Owner> {
+        let depth = self.stack.iter().filter(|(i, _, _)| *i < column).count();
+        self.stack[..depth].last().map(|(_, line, label)| Owner {
+            line: *line,
+            label: label.clone(),
+            depth: depth - 1,
+        })
+    }
+
+    /// The item declared on `line`, nested under the open declarations.
+    fn owner_at(&self, line: usize, lines: &[&str]) -> Owner {
+        let indent = indent_of(lines[line]);
+        Owner {
+            line,
+            label: item_label(lines[line]),
+            depth: self.stack.iter().filter(|(i, _, _)| *i < indent).count(),
+        }
+    }
+}
+
+/// Returns the item label if `line` declares a function, type or module.
+fn declaration_label(line: &str) -> Option<String> {
+    let label = item_label(line);
+    let keyword = label.split_whitespace().next()?;
+    DECLARATION_KEYWORDS.contains(&keyword).then_some(label)
+}
+
+/// Short name of the item declared on a line: `pub fn load(path: &str) {`
+/// becomes `fn load`, `impl<R: Read> Package<R> {` becomes `impl Package`.
+fn item_label(line: &str) -> String {
+    let line = line.trim();
+    // `pub(crate)` would otherwise be cut at its parenthesis
+    let line = match line.strip_prefix("pub(") {
+        Some(rest) => rest.split_once(')').map_or(rest, |(_, item)| item),
+        None => line,
+    };
+    let line = line.split(['{', ';']).next().unwrap_or(line);
+    let line = strip_generics(line);
+
+    let mut end = line.len();
+    let mut prev = ' ';
+    for (i, c) in line.char_indices() {
+        let next = line[i + c.len_utf8()..].chars().next();
+        let cut = match c {
+            '(' => prev.is_alphanumeric() || prev == '_',
+            ':' => prev != ':' && next != Some(':'),
+            '=' => true,
+            _ => false,
+        };
+        if cut {
+            end = i;
+            break;
+        }
+        prev = c;
+    }
+    let line = line[..end].split(" where ").next().unwrap_or("");
+
+    let words: Vec<&str> = line
+        .split_whitespace()
+        .skip_while(|w| MODIFIERS.contains(w))
+        .take(MAX_LABEL_WORDS)
+        .collect();
+    words.join(" ")
+}
+
+/// Removes `<...>` generic parameters, keeping unbalanced `<` (operators).
+fn strip_generics(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut depth = 0usize;
+    let mut prev = ' ';
+    for c in text.chars() {
+        match c {
+            '<' if prev != ' ' || depth > 0 => depth += 1,
+            '>' if depth > 0 && prev != '-' => depth -= 1,
+            _ if depth == 0 => out.push(c),
+            _ => {}
+        }
+        prev = c;
+    }
+    out
+}
+
+/// Joins consecutive standalone line comments of the same kind and column.
+fn merge_line_comments(comments: Vec<Comment>) -> Vec<Comment> {
+    let mut blocks: Vec<Comment> = Vec::new();
+    for comment in comments {
+        if let Some(last) = blocks.last_mut() {
+            let continues = !comment.trailing
+                && !last.trailing
+                && comment.kind == last.kind
+                && comment.column == last.column
+                && comment.line == last.end_line + 1;
+            if continues {
+                last.text.push('\n');
+                last.text.push_str(&comment.text);
+                last.end_line = comment.end_line;
+                continue;
+            }
+        }
+        blocks.push(comment);
+    }
+    blocks
+}
+
+/// Splits comment text into prose paragraphs.
+///
+/// Blank lines and separator rules end a paragraph; fenced code blocks and
+/// lines that look like commented-out code are dropped, and Markdown markup
+/// is reduced to its text.
+fn prose_paragraphs(text: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current = String::new();
+    let mut in_fence = false;
+
+    let mut flush = |current: &mut String| {
+        if !current.trim().is_empty() {
+            paragraphs.push(current.trim().to_string());
+        }
+        current.clear();
+    };
+
+    for line in text.lines() {
+        let line = line.trim();
+        let line = line.strip_prefix('*').map_or(line, str::trim_start);
+        if line.starts_with("```") {
+            in_fence = !in_fence;
+            flush(&mut current);
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let line = clean_markdown(line);
+        let is_code = line.ends_with(';') || line.ends_with('{') || line.ends_with('}');
+        if !line.chars().any(char::is_alphanumeric) || is_code {
+            flush(&mut current);
+            continue;
+        }
+        current.push(' ');
+        current.push_str(&line);
+    }
+    flush(&mut current);
+    paragraphs
+}
+
+/// Reduces a Markdown line to its text: headings, bullets, quotes, code
+/// spans, emphasis and link targets are removed.
+fn clean_markdown(line: &str) -> String {
+    let line = line.trim_start_matches('#').trim_start();
+    let line = ["- ", "* ", "> "]
+        .iter()
+        .find_map(|bullet| line.strip_prefix(bullet))
+        .unwrap_or(line);
+
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '`' | '[' => {}
+            ']' if chars.peek() == Some(&'(') => {
+                for c in chars.by_ref() {
+                    if c == ')' {
+                        break;
+                    }
+                }
+            }
+            ']' => {}
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out.trim().to_string()
+}
+
+/// Splits source into comments and marks which lines contain code.
+///
+/// String literals are skipped so comment markers inside them are ignored;
+/// Python docstrings are returned as inner doc comments.
+fn scan(source: &str, syntax: Syntax) -> (Vec<Comment>, Vec<bool>) {
+    let chars: Vec<char> = source.chars().collect();
+    let mut scanner = Scanner {
+        chars: &chars,
+        pos: 0,
+        line: 0,
+        line_start: 0,
+        line_has_code: false,
+        code_lines: vec![false; source.lines().count() + 1],
+        comments: Vec::new(),
+        source_lines: source.lines().collect(),
+    };
+    scanner.run(syntax);
+    (scanner.comments, scanner.code_lines)
+}
+
+struct Scanner<'a> {
+    chars: &'a [char],
+    pos: usize,
+    line: usize,
+    line_start: usize,
+    line_has_code: bool,
+    code_lines: Vec<bool>,
+    comments: Vec<Comment>,
+    source_lines: Vec<&'a str>,
+}
+
+impl Scanner<'_> {
+    fn run(&mut self, syntax: Syntax) {
+        let c_style = matches!(syntax, Syntax::Rust | Syntax::CLike);
+
+        while let Some(&c) = self.chars.get(self.pos) {
+            if c == '\n' {
+                self.newline();
+                self.pos += 1;
+            } else if c.is_whitespace() {
+                self.pos += 1;
+            } else if c_style && self.at("//") {
+                self.line_comment(2, syntax);
+            } else if c_style && self.at("/*") {
+                self.block_comment(syntax);
+            } else if !c_style && c == '#' {
+                self.line_comment(1, syntax);
+            } else if syntax == Syntax::Python && (self.at("\"\"\"") || self.at("'''")) {
+                self.triple_string();
+            } else if syntax == Syntax::Rust && self.raw_string_hashes().is_some() {
+                self.raw_string();
+            } else if c == '"' || c == '`' || (c == '\'' && syntax != Syntax::Rust) {
+                self.string(c);
+            } else if c == '\'' {
+                self.rust_char_or_lifetime();
+            } else {
+                self.mark_code();
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn at(&self, pattern: &str) -> bool {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(i, p)| self.chars.get(self.pos + i) == Some(&p))
+    }
+
+    fn newline(&mut self) {
+        self.line += 1;
+        self.line_start = self.pos + 1;
+        self.line_has_code = false;
+    }
+
+    fn mark_code(&mut self) {
+        self.line_has_code = true;
+        if let Some(flag) = self.code_lines.get_mut(self.line) {
+            *flag = true;
+        }
+    }
+
+    fn push_comment(&mut self, kind: CommentKind, line: usize, column: usize, text: String) {
+        self.comments.push(Comment {
+            kind,
+            line,
+            end_line: self.line,
+            column,
+            trailing: self.line_has_code && line == self.line,
+            text,
+        });
+    }
+
+    fn line_comment(&mut self, prefix: usize, syntax: Syntax) {
+        let column = self.pos - self.line_start;
+        let shebang = self.line == 0 && column == 0 && self.at("#!");
+        let kind = match syntax {
+            Syntax::Rust | Syntax::CLike if self.at("///") && !self.at("////") => {
+                CommentKind::Outer
+            }
+            Syntax::Rust if self.at("//!") => CommentKind::Inner,
+            _ => CommentKind::Plain,
+        };
+        let skip = if kind == CommentKind::Plain {
+            prefix
+        } else {
+            3
+        };
+
+        let start = self.pos + skip;
+        let mut end = start;
+        while end < self.chars.len() && self.chars[end] != '\n' {
+            end += 1;
+        }
+        let text: String = self.chars[start.min(end)..end].iter().collect();
+        self.pos = end;
+        if !shebang {
+            self.push_comment(kind, self.line, column, text);
+        }
+    }
+
+    fn block_comment(&mut self, syntax: Syntax) {
+        let line = self.line;
+        let column = self.pos - self.line_start;
+        let kind = if self.at("/**") && !self.at("/**/") && !self.at("/***") {
+            CommentKind::Outer
+        } else if syntax == Syntax::Rust && self.at("/*!") {
+            CommentKind::Inner
+        } else {
+            CommentKind::Plain
+        };
+        self.pos += if kind == CommentKind::Plain { 2 } else { 3 };
+
+        let start = self.pos;
+        let mut depth = 1;
+        let mut end = self.chars.len();
+        while self.pos < self.chars.len() {
+            if self.at("*/") {
+                depth -= 1;
+                if depth == 0 {
+                    end = self.pos;
+                    self.pos += 2;
+                    break;
+                }
+                self.pos += 2;
+            } else if syntax == Syntax::Rust && self.at("/*") {
+                depth += 1;
+                self.pos += 2;
+            } else {
+                if self.chars[self.pos] == '\n' {
+                    self.newline();
+                }
+                self.pos += 1;
+            }
+        }
+        let text: String = self.chars[start..end.max(start)].iter().collect();
+        self.push_comment(kind, line, column, text);
+    }
+
+    /// Skips a quoted string with backslash escapes.
+    fn string(&mut self, quote: char) {
+        self.mark_code();
+        self.pos += 1;
+        while let Some(&c) = self.chars.get(self.pos) {
+            self.pos += 1;
+            match c {
+                '\\' => {
+                    if self.chars.get(self.pos) == Some(&'\n') {
+                        self.newline();
+                    }
+                    self.pos += 1;
+                }
+                '\n' => {
+                    self.newline();
+                    self.mark_code();
+                }
+                _ if c == quote => break,
+                _ => {}
+            }
+        }
+    }
+
+    /// Number of `#`s if the cursor starts a Rust raw string (`r#"`, `br"`).
+    fn raw_string_hashes(&self) -> Option<usize> {
+        let prev = self.pos.checked_sub(1).map(|p| self.chars[p]);
+        if prev.is_some_and(|p| p.is_alphanumeric() || p == '_') {
+            return None;
+        }
+        let mut i = self.pos;
+        if self.chars.get(i) == Some(&'b') {
+            i += 1;
+        }
+        if self.chars.get(i) != Some(&'r') {
+            return None;
+        }
+        i += 1;
+        let hashes = self.chars[i..].iter().take_while(|&&c| c == '#').count();
+        (self.chars.get(i + hashes) == Some(&'"')).then_some(hashes)
+    }
+
+    fn raw_string(&mut self) {
+        let hashes = self.raw_string_hashes().unwrap_or(0);
+        self.mark_code();
+        while self.chars[self.pos] != '"' {
+            self.pos += 1;
+        }
+        self.pos += 1;
+        let closing = format!("\"{}", "#".repeat(hashes));
+        while self.pos < self.chars.len() && !self.at(&closing) {
+            if self.chars[self.pos] == '\n' {
+                self.newline();
+                self.mark_code();
+            }
+            self.pos += 1;
+        }
+        self.pos = (self.pos + closing.len()).min(self.chars.len());
+    }
+
+    /// Skips `'x'` or `'\n'` char literals; a lone `'` is a lifetime.
+    fn rust_char_or_lifetime(&mut self) {
+        self.mark_code();
+        let literal_len = if self.chars.get(self.pos + 1) == Some(&'\\') {
+            self.chars[self.pos + 2..]
+                .iter()
+                .take(10)
+                .position(|&c| c == '\'')
+                .map(|p| p + 3)
+        } else {
+            (self.chars.get(self.pos + 2) == Some(&'\'')).then_some(3)
+        };
+        self.pos += literal_len.unwrap_or(1);
+    }
+
+    /// A Python triple-quoted string: a docstring if it is the first
+    /// statement of the module or of a `def`/`class` body, otherwise code.
+    fn triple_string(&mut self) {
+        let line = self.line;
+        let column = self.pos - self.line_start;
+        let quote: String = self.chars[self.pos..self.pos + 3].iter().collect();
+        let is_docstring = !self.line_has_code && self.follows_header_or_start();
+        if !is_docstring {
+            self.mark_code();
+        }
+
+        self.pos += 3;
+        let start = self.pos;
+        while self.pos < self.chars.len() && !self.at(&quote) {
+            match self.chars[self.pos] {
+                '\\' => self.pos += 1,
+                '\n' => {
+                    self.newline();
+                    if !is_docstring {
+                        self.mark_code();
+                    }
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        let end = self.pos.min(self.chars.len());
+        self.pos = (self.pos + 3).min(self.chars.len());
+
+        if is_docstring {
+            let text: String = self.chars[start..end].iter().collect();
+            self.push_comment(CommentKind::Inner, line, column, text);
+        }
+    }
+
+    fn follows_header_or_start(&self) -> bool {
+        let previous = (0..self.line).rev().find(|&l| self.code_lines[l]);
+        match previous {
+            None => true,
+            Some(l) => {
+                let text = self.source_lines[l].trim();
+                let header = text.starts_with("def ")
+                    || text.starts_with("async def ")
+                    || text.starts_with("class ");
+                header && text.ends_with(':')
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUST: &str = r##"//! Kitty graphics protocol renderer.
+//!
+//! Sends images with `APC` escapes.
+
+use std::io::Write;
+
+/// Maximum payload per chunk.
+const CHUNK: usize = 4096;
+
+/// Renders words as images.
+///
+/// ```
+/// let r = KittyRenderer::new();
+/// ```
+#[derive(Debug)]
+pub struct KittyRenderer<W: Write> {
+    out: W,
+}
+
+impl<W: Write> KittyRenderer<W> {
+    /// Sends one chunk.
+    pub fn send(&mut self, data: &str) {
+        // Escape sequences must not be split.
+        let url = "http://example.com // not a comment";
+        let c = '/'; /* trailing note */
+        let _ = (url, c);
+    }
+}
+"##;
+
+    fn words(doc: &LoadedDocument) -> Vec<&str> {
+        doc.tokens
+            .iter()
+            .filter(|t| !t.text.is_empty())
+            .map(|t| t.text)
+            .collect()
+    }
+
+    fn load_rust() -> LoadedDocument {
+        extract_prose(RUST, Syntax::Rust, "kitty.rs", "code:kitty.rs".to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_rust_comments_and_markers() {
+        let doc = load_rust();
+        let sections: Vec<(&str, u8)> = doc
+            .sections
+            .iter()
+            .map(|s| (s.title.as_str(), s.level))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                ("kitty.rs", 1),
+                ("const CHUNK", 1),
+                ("struct KittyRenderer", 1),
+                ("fn send", 2),
+            ]
+        );
+        assert_eq!(
+            doc.tokens.get(doc.sections[3].token_index).unwrap().text,
+            "§ fn send"
+        );
+    }
+
+    #[test]
+    fn test_rust_code_is_skipped() {
+        let doc = load_rust();
+        let words = words(&doc);
+
+        assert!(words.contains(&"APC"));
+        assert!(words.contains(&"Escape"));
+        assert!(words.contains(&"trailing"));
+        assert!(!words.contains(&"let"));
+        assert!(!words.contains(&"http://example.com"));
+        assert!(!words.contains(&"KittyRenderer::new()"));
+        assert!(!words.iter().any(|w| w.contains("derive")));
+    }
+
+    #[test]
+    fn test_python_docstrings_and_comments() {
+        let source = "#!/usr/bin/env python\n\
+            \"\"\"Utilities for parsing.\"\"\"\n\
+            \n\
+            # Cache of results\n\
+            CACHE = {}\n\
+            \n\
+            class Parser:\n\
+            \x20   \"\"\"Parses things.\n\
+            \n\
+            \x20   Second paragraph.\n\
+            \x20   \"\"\"\n\
+            \n\
+            \x20   def run(self):\n\
+            \x20       text = '''not a docstring'''\n\
+            \x20       # Step one\n\
+            \x20       return text\n";
+        let doc = extract_prose(
+            source,
+            Syntax::Python,
+            "util.py",
+            "code:util.py".to_string(),
+        )
+        .unwrap();
+
+        let titles: Vec<&str> = doc.sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["util.py", "class Parser", "def run"]);
+        let words = words(&doc);
+        assert!(words.contains(&"Cache"));
+        assert!(words.contains(&"Second"));
+        assert!(words.contains(&"Step"));
+        assert!(!words.contains(&"not"));
+        assert!(!words.contains(&"python"));
+    }
+
+    #[test]
+    fn test_c_like_doc_comments() {
+        let source = "/**\n * Adds two numbers.\n * @return the sum\n */\nint add(int a, int b) {\n    return a + b; // fast path\n}\n";
+        let doc = extract_prose(source, Syntax::CLike, "add.c", "code:add.c".to_string()).unwrap();
+
+        assert_eq!(doc.sections[0].title, "int add");
+        let words = words(&doc);
+        assert!(words.contains(&"Adds"));
+        assert!(words.contains(&"fast"));
+    }
+
+    #[test]
+    fn test_item_label() {
+        assert_eq!(
+            item_label("pub fn load(path: &str) -> Result<(), E> {"),
+            "fn load"
+        );
+        assert_eq!(
+            item_label("impl<R: Read + Seek> Package<R> {"),
+            "impl Package"
+        );
+        assert_eq!(
+            item_label("impl fmt::Display for LoadRange {"),
+            "impl fmt::Display for LoadRange"
+        );
+        assert_eq!(item_label("pub(crate) const MAX: usize = 3;"), "const MAX");
+        assert_eq!(
+            item_label("func (s *Server) Start(port int) {"),
+            "func (s *Server) Start"
+        );
+        assert_eq!(item_label("class Parser(Base):"), "class Parser");
+    }
+
+    #[test]
+    fn test_markdown_cleanup() {
+        assert_eq!(
+            clean_markdown("- See [`Token`](crate::Token) and **bold** `code`."),
+            "See Token and bold code."
+        );
+        assert_eq!(clean_markdown("# Examples"), "Examples");
+    }
+
+    #[test]
+    fn test_legacy_encoding_is_detected() {
+        let source = b"/// Renvoie le r\xe9sultat \xe0 l'appelant, sans d\xe9lai.\nfn f() {}\n";
+        let doc = load_bytes(
+            source,
+            Path::new("f.rs"),
+            Path::new("f.rs"),
+            &LoadOptions::default(),
+        )
+        .unwrap();
+        assert!(words(&doc).contains(&"résultat"));
+        assert_eq!(doc.metadata.encoding.as_deref(), Some("windows-1252"));
+    }
+
+    #[test]
+    fn test_no_comments() {
+        let result = extract_prose(
+            "fn main() {}\n",
+            Syntax::Rust,
+            "main.rs",
+            "code:main.rs".to_string(),
+        );
+        assert!(matches!(result, Err(LoadError::TextRead(_))));
+    }
+}
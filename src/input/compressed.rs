@@ -0,0 +1,271 @@
+//! Transparent decompression of gzip, bzip2 and zstd files
+//!
+//! Compressed files are recognised by their magic bytes rather than their
+//! name, so a gzipped download saved without `.gz` still opens. The stream is
+//! decompressed while it is read, and the loader is then chosen from the name
+//! beneath the compression suffix: `notes.txt.gz` loads as text and
+//! `book.epub.zst` as an EPUB.
+//!
+//! Output is capped at [`MAX_DECOMPRESSED`] bytes, so a few kilobytes that
+//! expand to gigabytes fail with an error instead of exhausting memory.
+
+use super::LoadError;
+use ruzstd::decoding::{FrameDecoder, StreamingDecoder};
+use std::io::{self, BufRead, Read};
+use std::path::{Path, PathBuf};
+
+/// Most bytes a compressed document may expand to (1 GiB).
+pub const MAX_DECOMPRESSED: u64 = 1 << 30;
+
+/// A compression container wrapped around a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Bzip2,
+    Zstd,
+}
+
+impl Compression {
+    /// Recognises a container from the first bytes of a file.
+    pub fn detect(header: &[u8]) -> Option<Self> {
+        if header.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::Gzip)
+        } else if header.starts_with(b"BZh") {
+            Some(Self::Bzip2)
+        } else if header.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// File name suffixes used for this container, lowercase without the dot.
+    fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::Gzip => &["gz", "gzip"],
+            Self::Bzip2 => &["bz2", "bzip2"],
+            Self::Zstd => &["zst", "zstd"],
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Bzip2 => "bzip2",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Decompresses the whole stream into memory.
+    pub fn decompress<R: BufRead>(self, reader: R) -> Result<Vec<u8>, LoadError> {
+        let mut data = Vec::new();
+        self.reader(reader)
+            .read_to_end(&mut data)
+            .map_err(LoadError::Decompress)?;
+        Ok(data)
+    }
+
+    /// Decompresses `reader` as it is read.
+    ///
+    /// Concatenated members and frames (as written by `pigz`, `pbzip2` or
+    /// `cat a.zst b.zst`) are read as one stream. Errors name the container,
+    /// and reading fails once the output passes [`MAX_DECOMPRESSED`].
+    pub fn reader<'a, R: BufRead + 'a>(self, reader: R) -> impl Read + 'a {
+        self.reader_with_limit(reader, MAX_DECOMPRESSED)
+    }
+
+    fn reader_with_limit<'a, R: BufRead + 'a>(self, reader: R, limit: u64) -> Limited<'a> {
+        let decoder: Box<dyn Read + 'a> = match self {
+            Self::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(reader)),
+            Self::Bzip2 => Box::new(bzip2::bufread::MultiBzDecoder::new(reader)),
+            Self::Zstd => Box::new(ZstdFrames {
+                reader: Some(reader),
+                decoder: None,
+            }),
+        };
+        Limited {
+            inner: decoder.take(limit),
+            limit,
+            compression: self,
+        }
+    }
+}
+
+/// Decompressed output cut off after `limit` bytes with an error.
+struct Limited<'a> {
+    inner: io::Take<Box<dyn Read + 'a>>,
+    limit: u64,
+    compression: Compression,
+}
+
+impl Read for Limited<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let name = self.compression.name();
+        let read = self
+            .inner
+            .read(buf)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", name, e)))?;
+        // The limit is reached: fail if the stream goes on past it
+        if read == 0 && !buf.is_empty() && self.inner.limit() == 0 {
+            self.inner.set_limit(1);
+            if self.inner.read(&mut [0])? > 0 {
+                return Err(io::Error::other(format!(
+                    "{}: decompressed data exceeds {} MiB",
+                    name,
+                    self.limit >> 20
+                )));
+            }
+        }
+        Ok(read)
+    }
+}
+
+/// Reads zstd frames one after another from the same stream.
+struct ZstdFrames<R: BufRead> {
+    reader: Option<R>,
+    decoder: Option<StreamingDecoder<R, FrameDecoder>>,
+}
+
+impl<R: BufRead> Read for ZstdFrames<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if let Some(decoder) = self.decoder.as_mut() {
+                let read = decoder.read(buf)?;
+                if read > 0 || buf.is_empty() {
+                    return Ok(read);
+                }
+                self.reader = self.decoder.take().map(StreamingDecoder::into_inner);
+            }
+            let Some(mut reader) = self.reader.take() else {
+                return Ok(0);
+            };
+            if reader.fill_buf()?.is_empty() {
+                return Ok(0);
+            }
+            let decoder = StreamingDecoder::new(reader)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            self.decoder = Some(decoder);
+        }
+    }
+}
+
+/// Strips a compression suffix from `path`: `notes.txt.gz` becomes
+/// `notes.txt`. Paths without one are returned unchanged.
+pub fn inner_path(path: &Path) -> PathBuf {
+    let is_compression_suffix = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .is_some_and(|ext| {
+            [Compression::Gzip, Compression::Bzip2, Compression::Zstd]
+                .iter()
+                .any(|c| c.extensions().contains(&ext.as_str()))
+        });
+
+    if is_compression_suffix {
+        path.with_extension("")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const TEXT: &[u8] = b"Compressed words read like any others.";
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_detect_magic_bytes() {
+        assert_eq!(Compression::detect(&gzip(TEXT)), Some(Compression::Gzip));
+        assert_eq!(Compression::detect(b"BZh91AY&SY"), Some(Compression::Bzip2));
+        assert_eq!(
+            Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(Compression::detect(b"PK\x03\x04"), None);
+        assert_eq!(Compression::detect(b"%PDF-1.7"), None);
+    }
+
+    #[test]
+    fn test_gzip_concatenated_members() {
+        let mut data = gzip(b"first ");
+        data.extend(gzip(b"second"));
+        let out = Compression::Gzip.decompress(data.as_slice()).unwrap();
+        assert_eq!(out, b"first second");
+    }
+
+    #[test]
+    fn test_bzip2_round_trip() {
+        let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        encoder.write_all(TEXT).unwrap();
+        let data = encoder.finish().unwrap();
+        assert_eq!(Compression::detect(&data), Some(Compression::Bzip2));
+        let out = Compression::Bzip2.decompress(data.as_slice()).unwrap();
+        assert_eq!(out, TEXT);
+    }
+
+    #[test]
+    fn test_zstd_round_trip() {
+        let data =
+            ruzstd::encoding::compress_to_vec(TEXT, ruzstd::encoding::CompressionLevel::Fastest);
+        assert_eq!(Compression::detect(&data), Some(Compression::Zstd));
+        let out = Compression::Zstd.decompress(data.as_slice()).unwrap();
+        assert_eq!(out, TEXT);
+    }
+
+    #[test]
+    fn test_zstd_concatenated_frames() {
+        let level = ruzstd::encoding::CompressionLevel::Fastest;
+        let mut data = ruzstd::encoding::compress_to_vec(&b"first "[..], level);
+        data.extend(ruzstd::encoding::compress_to_vec(&b"second"[..], level));
+        let out = Compression::Zstd.decompress(data.as_slice()).unwrap();
+        assert_eq!(out, b"first second");
+    }
+
+    #[test]
+    fn test_truncated_stream_is_an_error() {
+        let data = gzip(TEXT);
+        let result = Compression::Gzip.decompress(&data[..data.len() / 2]);
+        assert!(matches!(result, Err(LoadError::Decompress(_))));
+    }
+
+    #[test]
+    fn test_output_is_capped() {
+        let data = gzip(&[b'a'; 4096]);
+        let mut out = Vec::new();
+        let result = Compression::Gzip
+            .reader_with_limit(data.as_slice(), 1024)
+            .read_to_end(&mut out);
+        assert!(result.unwrap_err().to_string().contains("exceeds"));
+
+        // Exactly at the limit is fine
+        let mut out = Vec::new();
+        Compression::Gzip
+            .reader_with_limit(data.as_slice(), 4096)
+            .read_to_end(&mut out)
+            .unwrap();
+        assert_eq!(out.len(), 4096);
+    }
+
+    #[test]
+    fn test_inner_path() {
+        assert_eq!(
+            inner_path(Path::new("dir/notes.txt.GZ")),
+            Path::new("dir/notes.txt")
+        );
+        assert_eq!(
+            inner_path(Path::new("book.epub.zst")),
+            Path::new("book.epub")
+        );
+        assert_eq!(inner_path(Path::new("book.epub")), Path::new("book.epub"));
+        assert_eq!(inner_path(Path::new("README.bz2")), Path::new("README"));
+    }
+}
@@ -0,0 +1,215 @@
+//! Choosing a loader for a file
+//!
+//! The loader is picked from the file extension, after first undoing any
+//! compression layer (see input::compressed), so every format can also be
+//! read gzipped, bzip2ed or zstd-compressed.
+
+use super::background::Reporter;
+use super::compressed::{self, Compression};
+use super::{
+    code, docx, email, epub, fb2, html, mobi, notebook, odt, org, pdf, rst, subtitle, text,
+    LoadError, LoadOptions, LoadedDocument,
+};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Extensions of the document formats matched in [`load_path`].
+const DOCUMENT_EXTENSIONS: &[&str] = &[
+    "pdf", "epub", "mobi", "azw", "azw3", "prc", "fb2", "srt", "vtt", "html", "htm", "xhtml",
+    "docx", "odt", "ipynb", "rst", "org", "eml", "mbox", "txt", "text", "md", "markdown",
+];
+
+/// Load any supported file, decompressing it first if needed.
+///
+/// Purpose: Single entry point behind `@file` and the FILE argument.
+/// Big Picture: App::open_file resolves ranges and calls this.
+/// Connections: Detects containers via input::compressed and hands the bytes
+/// to the format loader named by the (inner) extension.
+pub fn load_path(path: &str, options: &LoadOptions) -> Result<LoadedDocument, LoadError> {
+    load_path_reporting(path, options, &Reporter::silent())
+}
+
+/// [`load_path`] for a background [`LoadJob`](super::background::LoadJob):
+/// PDF and EPUB loaders report progress to `reporter` and stop when it is
+/// cancelled.
+pub fn load_path_reporting(
+    path: &str,
+    options: &LoadOptions,
+    reporter: &Reporter,
+) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let file = File::open(path)?;
+    let mut reader = BufReader::new(file);
+    let header = reader.fill_buf()?;
+    let compression = Compression::detect(header);
+
+    // The suffix is dropped even for files that turn out to be uncompressed,
+    // since browsers often save already-decoded downloads as `page.html.gz`
+    let name = compressed::inner_path(path);
+    let ext = name
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+
+    if !is_supported(ext.as_deref()) {
+        return Err(LoadError::UnsupportedFormat(unsupported_label(
+            path,
+            ext.as_deref(),
+            compression,
+        )));
+    }
+
+    let mut stream: Box<dyn Read> = match compression {
+        Some(compression) => Box::new(compression.reader(reader)),
+        None => Box::new(reader),
+    };
+
+    // Text is decoded straight from the stream; other formats need the
+    // whole file in memory
+    if matches!(
+        ext.as_deref(),
+        None | Some("txt" | "text" | "md" | "markdown")
+    ) {
+        return text::load_reader(stream, &format!("text:{}", path.display()), options);
+    }
+    let mut data = Vec::new();
+    stream
+        .read_to_end(&mut data)
+        .map_err(|e| match compression {
+            Some(_) => LoadError::Decompress(e),
+            None => LoadError::Io(e),
+        })?;
+
+    match ext.as_deref() {
+        Some("pdf") => pdf::load_bytes_reporting(&data, path, options, reporter),
+        Some("epub") => epub::load_bytes_reporting(&data, path, options, reporter),
+        Some("mobi" | "azw" | "azw3" | "prc") => mobi::load_bytes(&data, path, options),
+        Some("fb2") => fb2::load_bytes(&data, path),
+        Some("srt" | "vtt") => subtitle::load_bytes(&data, path, options),
+        Some(ext) if code::is_source_extension(ext) => {
+            code::load_bytes(&data, path, &name, options)
+        }
+        Some("html" | "htm" | "xhtml") => html::load_bytes(&data, path, options),
+        Some("docx") => docx::load_bytes(&data, path),
+        Some("odt") => odt::load_bytes(&data, path),
+        Some("ipynb") => notebook::load_bytes(&data, path),
+        Some("rst") => rst::load_bytes(&data, path, options),
+        Some("org") => org::load_bytes(&data, path, options),
+        Some("eml" | "mbox") => email::load_bytes(&data, path),
+        _ => text::load_bytes(&data, path, options),
+    }
+}
+
+/// Returns true if `path` has an extension some loader handles, looking
+/// beneath a compression suffix.
+///
+/// Unlike [`load_path`], extensionless files do not count: a directory queue
+/// should not pick up `Makefile` or `LICENSE`.
+pub fn is_supported_path(path: &Path) -> bool {
+    compressed::inner_path(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| is_supported(Some(&ext.to_lowercase())))
+}
+
+/// Returns true if some loader handles files with this extension.
+fn is_supported(ext: Option<&str>) -> bool {
+    ext.is_none_or(|ext| DOCUMENT_EXTENSIONS.contains(&ext) || code::is_source_extension(ext))
+}
+
+/// Names the file, and for compressed files both layers, e.g.
+/// `data.xyz.gz (gzip containing .xyz)`.
+fn unsupported_label(path: &Path, ext: Option<&str>, compression: Option<Compression>) -> String {
+    let filename = path.file_name().map_or_else(
+        || "unknown".to_string(),
+        |n| n.to_string_lossy().to_string(),
+    );
+    match (compression, ext) {
+        (Some(compression), Some(ext)) => {
+            format!("{} ({} containing .{})", filename, compression.name(), ext)
+        }
+        _ => filename,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_gzip(name: &str, data: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("speedy-{}-{}", std::process::id(), name));
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_gzipped_text_loads_as_text() {
+        let path = write_gzip("notes.txt.gz", b"Hello from inside gzip.");
+        let doc = load_path(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(doc.tokens.get(0).unwrap().text, "Hello");
+        assert!(doc.source.starts_with("text:"));
+        assert!(doc.source.ends_with("notes.txt.gz"));
+    }
+
+    #[test]
+    fn test_gzipped_inner_format_dispatch() {
+        let srt = b"1\n00:00:01,000 --> 00:00:02,000\nCompressed captions.\n";
+        let path = write_gzip("talk.srt.gz", srt);
+        let doc = load_path(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(doc.source.starts_with("subtitle:"));
+        assert_eq!(doc.cues.len(), 1);
+    }
+
+    #[test]
+    fn test_unsupported_inner_format_names_both_layers() {
+        let path = write_gzip("data.xyz.gz", b"binary");
+        let result = load_path(path.to_str().unwrap(), &LoadOptions::default());
+        std::fs::remove_file(&path).unwrap();
+
+        match result {
+            Err(LoadError::UnsupportedFormat(message)) => {
+                assert!(message.ends_with("data.xyz.gz (gzip containing .xyz)"));
+            }
+            other => panic!("expected UnsupportedFormat, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_uncompressed_names_file() {
+        let label = unsupported_label(Path::new("dir/data.xyz"), Some("xyz"), None);
+        assert_eq!(label, "data.xyz");
+    }
+
+    #[test]
+    fn test_load_nonexistent_file() {
+        let result = load_path("/nonexistent/notes.txt.gz", &LoadOptions::default());
+        assert!(matches!(result, Err(LoadError::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_prose_formats_dispatch_by_extension() {
+        for (path, prefix) in [
+            ("tests/test_files/analysis.ipynb", "notebook:"),
+            ("tests/test_files/analysis.rst", "rst:"),
+            ("tests/test_files/analysis.org", "org:"),
+            ("tests/test_files/digest.mbox", "email:"),
+        ] {
+            let doc = load_path(path, &LoadOptions::default()).unwrap();
+            assert!(doc.source.starts_with(prefix), "{}", doc.source);
+            assert!(!doc.sections.is_empty());
+        }
+    }
+}
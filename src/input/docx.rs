@@ -0,0 +1,371 @@
+//! Word (DOCX) document loader
+//!
+//! Walks `word/document.xml` paragraph by paragraph. Paragraph styles are
+//! resolved through `word/styles.xml` to find headings, and footnote and
+//! endnote references are attached from `word/footnotes.xml` and
+//! `word/endnotes.xml`. Tracked deletions, moved-away text, field codes and
+//! comments never reach the reading stream.
+
+use super::markup::{self, Element, Node};
+use super::notes::note_ref_marker;
+use super::package::Package;
+use super::structure::DocumentBuilder;
+use super::{LoadError, LoadedDocument};
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+/// Run-level elements whose content is never read.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "w:del",
+    "w:deltext",
+    "w:movefrom",
+    "w:instrtext",
+    "w:commentreference",
+    "w:commentrangestart",
+    "w:commentrangeend",
+    "w:rpr",
+    "w:ppr",
+    "w:drawing",
+    "w:pict",
+    "mc:fallback",
+];
+
+/// Load a DOCX file.
+///
+/// Purpose: Reads Word documents with headings, paragraphs and footnotes.
+/// Big Picture: Enables `@report.docx` in the command deck.
+/// Connections: Reads the package via input::package, parses XML with
+/// input::markup and builds the document via input::structure.
+pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let package = Package::open(path)?;
+    load_package(package, format!("docx:{}", path.display()))
+}
+
+/// Load a DOCX already read into memory; `path` is only used to name the source.
+pub fn load_bytes(data: &[u8], path: &Path) -> Result<LoadedDocument, LoadError> {
+    let package = Package::from_reader(Cursor::new(data))?;
+    load_package(package, format!("docx:{}", path.display()))
+}
+
+fn load_package<R: Read + Seek>(
+    mut package: Package<R>,
+    source: String,
+) -> Result<LoadedDocument, LoadError> {
+    let document = package.require_part("word/document.xml")?;
+    let styles = package.read_part("word/styles.xml")?;
+    let mut note_bodies = HashMap::new();
+    for (part, kind) in [
+        ("word/footnotes.xml", "w:footnote"),
+        ("word/endnotes.xml", "w:endnote"),
+    ] {
+        if let Some(xml) = package.read_part(part)? {
+            collect_note_bodies(&markup::parse(&xml), kind, &mut note_bodies);
+        }
+    }
+
+    let heading_styles = styles
+        .map(|xml| heading_styles(&markup::parse(&xml)))
+        .unwrap_or_default();
+
+    let mut reader = DocxReader {
+        heading_styles,
+        note_bodies,
+        notes: Vec::new(),
+        builder: DocumentBuilder::new(),
+    };
+    let root = markup::parse(&document);
+    if let Some(body) = root.find(&|e| e.name == "w:body") {
+        reader.read_blocks(body);
+    }
+
+    if reader.builder.is_empty() {
+        return Err(LoadError::DocxParse(
+            "No text content found in document".to_string(),
+        ));
+    }
+    reader.builder.build(source, &reader.notes)
+}
+
+struct DocxReader {
+    /// Paragraph style id → heading level
+    heading_styles: HashMap<String, u8>,
+    /// `(kind, id)` → note text, kind being `w:footnote` or `w:endnote`
+    note_bodies: HashMap<(String, String), String>,
+    notes: Vec<String>,
+    builder: DocumentBuilder,
+}
+
+impl DocxReader {
+    /// Reads block-level content: paragraphs, tables and content controls.
+    fn read_blocks(&mut self, container: &Element) {
+        for child in &container.children {
+            let Node::Element(e) = child else {
+                continue;
+            };
+            match e.name.as_str() {
+                "w:p" => self.read_paragraph(e),
+                "w:tbl" | "w:tr" | "w:tc" | "w:sdt" | "w:sdtcontent" | "w:ins" | "w:customxml"
+                | "w:smarttag" => self.read_blocks(e),
+                _ => {}
+            }
+        }
+    }
+
+    fn read_paragraph(&mut self, p: &Element) {
+        let mut text = String::new();
+        self.read_runs(p, &mut text);
+
+        match self.paragraph_level(p) {
+            Some(level) => self.builder.heading(level, &text),
+            None => self.builder.paragraph(&text),
+        }
+    }
+
+    /// Collects the visible text of runs, replacing note references with markers.
+    fn read_runs(&mut self, e: &Element, out: &mut String) {
+        for child in &e.children {
+            match child {
+                Node::Text(_) => {}
+                Node::Element(child) => match child.name.as_str() {
+                    name if SKIPPED_ELEMENTS.contains(&name) => {}
+                    "w:t" => out.push_str(&text_content(child)),
+                    "w:tab" | "w:br" | "w:cr" => out.push(' '),
+                    "w:nobreakhyphen" => out.push('-'),
+                    "w:footnotereference" => self.note_reference(child, "w:footnote", out),
+                    "w:endnotereference" => self.note_reference(child, "w:endnote", out),
+                    _ => self.read_runs(child, out),
+                },
+            }
+        }
+    }
+
+    fn note_reference(&mut self, reference: &Element, kind: &str, out: &mut String) {
+        let Some(id) = reference.attr("w:id") else {
+            return;
+        };
+        if let Some(body) = self.note_bodies.get(&(kind.to_string(), id.to_string())) {
+            self.notes.push(body.clone());
+            out.push_str(&note_ref_marker(self.notes.len() - 1));
+        }
+    }
+
+    fn paragraph_level(&self, p: &Element) -> Option<u8> {
+        let properties = p.children.iter().find_map(|n| match n {
+            Node::Element(e) if e.name == "w:ppr" => Some(e),
+            _ => None,
+        })?;
+
+        if let Some(outline) = properties.find(&|e| e.name == "w:outlinelvl") {
+            if let Some(level) = outline.attr("w:val").and_then(|v| v.parse::<u8>().ok()) {
+                // Level 9 means body text
+                return (level < 9).then_some(level + 1);
+            }
+        }
+        let style = properties.find(&|e| e.name == "w:pstyle")?.attr("w:val")?;
+        self.heading_styles
+            .get(style)
+            .copied()
+            .or_else(|| heading_level_from_name(style))
+    }
+}
+
+/// Concatenated text nodes of an element (`w:t` keeps spaces verbatim).
+fn text_content(e: &Element) -> String {
+    e.children
+        .iter()
+        .filter_map(|n| match n {
+            Node::Text(text) => Some(text.as_str()),
+            Node::Element(_) => None,
+        })
+        .collect()
+}
+
+/// Maps paragraph style ids to heading levels using style names and outline levels.
+fn heading_styles(styles: &Element) -> HashMap<String, u8> {
+    let mut levels = HashMap::new();
+    styles.walk(&mut |e| {
+        if e.name != "w:style" || e.attr("w:type") != Some("paragraph") {
+            return;
+        }
+        let Some(id) = e.attr("w:styleid") else {
+            return;
+        };
+        let outline = e
+            .find(&|c| c.name == "w:outlinelvl")
+            .and_then(|c| c.attr("w:val"))
+            .and_then(|v| v.parse::<u8>().ok())
+            .filter(|&level| level < 9)
+            .map(|level| level + 1);
+        let by_name = e
+            .find(&|c| c.name == "w:name")
+            .and_then(|c| c.attr("w:val"))
+            .and_then(heading_level_from_name);
+        if let Some(level) = outline.or(by_name) {
+            levels.insert(id.to_string(), level);
+        }
+    });
+    levels
+}
+
+/// Recognises built-in heading style names and ids (`heading 2`, `Heading2`, `Title`).
+fn heading_level_from_name(name: &str) -> Option<u8> {
+    let name = name.to_lowercase().replace(' ', "");
+    if name == "title" {
+        return Some(1);
+    }
+    match name.strip_prefix("heading")?.parse::<u8>() {
+        Ok(level @ 1..=9) => Some(level),
+        _ => None,
+    }
+}
+
+/// Collects footnote or endnote bodies, skipping separator notes.
+fn collect_note_bodies(root: &Element, kind: &str, bodies: &mut HashMap<(String, String), String>) {
+    root.walk(&mut |e| {
+        if e.name != kind || e.attr("w:type").is_some_and(|t| t != "normal") {
+            return;
+        }
+        let Some(id) = e.attr("w:id") else {
+            return;
+        };
+        let mut paragraphs = Vec::new();
+        e.walk(&mut |p| {
+            if p.name == "w:p" {
+                let mut text = String::new();
+                collect_note_text(p, &mut text);
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !text.is_empty() {
+                    paragraphs.push(text);
+                }
+            }
+        });
+        bodies.insert((kind.to_string(), id.to_string()), paragraphs.join(" "));
+    });
+}
+
+fn collect_note_text(e: &Element, out: &mut String) {
+    for child in &e.children {
+        if let Node::Element(child) = child {
+            match child.name.as_str() {
+                name if SKIPPED_ELEMENTS.contains(&name) => {}
+                // The note's own number mark
+                "w:footnoteref" | "w:endnoteref" => {}
+                "w:t" => out.push_str(&text_content(child)),
+                "w:tab" | "w:br" => out.push(' '),
+                _ => collect_note_text(child, out),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::package::test_support::build_package;
+    use crate::input::package::PackageError;
+
+    const DOCUMENT: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:body>
+  <w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>Quarterly Report</w:t></w:r></w:p>
+  <w:p><w:r><w:t xml:space="preserve">Revenue grew </w:t></w:r><w:del w:id="1" w:author="A"><w:r><w:delText>slightly </w:delText></w:r></w:del><w:ins w:id="2" w:author="A"><w:r><w:t xml:space="preserve">sharply </w:t></w:r></w:ins><w:r><w:t>this quarter</w:t></w:r><w:r><w:footnoteReference w:id="2"/></w:r><w:r><w:t>.</w:t></w:r></w:p>
+  <w:p><w:commentRangeStart w:id="0"/><w:r><w:t>Costs were flat.</w:t></w:r><w:commentRangeEnd w:id="0"/><w:r><w:commentReference w:id="0"/></w:r></w:p>
+  <w:p><w:pPr><w:pStyle w:val="Custom"/></w:pPr><w:r><w:t>Outlook</w:t></w:r></w:p>
+  <w:tbl><w:tr><w:tc><w:p><w:r><w:t>Cell text</w:t></w:r></w:p></w:tc></w:tr></w:tbl>
+  <w:p><w:r><w:fldChar w:fldCharType="begin"/></w:r><w:r><w:instrText> PAGE </w:instrText></w:r><w:r><w:t>Field result</w:t></w:r></w:p>
+</w:body></w:document>"#;
+
+    const STYLES: &str = r#"<w:styles>
+  <w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/></w:style>
+  <w:style w:type="paragraph" w:styleId="Custom"><w:name w:val="My Section"/><w:pPr><w:outlineLvl w:val="1"/></w:pPr></w:style>
+</w:styles>"#;
+
+    const FOOTNOTES: &str = r#"<w:footnotes>
+  <w:footnote w:type="separator" w:id="-1"><w:p><w:r><w:separator/></w:r></w:p></w:footnote>
+  <w:footnote w:id="2"><w:p><w:r><w:footnoteRef/></w:r><w:r><w:t xml:space="preserve"> Unaudited figures.</w:t></w:r></w:p></w:footnote>
+</w:footnotes>"#;
+
+    fn load_test_document() -> LoadedDocument {
+        let package = build_package(&[
+            ("word/document.xml", DOCUMENT),
+            ("word/styles.xml", STYLES),
+            ("word/footnotes.xml", FOOTNOTES),
+        ]);
+        load_package(
+            Package::from_reader(package).unwrap(),
+            "docx:test".to_string(),
+        )
+        .unwrap()
+    }
+
+    fn words(doc: &LoadedDocument) -> Vec<&str> {
+        doc.tokens
+            .iter()
+            .filter(|t| !t.text.is_empty())
+            .map(|t| t.text)
+            .collect()
+    }
+
+    #[test]
+    fn test_tracked_deletions_and_comments_excluded() {
+        let doc = load_test_document();
+        let words = words(&doc);
+
+        assert!(words.contains(&"sharply"));
+        assert!(!words.contains(&"slightly"));
+        assert!(words.contains(&"Costs"));
+        assert!(!words.contains(&"PAGE"));
+        assert!(words.contains(&"Field"));
+        assert!(words.contains(&"Cell"));
+    }
+
+    #[test]
+    fn test_headings_from_styles() {
+        let doc = load_test_document();
+        let sections: Vec<(&str, u8)> = doc
+            .sections
+            .iter()
+            .map(|s| (s.title.as_str(), s.level))
+            .collect();
+        assert_eq!(sections, vec![("Quarterly Report", 1), ("Outlook", 2)]);
+    }
+
+    #[test]
+    fn test_footnote_attached() {
+        let doc = load_test_document();
+        let quarter = doc.tokens.iter().find(|t| t.text == "quarter").unwrap();
+        assert_eq!(quarter.note, Some("Unaudited figures."));
+        assert_eq!(quarter.punctuation.as_str(), ".");
+    }
+
+    #[test]
+    fn test_heading_level_from_name() {
+        assert_eq!(heading_level_from_name("heading 3"), Some(3));
+        assert_eq!(heading_level_from_name("Heading2"), Some(2));
+        assert_eq!(heading_level_from_name("Title"), Some(1));
+        assert_eq!(heading_level_from_name("Normal"), None);
+    }
+
+    #[test]
+    fn test_docx_missing_document_part() {
+        let package = build_package(&[("word/styles.xml", STYLES)]);
+        let result = load_package(Package::from_reader(package).unwrap(), "docx:x".to_string());
+        assert!(matches!(
+            result,
+            Err(LoadError::Package(PackageError::Missing(_)))
+        ));
+    }
+
+    #[test]
+    fn test_docx_load_nonexistent_file() {
+        let result = load("/nonexistent/path/report.docx");
+        assert!(matches!(result, Err(LoadError::FileNotFound(_))));
+    }
+}
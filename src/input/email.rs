@@ -0,0 +1,613 @@
+//! Email loader for `.eml` messages and mbox archives
+//!
+//! Parses RFC 5322 messages, walks their MIME structure and decodes
+//! quoted-printable and base64 bodies in their declared charset. The
+//! `text/plain` alternative is preferred; HTML-only mail is reduced to text.
+//! Quoted reply chains, attribution lines and signatures are removed so that
+//! only what the sender wrote is read.
+//!
+//! Every message becomes a chapter headed by its sender and subject, so an
+//! mbox of newsletters reads one issue after another.
+
+use super::markup::{self, Element, Node};
+use super::structure::DocumentBuilder;
+use super::{encoding, LoadError, LoadedDocument, Metadata};
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+use std::path::Path;
+
+/// MIME nesting depth past which parts are ignored.
+const MAX_DEPTH: usize = 16;
+
+/// Elements of HTML mail that are never read: quoted replies and signatures
+/// are matched by class in [`is_quoted_html`].
+const SKIPPED_HTML_ELEMENTS: &[&str] = &["head", "script", "style", "title", "blockquote"];
+
+/// Class fragments of quoted text and signatures added by mail clients.
+const QUOTED_HTML_CLASSES: &[&str] = &[
+    "gmail_quote",
+    "gmail_signature",
+    "moz-cite-prefix",
+    "moz-signature",
+    "yahoo_quoted",
+    "protonmail_quote",
+];
+
+/// Lines after which everything is an older message in the thread.
+const REPLY_SEPARATORS: &[&str] = &[
+    "-----Original Message-----",
+    "-------- Original Message --------",
+];
+
+/// Base64 as mail clients write it: padding is often missing.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &base64::alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Load an `.eml` or mbox file.
+///
+/// Purpose: Reads newsletters and saved mail without headers, quotes and
+/// signatures.
+/// Big Picture: Enables `@digest.eml` and `@newsletters.mbox` in the
+/// command deck.
+/// Connections: Reduces HTML mail with input::markup and builds the
+/// document via input::structure.
+pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let data = std::fs::read(path)?;
+    load_bytes(&data, path)
+}
+
+/// Load a message or mbox already read into memory; `path` is only used to
+/// name the source.
+pub fn load_bytes(data: &[u8], path: &Path) -> Result<LoadedDocument, LoadError> {
+    let messages = split_mbox(data);
+    let mut builder = DocumentBuilder::new();
+    let mut metadata = Metadata::default();
+    let mut read = 0;
+
+    for (index, raw) in messages.iter().enumerate() {
+        let message = Part::parse(raw);
+        let paragraphs = message
+            .readable_text(0)
+            .map(|text| paragraphs(&strip_replies(&text)))
+            .unwrap_or_default();
+        if paragraphs.is_empty() {
+            continue;
+        }
+
+        let sender = message
+            .header("from")
+            .map(|from| sender_name(&decode_words(from)));
+        let subject = message
+            .header("subject")
+            .map(decode_words)
+            .filter(|s| !s.trim().is_empty());
+        let heading = match (&sender, &subject) {
+            (Some(sender), Some(subject)) => format!("{}: {}", sender, subject),
+            (Some(name), None) | (None, Some(name)) => name.clone(),
+            (None, None) => format!("Message {}", index + 1),
+        };
+        builder.heading(1, &heading);
+        for paragraph in &paragraphs {
+            builder.paragraph(paragraph);
+        }
+
+        if messages.len() == 1 {
+            metadata.title = subject;
+            metadata.author = sender;
+        }
+        read += 1;
+    }
+
+    if read == 0 {
+        return Err(LoadError::EmailParse(
+            "No readable message text found".to_string(),
+        ));
+    }
+    let mut doc = builder.build(format!("email:{}", path.display()), &[])?;
+    doc.metadata = metadata;
+    Ok(doc)
+}
+
+/// Splits an mbox into its messages at `From ` lines that follow a blank
+/// line, undoing the `>From ` escaping of body lines. Data that does not
+/// start with a `From ` line is a single message.
+fn split_mbox(data: &[u8]) -> Vec<Vec<u8>> {
+    if !data.starts_with(b"From ") {
+        return vec![data.to_vec()];
+    }
+
+    let mut messages = Vec::new();
+    let mut current: Option<Vec<u8>> = None;
+    let mut previous_blank = true;
+    for line in data.split_inclusive(|&b| b == b'\n') {
+        if previous_blank && line.starts_with(b"From ") {
+            messages.extend(current.take());
+            current = Some(Vec::new());
+            previous_blank = false;
+            continue;
+        }
+        previous_blank = line.trim_ascii().is_empty();
+
+        let quoted_from = line
+            .iter()
+            .position(|&b| b != b'>')
+            .is_some_and(|depth| depth > 0 && line[depth..].starts_with(b"From "));
+        if let Some(message) = current.as_mut() {
+            message.extend_from_slice(if quoted_from { &line[1..] } else { line });
+        }
+    }
+    messages.extend(current);
+    messages
+}
+
+/// A message or MIME part: unfolded headers and the raw body.
+struct Part<'a> {
+    headers: Vec<(String, String)>,
+    body: &'a [u8],
+}
+
+impl<'a> Part<'a> {
+    fn parse(raw: &'a [u8]) -> Self {
+        let mut headers: Vec<(String, String)> = Vec::new();
+        let mut offset = 0;
+
+        for line in raw.split_inclusive(|&b| b == b'\n') {
+            offset += line.len();
+            let text = String::from_utf8_lossy(line);
+            let text = text.trim_end_matches(['\r', '\n']);
+            if text.is_empty() {
+                return Self {
+                    headers,
+                    body: &raw[offset..],
+                };
+            }
+            if text.starts_with([' ', '\t']) {
+                // Folded continuation of the previous header
+                if let Some((_, value)) = headers.last_mut() {
+                    value.push(' ');
+                    value.push_str(text.trim());
+                }
+            } else if let Some((name, value)) = text.split_once(':') {
+                headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+            } else {
+                // Not a header: the part has no header block
+                return Self {
+                    headers: Vec::new(),
+                    body: raw,
+                };
+            }
+        }
+        Self { headers, body: &[] }
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Lowercase MIME type and its parameters; `text/plain` if undeclared.
+    fn content_type(&self) -> (String, Vec<(String, String)>) {
+        match self.header("content-type") {
+            Some(value) => parse_content_type(value),
+            None => ("text/plain".to_string(), Vec::new()),
+        }
+    }
+
+    fn is_attachment(&self) -> bool {
+        self.header("content-disposition")
+            .is_some_and(|d| d.trim_start().to_lowercase().starts_with("attachment"))
+    }
+
+    /// The body with its transfer encoding undone.
+    fn decoded_body(&self) -> Vec<u8> {
+        let transfer = self
+            .header("content-transfer-encoding")
+            .map(|t| t.trim().to_lowercase());
+        match transfer.as_deref() {
+            Some("base64") => {
+                let compact: Vec<u8> = self
+                    .body
+                    .iter()
+                    .copied()
+                    .filter(|b| !b.is_ascii_whitespace())
+                    .collect();
+                BASE64.decode(compact).unwrap_or_default()
+            }
+            Some("quoted-printable") => decode_quoted_printable(self.body),
+            _ => self.body.to_vec(),
+        }
+    }
+
+    /// The text to read from this part: the plain-text alternative if there
+    /// is one, otherwise HTML reduced to text. Parts of `multipart/mixed`
+    /// are joined; attachments are skipped.
+    fn readable_text(&self, depth: usize) -> Option<String> {
+        if depth > MAX_DEPTH || self.is_attachment() {
+            return None;
+        }
+        let (mime, params) = self.content_type();
+        let charset = param(&params, "charset");
+
+        if let Some(subtype) = mime.strip_prefix("multipart/") {
+            let boundary = param(&params, "boundary")?;
+            let parts: Vec<Vec<u8>> = split_multipart(self.body, boundary);
+            let parts: Vec<Part> = parts.iter().map(|raw| Part::parse(raw)).collect();
+            return match subtype {
+                "alternative" => {
+                    // Plain text first, then whatever else reads
+                    let plain = parts
+                        .iter()
+                        .filter(|p| p.content_type().0 == "text/plain")
+                        .find_map(|p| p.readable_text(depth + 1));
+                    plain.or_else(|| parts.iter().find_map(|p| p.readable_text(depth + 1)))
+                }
+                "mixed" => {
+                    let texts: Vec<String> = parts
+                        .iter()
+                        .filter_map(|p| p.readable_text(depth + 1))
+                        .collect();
+                    (!texts.is_empty()).then(|| texts.join("\n\n"))
+                }
+                // related, signed and others: the first readable part
+                _ => parts.iter().find_map(|p| p.readable_text(depth + 1)),
+            };
+        }
+
+        let text = match mime.as_str() {
+            "text/plain" => decode_charset(&self.decoded_body(), charset),
+            "text/html" => html_to_text(&decode_charset(&self.decoded_body(), charset)),
+            _ => return None,
+        };
+        (!text.trim().is_empty()).then_some(text)
+    }
+}
+
+/// Parses `text/plain; charset="utf-8"` into its type and parameters.
+fn parse_content_type(value: &str) -> (String, Vec<(String, String)>) {
+    let mut fields = value.split(';');
+    let mime = fields.next().unwrap_or("").trim().to_lowercase();
+    let params = fields
+        .filter_map(|field| {
+            let (name, value) = field.split_once('=')?;
+            Some((
+                name.trim().to_lowercase(),
+                value.trim().trim_matches('"').to_string(),
+            ))
+        })
+        .collect();
+    (mime, params)
+}
+
+fn param<'p>(params: &'p [(String, String)], name: &str) -> Option<&'p str> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Splits a multipart body into its parts at `--boundary` lines; the
+/// preamble and epilogue are dropped.
+fn split_multipart(body: &[u8], boundary: &str) -> Vec<Vec<u8>> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut current: Option<Vec<u8>> = None;
+
+    for line in body.split_inclusive(|&b| b == b'\n') {
+        let trimmed = line.trim_ascii_end();
+        if let Some(rest) = trimmed.strip_prefix(delimiter.as_bytes()) {
+            if rest.is_empty() || rest == b"--" {
+                if let Some(mut part) = current.take() {
+                    // The line break before a delimiter belongs to it
+                    while part.last().is_some_and(|&b| b == b'\n' || b == b'\r') {
+                        part.pop();
+                    }
+                    parts.push(part);
+                }
+                if rest == b"--" {
+                    break;
+                }
+                current = Some(Vec::new());
+                continue;
+            }
+        }
+        if let Some(part) = current.as_mut() {
+            part.extend_from_slice(line);
+        }
+    }
+    parts.extend(current);
+    parts
+}
+
+fn decode_quoted_printable(body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len());
+    let mut i = 0;
+    while i < body.len() {
+        if body[i] != b'=' {
+            out.push(body[i]);
+            i += 1;
+            continue;
+        }
+        let rest = &body[i + 1..];
+        if rest.starts_with(b"\r\n") {
+            i += 3;
+        } else if rest.starts_with(b"\n") {
+            i += 2;
+        } else if let Some(byte) = rest
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(b'=');
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Decodes bytes in a declared charset, detecting it when missing or
+/// unknown.
+fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    match charset.and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes())) {
+        Some(encoding) => encoding.decode(bytes).0.into_owned(),
+        None => encoding::decode(bytes, None)
+            .map(|decoded| decoded.text)
+            .unwrap_or_else(|| String::from_utf8_lossy(bytes).into_owned()),
+    }
+}
+
+/// Decodes RFC 2047 encoded words (`=?utf-8?Q?Caf=C3=A9?=`) in a header.
+///
+/// Whitespace between adjacent encoded words is dropped, as the encoder
+/// split one run of text across them.
+fn decode_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut after_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let Some((text, consumed)) = decode_word(&rest[start..]) else {
+            out.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            after_word = false;
+            continue;
+        };
+        let between = &rest[..start];
+        if !(after_word && between.trim().is_empty()) {
+            out.push_str(between);
+        }
+        out.push_str(&text);
+        rest = &rest[start + consumed..];
+        after_word = true;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Decodes one encoded word at the start of `s`; returns the text and the
+/// length consumed.
+fn decode_word(s: &str) -> Option<(String, usize)> {
+    let inner = s.strip_prefix("=?")?;
+    let (charset, inner) = inner.split_once('?')?;
+    let (method, inner) = inner.split_once('?')?;
+    let end = inner.find("?=")?;
+    let encoded = &inner[..end];
+    if encoded.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let bytes = match method.to_ascii_uppercase().as_str() {
+        "B" => BASE64.decode(encoded).ok()?,
+        "Q" => decode_quoted_printable(encoded.replace('_', " ").as_bytes()),
+        _ => return None,
+    };
+    let consumed = 2 + charset.len() + 1 + method.len() + 1 + end + 2;
+    // `charset*language` from RFC 2231
+    let charset = charset.split('*').next().unwrap_or(charset);
+    Some((decode_charset(&bytes, Some(charset)), consumed))
+}
+
+/// Display name of a `From` header, or the address if there is none.
+fn sender_name(from: &str) -> String {
+    let from = from.trim();
+    if let Some(open) = from.rfind('<') {
+        let name = from[..open].trim().trim_matches('"').trim();
+        if !name.is_empty() {
+            return name.to_string();
+        }
+        return from[open + 1..].trim_end_matches('>').trim().to_string();
+    }
+    // `jane@example.com (Jane Doe)`
+    if let (Some(open), true) = (from.find('('), from.ends_with(')')) {
+        let name = from[open + 1..from.len() - 1].trim();
+        if !name.is_empty() {
+            return name.to_string();
+        }
+    }
+    from.to_string()
+}
+
+/// Reduces an HTML body to text, one block per paragraph, dropping quoted
+/// replies and signatures.
+fn html_to_text(html: &str) -> String {
+    let root = markup::parse(html);
+    let mut out = String::new();
+    collect_html_text(&root, &mut out);
+    out
+}
+
+fn collect_html_text(e: &Element, out: &mut String) {
+    for child in &e.children {
+        match child {
+            Node::Text(text) => out.push_str(&text.replace(['\r', '\n'], " ")),
+            Node::Element(child) if is_quoted_html(child) => {}
+            Node::Element(child) if child.name == "br" => out.push('\n'),
+            Node::Element(child) if child.is_block() || child.name == "td" => {
+                out.push_str("\n\n");
+                collect_html_text(child, out);
+                out.push_str("\n\n");
+            }
+            Node::Element(child) => collect_html_text(child, out),
+        }
+    }
+}
+
+fn is_quoted_html(e: &Element) -> bool {
+    SKIPPED_HTML_ELEMENTS.contains(&e.name.as_str())
+        || QUOTED_HTML_CLASSES
+            .iter()
+            .any(|class| e.attr_contains("class", class))
+}
+
+/// Removes quoted lines, reply attributions, older messages and the
+/// signature from a message body.
+fn strip_replies(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(|l| l.trim_end_matches('\r')).collect();
+    let mut kept: Vec<&str> = Vec::with_capacity(lines.len());
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        // `-- ` opens the signature (RFC 3676); some clients trim the space
+        if *line == "-- " || trimmed == "--" || REPLY_SEPARATORS.contains(&trimmed) {
+            break;
+        }
+        if is_outlook_header(&lines[i..]) {
+            break;
+        }
+        if trimmed.starts_with('>') {
+            continue;
+        }
+        if is_attribution(trimmed, lines.get(i + 1).map(|l| l.trim())) {
+            continue;
+        }
+        if trimmed.ends_with("wrote:") && kept.last().is_some_and(|l| l.trim().starts_with("On ")) {
+            // Second line of a wrapped attribution
+            kept.pop();
+            continue;
+        }
+        kept.push(line);
+    }
+
+    // `Sent from my phone` trailers
+    while kept
+        .last()
+        .is_some_and(|l| l.trim().is_empty() || l.trim().starts_with("Sent from my "))
+    {
+        kept.pop();
+    }
+    kept.join("\n")
+}
+
+/// `On Mon, 3 Jun 2024, Jane <jane@example.com> wrote:` introducing a quote.
+fn is_attribution(line: &str, next: Option<&str>) -> bool {
+    line.starts_with("On ")
+        && line.ends_with("wrote:")
+        && next.is_none_or(|next| next.is_empty() || next.starts_with('>'))
+}
+
+/// Outlook's reply header: a `From:` line followed by `Sent:` or `Date:`.
+fn is_outlook_header(lines: &[&str]) -> bool {
+    let line = |i: usize| lines.get(i).map_or("", |l| l.trim());
+    line(0).starts_with("From: ")
+        && (line(1).starts_with("Sent: ") || line(1).starts_with("Date: "))
+}
+
+/// Splits text into paragraphs at blank lines.
+fn paragraphs(text: &str) -> Vec<String> {
+    text.split("\n\n")
+        .map(|p| p.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(doc: &LoadedDocument) -> String {
+        doc.tokens
+            .iter()
+            .map(|t| t.text)
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_mbox_messages_become_chapters() {
+        let doc = load("tests/test_files/digest.mbox").unwrap();
+        let titles: Vec<&str> = doc.sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            [
+                "Dana Reyes: Weekly café digest",
+                "Sam Lee: Re: Weekly digest"
+            ]
+        );
+        assert!(doc.source.starts_with("email:"));
+        // Several messages: no single title or author
+        assert_eq!(doc.metadata.title, None);
+    }
+
+    #[test]
+    fn test_plain_alternative_is_decoded_without_replies_or_signature() {
+        let doc = load("tests/test_files/digest.mbox").unwrap();
+        let text = words(&doc);
+        assert!(text.contains("Welcome to the café digest"), "{}", text);
+        assert!(text.contains("the new onboarding flow is live"), "{}", text);
+        assert!(text.contains("From the archive"), "{}", text);
+        for gone in ["HTML version", "numbers", "wrote", "Analytics"] {
+            assert!(!text.contains(gone), "{} in {}", gone, text);
+        }
+    }
+
+    #[test]
+    fn test_html_only_message_skips_quotes_and_attachments() {
+        let doc = load("tests/test_files/digest.mbox").unwrap();
+        let text = words(&doc);
+        assert!(text.contains("rerun the cohort split tonight"), "{}", text);
+        assert!(text.contains("Results by Friday"), "{}", text);
+        for gone in ["color", "Old quoted", "retained"] {
+            assert!(!text.contains(gone), "{} in {}", gone, text);
+        }
+    }
+
+    #[test]
+    fn test_single_message_sets_metadata() {
+        let doc = load("tests/test_files/newsletter.eml").unwrap();
+        assert_eq!(doc.metadata.title.as_deref(), Some("Field notes"));
+        assert_eq!(doc.metadata.author.as_deref(), Some("José M"));
+        let text = words(&doc);
+        assert!(text.ends_with("written on the road"), "{}", text);
+    }
+
+    #[test]
+    fn test_decoding_helpers() {
+        assert_eq!(
+            decode_quoted_printable(b"a=3Db =\r\nc=E9"),
+            b"a=b c\xe9".to_vec()
+        );
+        assert_eq!(
+            decode_words("=?UTF-8?B?SGVsbG8=?= =?utf-8?q?_w=C3=B6rld?= today"),
+            "Hello wörld today"
+        );
+        assert_eq!(sender_name("\"Doe, Jane\" <jane@example.com>"), "Doe, Jane");
+        assert_eq!(sender_name("<jane@example.com>"), "jane@example.com");
+        assert_eq!(
+            strip_replies("Agreed.\n\nOn Mon, Jane\n<j@example.com> wrote:\n> old"),
+            "Agreed."
+        );
+    }
+}
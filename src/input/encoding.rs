@@ -0,0 +1,180 @@
+//! Character encoding detection for plain-text inputs
+//!
+//! Text files arrive in whatever encoding the writer's system used: Latin-1
+//! and Windows-1252 from older Windows tools, UTF-16 from Notepad, Shift-JIS
+//! or GBK from East Asian sites. Bytes are decoded to UTF-8 by trying, in
+//! order, an explicit override, a byte order mark, strict UTF-8, BOM-less
+//! UTF-16, and finally chardetng's statistical guess.
+
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+/// Bytes inspected when looking for the zero bytes of BOM-less UTF-16.
+const UTF16_SAMPLE_BYTES: usize = 4096;
+
+/// Share of even or odd bytes that must be zero to call a file UTF-16.
+const UTF16_ZERO_SHARE: f64 = 0.3;
+
+/// Text decoded to UTF-8, with the encoding it was read as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    pub text: String,
+    pub encoding: &'static Encoding,
+}
+
+/// Decodes `bytes`, using `forced` if given and detecting the encoding
+/// otherwise.
+///
+/// Returns `None` only when detection fails: the bytes contain zero bytes
+/// that are not UTF-16 (a binary file), or are malformed in the guessed
+/// encoding. A forced encoding never fails; malformed sequences become U+FFFD.
+pub fn decode(bytes: &[u8], forced: Option<&'static Encoding>) -> Option<Decoded> {
+    if let Some(encoding) = forced {
+        let (text, _) = encoding.decode_with_bom_removal(bytes);
+        return Some(Decoded {
+            text: text.into_owned(),
+            encoding,
+        });
+    }
+
+    let (encoding, body) = match Encoding::for_bom(bytes) {
+        Some((encoding, bom_length)) => (encoding, &bytes[bom_length..]),
+        None => (detect(bytes)?, bytes),
+    };
+
+    let (text, had_errors) = encoding.decode_without_bom_handling(body);
+    if had_errors {
+        return None;
+    }
+    Some(Decoded {
+        text: text.into_owned(),
+        encoding,
+    })
+}
+
+/// Guesses the encoding of BOM-less bytes.
+fn detect(bytes: &[u8]) -> Option<&'static Encoding> {
+    if std::str::from_utf8(bytes).is_ok() && !bytes.contains(&0) {
+        return Some(UTF_8);
+    }
+    if let Some(encoding) = detect_utf16(bytes) {
+        return Some(encoding);
+    }
+    if bytes.contains(&0) {
+        return None;
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    Some(detector.guess(None, true))
+}
+
+/// Recognises BOM-less UTF-16 by its zero high bytes, which mostly-ASCII
+/// (or mostly-Latin) text has in every other position.
+fn detect_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let sample = &bytes[..bytes.len().min(UTF16_SAMPLE_BYTES) & !1];
+    if sample.is_empty() {
+        return None;
+    }
+
+    let pairs = (sample.len() / 2) as f64;
+    let zero_share = |offset: usize| {
+        sample
+            .iter()
+            .skip(offset)
+            .step_by(2)
+            .filter(|&&b| b == 0)
+            .count() as f64
+            / pairs
+    };
+    let (even, odd) = (zero_share(0), zero_share(1));
+
+    if odd >= UTF16_ZERO_SHARE && even < UTF16_ZERO_SHARE / 3.0 {
+        Some(UTF_16LE)
+    } else if even >= UTF16_ZERO_SHARE && odd < UTF16_ZERO_SHARE / 3.0 {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{SHIFT_JIS, WINDOWS_1251, WINDOWS_1252};
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn test_utf8_passes_through() {
+        let decoded = decode("naïve café".as_bytes(), None).unwrap();
+        assert_eq!(decoded.text, "naïve café");
+        assert_eq!(decoded.encoding, UTF_8);
+    }
+
+    #[test]
+    fn test_boms() {
+        let decoded = decode(b"\xEF\xBB\xBFHello", None).unwrap();
+        assert_eq!((decoded.text.as_str(), decoded.encoding), ("Hello", UTF_8));
+
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(utf16le("Grüße"));
+        let decoded = decode(&bytes, None).unwrap();
+        assert_eq!(
+            (decoded.text.as_str(), decoded.encoding),
+            ("Grüße", UTF_16LE)
+        );
+    }
+
+    #[test]
+    fn test_bomless_utf16() {
+        let decoded = decode(&utf16le("Plain old Notepad text.\r\n"), None).unwrap();
+        assert_eq!(decoded.text, "Plain old Notepad text.\r\n");
+        assert_eq!(decoded.encoding, UTF_16LE);
+
+        let be: Vec<u8> = "Big endian"
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect();
+        assert_eq!(decode(&be, None).unwrap().encoding, UTF_16BE);
+    }
+
+    #[test]
+    fn test_detects_legacy_encodings() {
+        let text = "Le cœur a ses raisons que la raison ne connaît point. \
+            Nous le savons très bien, déjà.";
+        let (bytes, _, _) = WINDOWS_1252.encode(text);
+        let decoded = decode(&bytes, None).unwrap();
+        assert_eq!(decoded.encoding, WINDOWS_1252);
+        assert_eq!(decoded.text, text);
+
+        let text = "Все счастливые семьи похожи друг на друга, \
+            каждая несчастливая семья несчастлива по-своему.";
+        let (bytes, _, _) = WINDOWS_1251.encode(text);
+        assert_eq!(decode(&bytes, None).unwrap().text, text);
+
+        let text = "吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。";
+        let (bytes, _, _) = SHIFT_JIS.encode(text);
+        let decoded = decode(&bytes, None).unwrap();
+        assert_eq!(decoded.encoding, SHIFT_JIS);
+        assert_eq!(decoded.text, text);
+    }
+
+    #[test]
+    fn test_forced_encoding() {
+        // Valid UTF-8, but the user says it is Windows-1252
+        let decoded = decode("é".as_bytes(), Some(WINDOWS_1252)).unwrap();
+        assert_eq!(decoded.text, "Ã©");
+        assert_eq!(decoded.encoding, WINDOWS_1252);
+    }
+
+    #[test]
+    fn test_binary_fails() {
+        assert_eq!(
+            decode(b"\x7fELF\x02\x01\x01\x00\x00\x00\x00\x00\x80\xff", None),
+            None
+        );
+    }
+}
@@ -1,22 +1,68 @@
-use super::{LoadError, LoadedDocument};
-use crate::engine::tokenize_text;
-use std::path::Path;
+use super::background::{ProgressUnit, Reporter};
+use super::gutenberg;
+use super::markup::{self, Element, Node};
+use super::notes::note_ref_marker;
+use super::structure::tokenize_document;
+use super::{LoadError, LoadOptions, LoadRange, LoadedDocument, Metadata, RangeUnit};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 /// Load text from EPUB file using epub crate.
 ///
 /// Purpose: Provides EPUB file content as input source per PRD Section 2.2.
 /// Big Picture: Enables @filename.epub command in REPL to load EPUB content.
 /// PRD Reference: Section 2.2 (EPUB support), Section 7.1 (@filename command)
-/// Connections: Depends on engine::tokenize_text() for tokenization.
+/// Connections: Depends on structure::tokenize_document() for tokenization in
+/// the book's language and input::notes for footnote attachment.
 pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    load_with_options(path, &LoadOptions::default())
+}
+
+/// Load an EPUB, reading only the chapters in `options.range` if one is given.
+///
+/// Chapters are the top-level entries of the table of contents, falling back
+/// to spine items for books without one. Chapters outside the range are only
+/// read if the selected chapters link notes into them, so endnote references
+/// still resolve.
+pub fn load_with_options(path: &str, options: &LoadOptions) -> Result<LoadedDocument, LoadError> {
     let path = Path::new(path);
 
     if !path.exists() {
         return Err(LoadError::FileNotFound(path.to_path_buf()));
     }
 
-    let mut doc = epub::doc::EpubDoc::new(path).map_err(|e| LoadError::EpubParse(e.to_string()))?;
+    let doc = epub::doc::EpubDoc::new(path)?;
+    load_doc(doc, path, options, &Reporter::silent())
+}
+
+/// Load an EPUB already read into memory; `path` is only used to name the source.
+pub fn load_bytes(
+    data: &[u8],
+    path: &Path,
+    options: &LoadOptions,
+) -> Result<LoadedDocument, LoadError> {
+    load_bytes_reporting(data, path, options, &Reporter::silent())
+}
+
+/// [`load_bytes`] reporting each chapter read to `reporter`, which also
+/// receives the chapters read so far as partial documents.
+pub fn load_bytes_reporting(
+    data: &[u8],
+    path: &Path,
+    options: &LoadOptions,
+    reporter: &Reporter,
+) -> Result<LoadedDocument, LoadError> {
+    let doc = epub::doc::EpubDoc::from_reader(std::io::Cursor::new(data))?;
+    load_doc(doc, path, options, reporter)
+}
 
+fn load_doc<R: std::io::Read + std::io::Seek>(
+    mut doc: epub::doc::EpubDoc<R>,
+    path: &Path,
+    options: &LoadOptions,
+    reporter: &Reporter,
+) -> Result<LoadedDocument, LoadError> {
     let num_chapters = doc.get_num_chapters();
 
     if num_chapters == 0 {
@@ -25,23 +71,80 @@ pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
         ));
     }
 
-    // Extract all chapter content and concatenate with double newlines
-    let mut content = String::new();
+    let read = match options.range {
+        Some(range) => spine_range(&doc, range)?,
+        None => 0..num_chapters,
+    };
+
+    let source = match options.range {
+        Some(range) => format!("epub:{}#{}", path.display(), range),
+        None => format!("epub:{}", path.display()),
+    };
 
-    for chapter_idx in 0..num_chapters {
-        if !doc.set_current_chapter(chapter_idx) {
+    let metadata = Metadata {
+        title: metadata_value(&doc, "title"),
+        author: metadata_value(&doc, "creator"),
+        language: metadata_value(&doc, "language"),
+        ..Metadata::default()
+    };
+
+    // Chapters outside `read` stay empty unless they hold notes, which are
+    // only resolved once every chapter read is known.
+    let mut chapters = vec![String::new(); num_chapters];
+    let mut paths = vec![None; num_chapters];
+    for (done, chapter_idx) in read.clone().enumerate() {
+        (chapters[chapter_idx], paths[chapter_idx]) = read_chapter(&mut doc, chapter_idx);
+
+        reporter.progress(done + 1, read.len(), ProgressUnit::Chapters);
+        if reporter.partial_due(done + 1, read.len()) {
+            // Notes in chapters not read yet stay unresolved until the end
+            if let Ok(partial) = build_document(
+                &chapters,
+                &paths,
+                read.start..chapter_idx + 1,
+                options,
+                source.clone(),
+                metadata.clone(),
+            ) {
+                reporter.partial(partial);
+            }
+        }
+        reporter.check_cancelled()?;
+    }
+
+    // Endnotes usually live in their own file
+    let skipped = read.len() < num_chapters;
+    let files = skipped.then(|| note_files(&chapters, &paths, read.clone()));
+    for file in files.into_iter().flatten() {
+        let Some(chapter_idx) = doc.resource_uri_to_chapter(&file) else {
             continue;
+        };
+        if chapters[chapter_idx].is_empty() {
+            (chapters[chapter_idx], paths[chapter_idx]) = read_chapter(&mut doc, chapter_idx);
+            reporter.check_cancelled()?;
         }
+    }
 
-        if let Some((chapter_content, _mime)) = doc.get_current_str() {
-            if !chapter_content.is_empty() {
-                if !content.is_empty() {
-                    content.push_str("\n\n");
-                }
-                // Extract plain text from HTML content
-                let plain_text = extract_plain_text(&chapter_content);
-                content.push_str(&plain_text);
-            }
+    build_document(&chapters, &paths, read, options, source, metadata)
+}
+
+/// Turns the chapters in `read` into a document, with notes resolved from
+/// any of `chapters` and Gutenberg boilerplate removed unless kept.
+fn build_document(
+    chapters: &[String],
+    paths: &[Option<PathBuf>],
+    read: Range<usize>,
+    options: &LoadOptions,
+    source: String,
+    metadata: Metadata,
+) -> Result<LoadedDocument, LoadError> {
+    let (mut content, notes) = extract_chapters(chapters, paths, read);
+
+    let mut notices = Vec::new();
+    if !options.keep_boilerplate {
+        if let Some(trimmed) = gutenberg::strip_boilerplate(&content) {
+            content = trimmed.text;
+            notices.push(trimmed.notice);
         }
     }
 
@@ -52,39 +155,366 @@ pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
     }
 
     Ok(LoadedDocument {
-        tokens: tokenize_text(&content),
-        source: format!("epub:{}", path.display()),
+        tokens: tokenize_document(&content, metadata.language.as_deref(), &notes)?,
+        source,
+        sections: Vec::new(),
+        metadata,
+        cues: Vec::new(),
+        notices,
     })
 }
 
-/// Extract plain text from HTML content by removing tags.
-fn extract_plain_text(html: &str) -> String {
-    let mut result = String::new();
-    let mut in_tag = false;
+/// Returns a trimmed, non-empty Dublin Core value from the OPF metadata.
+fn metadata_value<R: std::io::Read + std::io::Seek>(
+    doc: &epub::doc::EpubDoc<R>,
+    property: &str,
+) -> Option<String> {
+    doc.mdata(property)
+        .map(|item| item.value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Maps a chapter range to the spine items it covers.
+fn spine_range<R: std::io::Read + std::io::Seek>(
+    doc: &epub::doc::EpubDoc<R>,
+    range: LoadRange,
+) -> Result<Range<usize>, LoadError> {
+    if range.unit != RangeUnit::Chapter {
+        return Err(LoadError::InvalidRange(format!(
+            "{} selects pages; use #ch<first>-<last> for EPUB chapters",
+            range
+        )));
+    }
+
+    let num_chapters = doc.get_num_chapters();
+    let toc_starts: Vec<Option<usize>> = doc
+        .toc
+        .iter()
+        .map(|point| {
+            let content = point.content.to_string_lossy();
+            let file = content.split('#').next().unwrap_or_default();
+            doc.resource_uri_to_chapter(&PathBuf::from(file))
+        })
+        .collect();
+    let starts = chapter_starts(&toc_starts, num_chapters);
+
+    let selected = range.indices(starts.len())?;
+    let start = starts[selected.start];
+    let end = starts.get(selected.end).copied().unwrap_or(num_chapters);
+    Ok(start..end)
+}
+
+/// First spine index of each chapter, given the spine index each top-level
+/// table of contents entry points to.
+///
+/// Entries that do not resolve or point back into an earlier chapter are
+/// skipped. Without a usable table of contents every spine item is a chapter.
+fn chapter_starts(toc_starts: &[Option<usize>], num_chapters: usize) -> Vec<usize> {
+    let mut starts: Vec<usize> = Vec::new();
+    for &start in toc_starts.iter().flatten() {
+        if starts.last().is_none_or(|&last| start > last) {
+            starts.push(start);
+        }
+    }
+    if starts.is_empty() {
+        starts = (0..num_chapters).collect();
+    }
+    starts
+}
+
+/// Decompresses a spine item, returning its XHTML and its path in the
+/// package; a missing item reads as empty.
+fn read_chapter<R: std::io::Read + std::io::Seek>(
+    doc: &mut epub::doc::EpubDoc<R>,
+    chapter_idx: usize,
+) -> (String, Option<PathBuf>) {
+    if !doc.set_current_chapter(chapter_idx) {
+        return (String::new(), None);
+    }
+    let html = doc.get_current_str().map(|(html, _mime)| html);
+    (html.unwrap_or_default(), doc.get_current_path())
+}
+
+/// Package paths of other files that note references in the chapters in
+/// `read` point into.
+///
+/// A link counts as a note reference if it is marked as one or, as in EPUB 2
+/// books, its text is a note label such as `1` or `*`.
+fn note_files(
+    chapters: &[String],
+    paths: &[Option<PathBuf>],
+    read: Range<usize>,
+) -> BTreeSet<PathBuf> {
+    let mut files = BTreeSet::new();
+    for chapter_idx in read {
+        let Some(base) = &paths[chapter_idx] else {
+            continue;
+        };
+        markup::parse(&chapters[chapter_idx]).walk(&mut |e| {
+            if e.name != "a" || !(is_note_ref(e) || is_note_label(&e.text())) {
+                return;
+            }
+            let file = e.attr("href").and_then(|href| href.split('#').next());
+            if let Some(file) = file.filter(|file| !file.is_empty()) {
+                files.insert(resolve_href(base, file));
+            }
+        });
+    }
+    files
+}
+
+/// Resolves a link relative to the package path of the file it is in.
+fn resolve_href(base: &Path, href: &str) -> PathBuf {
+    let mut path = base.parent().map(Path::to_path_buf).unwrap_or_default();
+    for part in href.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                path.pop();
+            }
+            part => path.push(part),
+        }
+    }
+    path
+}
+
+/// Converts chapter XHTML to plain text with note references replaced by
+/// markers, returning the text and the note bodies the markers index into.
+///
+/// Only chapters in `read` contribute text; the others are searched for note
+/// bodies only. Chapters are separated by blank lines. Note bodies (EPUB 3 `footnote`,
+/// `endnote` and `rearnote` asides, DPUB-ARIA roles, and EPUB 2 style bodies
+/// targeted from superscript number links) are removed from the text.
+///
+/// Bodies are keyed by the package path of their chapter as well as their id,
+/// since many books reuse ids such as `fn1` in every chapter.
+fn extract_chapters(
+    chapters: &[String],
+    paths: &[Option<PathBuf>],
+    read: Range<usize>,
+) -> (String, Vec<String>) {
+    let mut trees: Vec<Element> = chapters.iter().map(|html| markup::parse(html)).collect();
+    let paths: Vec<PathBuf> = (0..chapters.len())
+        .map(|idx| paths.get(idx).cloned().flatten().unwrap_or_default())
+        .collect();
+
+    // EPUB 2 books have no semantic markup: treat the targets of superscript
+    // number links as note bodies.
+    let mut untyped_targets = HashSet::new();
+    for (tree, path) in trees.iter().zip(&paths) {
+        tree.walk(&mut |e| {
+            if e.name == "sup" {
+                if let Some(anchor) = e.find(&|a| a.name == "a") {
+                    if is_note_label(&anchor.text()) {
+                        untyped_targets.extend(link_target(anchor, path));
+                    }
+                }
+            }
+        });
+    }
+
+    let mut bodies: HashMap<(PathBuf, String), String> = HashMap::new();
+    for (tree, path) in trees.iter_mut().zip(&paths) {
+        tree.rewrite(&mut |e| {
+            if is_note_container(e) {
+                e.walk(&mut |child| {
+                    if let Some(id) = child.attr("id") {
+                        if child.is_block() {
+                            bodies.insert((path.clone(), id.to_string()), note_text(child));
+                        }
+                    }
+                });
+                return Some(vec![]);
+            }
+            if let Some(id) = note_body_id(e, path, &untyped_targets) {
+                bodies.insert((path.clone(), id), note_text(e));
+                return Some(vec![]);
+            }
+            None
+        });
+    }
+
+    let mut notes = Vec::new();
+    let mut content = String::new();
+    for (tree, path) in trees[read.clone()].iter_mut().zip(&paths[read]) {
+        tree.rewrite(&mut |e| {
+            if e.name != "a" {
+                return None;
+            }
+            let body = link_target(e, path).and_then(|target| bodies.get(&target));
+            match body {
+                Some(body) => {
+                    notes.push(body.clone());
+                    Some(vec![Node::Text(note_ref_marker(notes.len() - 1))])
+                }
+                // A reference whose body could not be found is still noise.
+                None if is_note_ref(e) => Some(vec![]),
+                None => None,
+            }
+        });
+
+        let text = tree.to_plain_text();
+        if !text.is_empty() {
+            if !content.is_empty() {
+                content.push_str("\n\n");
+            }
+            content.push_str(&text);
+        }
+    }
+
+    (content, notes)
+}
+
+/// Returns true for elements marked as a note reference.
+fn is_note_ref(e: &Element) -> bool {
+    e.attr_contains("epub:type", "noteref") || e.attr_contains("role", "doc-noteref")
+}
+
+/// Returns true for sections that hold a list of notes.
+fn is_note_container(e: &Element) -> bool {
+    ["footnotes", "endnotes", "rearnotes"]
+        .iter()
+        .any(|kind| e.attr_contains("epub:type", kind))
+        || e.attr_contains("role", "doc-endnotes")
+}
+
+/// Returns the id of a note body element in the chapter at `path`, if this
+/// element is one.
+fn note_body_id(
+    e: &Element,
+    path: &Path,
+    untyped_targets: &HashSet<(PathBuf, String)>,
+) -> Option<String> {
+    let is_target = |id: &str| untyped_targets.contains(&(path.to_path_buf(), id.to_string()));
+    let typed = ["footnote", "endnote", "rearnote", "note"]
+        .iter()
+        .any(|kind| e.attr_contains("epub:type", kind))
+        || e.attr_contains("role", "doc-footnote")
+        || e.attr_contains("role", "doc-endnote");
 
-    for c in html.chars() {
-        if c == '<' {
-            in_tag = true;
-        } else if c == '>' {
-            in_tag = false;
-        } else if !in_tag {
-            result.push(c);
+    if let Some(id) = e.attr("id") {
+        if typed || (e.is_block() && is_target(id)) {
+            return Some(id.to_string());
         }
     }
 
-    // Clean up extra whitespace
-    result
-        .lines()
-        .map(|line| line.trim())
-        .filter(|line| !line.is_empty())
-        .collect::<Vec<_>>()
-        .join("\n")
+    // EPUB 2 often puts the target id on the back-link inside the note paragraph.
+    if e.is_block() {
+        for child in &e.children {
+            if let Node::Element(child) = child {
+                if let Some(id) = child.attr("id") {
+                    if is_target(id) && is_note_label(&child.text()) {
+                        return Some(id.to_string());
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Returns the package path and fragment identifier a link points to,
+/// resolving its href against the path of the chapter it is in.
+fn link_target(e: &Element, base: &Path) -> Option<(PathBuf, String)> {
+    let (file, fragment) = e.attr("href")?.split_once('#')?;
+    if fragment.is_empty() {
+        return None;
+    }
+    let path = if file.is_empty() {
+        base.to_path_buf()
+    } else {
+        resolve_href(base, file)
+    };
+    Some((path, fragment.to_string()))
+}
+
+/// Returns true for short reference labels such as `1`, `[12]`, `*` or `↩`.
+fn is_note_label(text: &str) -> bool {
+    let label = text
+        .trim()
+        .trim_matches(|c| matches!(c, '[' | ']' | '(' | ')' | '.'));
+    if label.is_empty() {
+        return false;
+    }
+    let is_number = label.len() <= 3 && label.chars().all(|c| c.is_ascii_digit());
+    let is_symbol = label.chars().count() == 1
+        && matches!(
+            label.chars().next(),
+            Some('*' | '†' | '‡' | '§' | '¶' | '↩' | '↑' | '^')
+        );
+    is_number || is_symbol
+}
+
+/// Note body text without its back-link label.
+fn note_text(e: &Element) -> String {
+    let mut body = e.clone();
+    body.rewrite(&mut |child| {
+        let back_link = child.name == "a"
+            && (is_note_label(&child.text())
+                || child.attr_contains("role", "doc-backlink")
+                || child.attr_contains("epub:type", "backlink"));
+        back_link.then(Vec::new)
+    });
+    body.text()
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use crate::input::package::test_support::build_package;
+
+    /// Builds an EPUB whose spine is `chapters`, each `(file, body)` in OEBPS.
+    pub fn build_epub(chapters: &[(&str, &str)]) -> Vec<u8> {
+        let container = r#"<?xml version="1.0"?>
+            <container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+            <rootfiles><rootfile full-path="OEBPS/content.opf"
+                media-type="application/oebps-package+xml"/></rootfiles></container>"#;
+        let items: String = chapters
+            .iter()
+            .enumerate()
+            .map(|(i, (file, _))| {
+                format!(
+                    r#"<item id="c{}" href="{}" media-type="application/xhtml+xml"/>"#,
+                    i, file
+                )
+            })
+            .collect();
+        let spine: String = (0..chapters.len())
+            .map(|i| format!(r#"<itemref idref="c{}"/>"#, i))
+            .collect();
+        let opf = format!(
+            r#"<?xml version="1.0"?>
+            <package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+            <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>T</dc:title></metadata>
+            <manifest>{}</manifest><spine>{}</spine></package>"#,
+            items, spine
+        );
+        let pages: Vec<(String, String)> = chapters
+            .iter()
+            .map(|(file, body)| {
+                let html = format!("<html><body>{}</body></html>", body);
+                (format!("OEBPS/{}", file), html)
+            })
+            .collect();
+        let mut parts = vec![
+            ("mimetype", "application/epub+zip"),
+            ("META-INF/container.xml", container),
+            ("OEBPS/content.opf", opf.as_str()),
+        ];
+        parts.extend(
+            pages
+                .iter()
+                .map(|(name, html)| (name.as_str(), html.as_str())),
+        );
+        build_package(&parts).into_inner()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::test_support::build_epub;
     use super::*;
-    use crate::engine::Token;
+    use crate::engine::{tokenize_text, Token};
+    use crate::input::notes::attach_notes;
 
     /// Test that load returns FileNotFound for non-existent files.
     #[test]
@@ -97,14 +527,13 @@ mod tests {
     /// Test that LoadedDocument has correct source field for EPUB.
     #[test]
     fn test_loaded_document_source_epub() {
-        let doc = LoadedDocument {
-            tokens: vec![Token {
-                text: "test".to_string(),
-                punctuation: vec![],
+        let doc = LoadedDocument::new(
+            vec![Token {
                 is_sentence_start: true,
+                ..Token::new("test")
             }],
-            source: "epub:/path/to/book.epub".to_string(),
-        };
+            "epub:/path/to/book.epub",
+        );
 
         assert!(doc.source.starts_with("epub:"));
     }
@@ -112,17 +541,16 @@ mod tests {
     /// Test that LoadedDocument tokens preserve sentence boundaries from EPUB text.
     #[test]
     fn test_loaded_document_sentence_boundaries() {
-        let doc = LoadedDocument {
-            tokens: tokenize_text("Chapter One. This is the first sentence. And another! Yes?"),
-            source: "epub:test.epub".to_string(),
-        };
+        let doc = LoadedDocument::new(
+            tokenize_text("Chapter One. This is the first sentence. And another! Yes?"),
+            "epub:test.epub",
+        );
 
         // Verify sentence boundaries are detected
         assert!(doc.tokens.len() >= 4);
 
         // Find tokens that should be sentence starts
-        let sentence_starts: Vec<&Token> =
-            doc.tokens.iter().filter(|t| t.is_sentence_start).collect();
+        let sentence_starts: Vec<_> = doc.tokens.iter().filter(|t| t.is_sentence_start).collect();
 
         // Should have multiple sentence starts (first token + after each terminator)
         assert!(
@@ -132,7 +560,7 @@ mod tests {
         );
 
         // First token should always be sentence start
-        assert!(doc.tokens[0].is_sentence_start);
+        assert!(doc.tokens.get(0).unwrap().is_sentence_start);
     }
 
     /// Test EPUB-specific error type.
@@ -142,13 +570,194 @@ mod tests {
         assert!(matches!(err, LoadError::EpubParse(msg) if msg.contains("Invalid")));
     }
 
+    /// Extracts `chapters`, each `(file, html)` in the package root.
+    fn extract(chapters: &[(&str, &str)], read: Range<usize>) -> (String, Vec<String>) {
+        let html: Vec<String> = chapters.iter().map(|(_, html)| html.to_string()).collect();
+        let paths: Vec<Option<PathBuf>> = chapters
+            .iter()
+            .map(|(file, _)| Some(PathBuf::from(file)))
+            .collect();
+        extract_chapters(&html, &paths, read)
+    }
+
     /// Test plain text extraction from HTML.
     #[test]
     fn test_extract_plain_text() {
         let html = "<html><body><p>Hello World</p></body></html>";
-        let result = extract_plain_text(html);
+        let (result, _notes) = extract(&[("c.xhtml", html)], 0..1);
         assert!(result.contains("Hello World"));
         assert!(!result.contains("<html>"));
         assert!(!result.contains("<p>"));
     }
+
+    /// Test that EPUB 3 footnote asides are removed and attached to the referencing word.
+    #[test]
+    fn test_extract_chapters_epub3_footnote() {
+        let chapter = r##"<html><body>
+            <p>The claim<a epub:type="noteref" href="#fn1">1</a> stands. Next sentence.</p>
+            <aside epub:type="footnote" id="fn1"><p><a href="#ref1">1</a> See the appendix.</p></aside>
+            </body></html>"##;
+        let (content, notes) = extract(&[("c.xhtml", chapter)], 0..1);
+
+        assert_eq!(notes, vec!["See the appendix.".to_string()]);
+        assert!(!content.contains("appendix"));
+
+        let tokens = attach_notes(tokenize_text(&content), &notes);
+        let claim = tokens.iter().find(|t| t.text == "claim").unwrap();
+        assert_eq!(claim.note.as_deref(), Some("See the appendix."));
+        assert!(!tokens.iter().any(|t| t.text == "1"));
+    }
+
+    /// Test that endnotes in a separate chapter resolve and are not read.
+    #[test]
+    fn test_extract_chapters_endnotes_in_other_chapter() {
+        let text = r##"<p>Word<sup><a role="doc-noteref" href="notes.xhtml#en1">1</a></sup>.</p>"##;
+        let endnotes = r##"<section epub:type="endnotes"><ol>
+            <li id="en1"><p>Endnote body.</p></li></ol></section>"##;
+        let (content, notes) = extract(&[("c.xhtml", text), ("notes.xhtml", endnotes)], 0..2);
+
+        assert_eq!(notes, vec!["Endnote body.".to_string()]);
+        let tokens = attach_notes(tokenize_text(&content), &notes);
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].text, "Word");
+        assert_eq!(tokens[0].punctuation, vec!['.']);
+        assert!(tokens[0].note.is_some());
+    }
+
+    /// Test EPUB 2 style notes targeted by superscript number links.
+    #[test]
+    fn test_extract_chapters_epub2_superscript_notes() {
+        let chapter = r##"<p>Body text<sup><a href="#n1" id="r1">1</a></sup> continues.</p>
+            <p class="note"><a id="n1" href="#r1">1</a> An untyped note.</p>"##;
+        let (content, notes) = extract(&[("c.xhtml", chapter)], 0..1);
+
+        assert_eq!(notes, vec!["An untyped note.".to_string()]);
+        assert!(!content.contains("untyped"));
+    }
+
+    /// Test that references without a resolvable body are dropped from the text.
+    #[test]
+    fn test_extract_chapters_drops_dangling_noteref() {
+        let chapter = r##"<p>Text<a epub:type="noteref" href="#missing">9</a> here.</p>"##;
+        let (content, notes) = extract(&[("c.xhtml", chapter)], 0..1);
+
+        assert!(notes.is_empty());
+        assert_eq!(content, "Text here.");
+    }
+
+    /// Test that only chapters in the read range contribute text while notes
+    /// from other chapters still resolve.
+    #[test]
+    fn test_extract_chapters_read_range() {
+        let one = "<p>Chapter one text.</p>";
+        let two = r##"<p>Chapter two<a epub:type="noteref" href="notes.xhtml#en1">1</a>.</p>"##;
+        let notes_file = r##"<aside epub:type="endnote" id="en1"><p>Late note.</p></aside>"##;
+        let (content, notes) = extract(
+            &[
+                ("one.xhtml", one),
+                ("two.xhtml", two),
+                ("notes.xhtml", notes_file),
+            ],
+            1..2,
+        );
+
+        assert!(!content.contains("one"));
+        assert!(content.starts_with("Chapter two"));
+        assert_eq!(notes, vec!["Late note.".to_string()]);
+    }
+
+    /// Test that chapters reusing a note id each get their own note.
+    #[test]
+    fn test_extract_chapters_reused_note_ids() {
+        let chapter = |word: &str, note: &str| {
+            format!(
+                r##"<p>{}<a epub:type="noteref" href="#fn1">1</a>.</p>
+                <aside epub:type="footnote" id="fn1"><p>{}</p></aside>"##,
+                word, note
+            )
+        };
+        let one = chapter("First", "Note one.");
+        let two = chapter("Second", "Note two.");
+        let endnotes = r##"<p>Third<a epub:type="noteref" href="notes.xhtml#fn1">1</a>.</p>"##;
+        let notes_file = r##"<aside epub:type="endnote" id="fn1"><p>Note three.</p></aside>"##;
+        let (content, notes) = extract(
+            &[
+                ("text/one.xhtml", &one),
+                ("text/two.xhtml", &two),
+                ("text/three.xhtml", endnotes),
+                ("text/notes.xhtml", notes_file),
+            ],
+            0..3,
+        );
+
+        let tokens = attach_notes(tokenize_text(&content), &notes);
+        let note_of = |word: &str| {
+            let token = tokens.iter().find(|t| t.text == word).unwrap();
+            token.note.clone()
+        };
+        assert_eq!(note_of("First").as_deref(), Some("Note one."));
+        assert_eq!(note_of("Second").as_deref(), Some("Note two."));
+        assert_eq!(note_of("Third").as_deref(), Some("Note three."));
+    }
+
+    /// Test that a range load reads the selected chapters and the notes file
+    /// they link to, and leaves other chapters alone.
+    #[test]
+    fn test_range_load_reads_linked_note_files() {
+        let data = build_epub(&[
+            ("one.xhtml", "<p>Chapter one text.</p>"),
+            (
+                "text/two.xhtml",
+                r##"<p>Chapter two<sup><a href="../notes.xhtml#en1">1</a></sup>.</p>"##,
+            ),
+            ("three.xhtml", "<p>Chapter three text.</p>"),
+            (
+                "notes.xhtml",
+                r##"<p id="en1"><a href="text/two.xhtml">1</a> Late note.</p>"##,
+            ),
+        ]);
+        let options = LoadOptions {
+            range: LoadRange::parse("ch2"),
+            ..LoadOptions::default()
+        };
+        let doc = load_bytes(&data, Path::new("b.epub"), &options).unwrap();
+
+        let words: Vec<&str> = doc.tokens.iter().map(|t| t.text).collect();
+        assert_eq!(words, vec!["Chapter", "two"]);
+        assert_eq!(doc.tokens.get(1).unwrap().note, Some("Late note."));
+    }
+
+    #[test]
+    fn test_note_files_resolve_relative_links() {
+        let chapters = vec![r##"<p>A<sup><a href="../notes/n.xhtml#1">1</a></sup>
+                B<a epub:type="noteref" href="x.xhtml#n">a</a>
+                <a href="next.xhtml">Next chapter</a> <a href="#local">2</a></p>"##
+            .to_string()];
+        let paths = vec![Some(PathBuf::from("OEBPS/text/c1.xhtml"))];
+        let files: Vec<PathBuf> = note_files(&chapters, &paths, 0..1).into_iter().collect();
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("OEBPS/notes/n.xhtml"),
+                PathBuf::from("OEBPS/text/x.xhtml"),
+            ]
+        );
+    }
+
+    /// Test chapter boundaries derived from the table of contents.
+    #[test]
+    fn test_chapter_starts() {
+        // Cover and title page before the first TOC entry, two files in chapter 2
+        assert_eq!(
+            chapter_starts(&[Some(2), Some(3), Some(5)], 7),
+            vec![2, 3, 5]
+        );
+        // Unresolved and backwards entries are skipped
+        assert_eq!(
+            chapter_starts(&[Some(1), None, Some(0), Some(4)], 6),
+            vec![1, 4]
+        );
+        // No usable TOC: every spine item is a chapter
+        assert_eq!(chapter_starts(&[None], 3), vec![0, 1, 2]);
+    }
 }
@@ -0,0 +1,341 @@
+//! FictionBook 2 (FB2) ebook loader
+//!
+//! FB2 is a single XML file: `description/title-info` holds the metadata,
+//! the main `body` holds nested `section`s with their own `title`s, and a
+//! second body named "notes" holds footnotes that the text links to with
+//! `<a type="note">`. Section nesting gives the heading level.
+
+use super::markup::{self, Element, Node};
+use super::notes::note_ref_marker;
+use super::structure::DocumentBuilder;
+use super::{LoadError, LoadedDocument, Metadata};
+use encoding_rs::Encoding;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Elements read as one paragraph each.
+const PARAGRAPH_ELEMENTS: &[&str] = &["p", "subtitle", "v", "text-author", "td", "th"];
+
+/// Elements with nothing to read.
+const SKIPPED_ELEMENTS: &[&str] = &["image", "empty-line", "binary"];
+
+/// Load an FB2 file.
+///
+/// Purpose: Opens FictionBook ebooks with chapters, notes and metadata.
+/// Big Picture: Enables `@book.fb2` in the command deck.
+/// Connections: Parses XML with input::markup and builds the document via
+/// input::structure.
+pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let data = std::fs::read(path)?;
+    load_bytes(&data, path)
+}
+
+/// Load an FB2 already read into memory; `path` is only used to name the source.
+pub fn load_bytes(data: &[u8], path: &Path) -> Result<LoadedDocument, LoadError> {
+    parse_fb2(data, format!("fb2:{}", path.display()))
+}
+
+fn parse_fb2(data: &[u8], source: String) -> Result<LoadedDocument, LoadError> {
+    let root = markup::parse(&decode_xml(data));
+    let book = root
+        .find(&|e| e.name == "fictionbook")
+        .ok_or_else(|| LoadError::Fb2Parse("Missing FictionBook root element".to_string()))?;
+
+    let bodies: Vec<&Element> = child_elements(book).filter(|e| e.name == "body").collect();
+    let (note_bodies, main_bodies): (Vec<&Element>, Vec<&Element>) = bodies
+        .into_iter()
+        .partition(|e| matches!(e.attr("name"), Some("notes" | "comments")));
+
+    let mut reader = Fb2Reader {
+        note_bodies: collect_note_bodies(&note_bodies),
+        notes: Vec::new(),
+        builder: DocumentBuilder::new(),
+    };
+    for body in main_bodies {
+        reader.read_blocks(body, 0);
+    }
+
+    if reader.builder.is_empty() {
+        return Err(LoadError::Fb2Parse(
+            "No text content found in book".to_string(),
+        ));
+    }
+
+    reader
+        .builder
+        .build_with_metadata(source, &reader.notes, read_metadata(book))
+}
+
+/// Decodes the file using the encoding named in its XML declaration.
+///
+/// Many FB2 files (especially Russian ones) are windows-1251 rather than UTF-8.
+fn decode_xml(data: &[u8]) -> String {
+    let prolog = String::from_utf8_lossy(&data[..data.len().min(200)]);
+    let declared = prolog
+        .split("?>")
+        .next()
+        .and_then(|decl| decl.split("encoding=").nth(1))
+        .and_then(|rest| {
+            rest.trim_start_matches(['"', '\''])
+                .split(['"', '\''])
+                .next()
+        })
+        .and_then(|label| Encoding::for_label(label.trim().as_bytes()));
+
+    match declared {
+        Some(encoding) => encoding.decode_with_bom_removal(data).0.into_owned(),
+        None => String::from_utf8_lossy(data)
+            .trim_start_matches('\u{FEFF}')
+            .to_string(),
+    }
+}
+
+fn read_metadata(book: &Element) -> Metadata {
+    let Some(info) = book.find(&|e| e.name == "title-info") else {
+        return Metadata::default();
+    };
+
+    let text_of = |name: &str| {
+        child_elements(info)
+            .find(|e| e.name == name)
+            .map(|e| collapse(&e.text()))
+            .filter(|t| !t.is_empty())
+    };
+
+    let authors: Vec<String> = child_elements(info)
+        .filter(|e| e.name == "author")
+        .filter_map(|author| {
+            let parts: Vec<String> = ["first-name", "middle-name", "last-name"]
+                .iter()
+                .filter_map(|part| child_elements(author).find(|e| e.name == *part))
+                .map(|e| collapse(&e.text()))
+                .filter(|t| !t.is_empty())
+                .collect();
+            if parts.is_empty() {
+                child_elements(author)
+                    .find(|e| e.name == "nickname")
+                    .map(|e| collapse(&e.text()))
+            } else {
+                Some(parts.join(" "))
+            }
+        })
+        .collect();
+
+    Metadata {
+        title: text_of("book-title"),
+        author: (!authors.is_empty()).then(|| authors.join(", ")),
+        language: text_of("lang"),
+        ..Metadata::default()
+    }
+}
+
+/// Maps note section ids to their text, without the note's own title.
+fn collect_note_bodies(bodies: &[&Element]) -> HashMap<String, String> {
+    let mut notes = HashMap::new();
+    for body in bodies {
+        body.walk(&mut |e| {
+            if e.name != "section" {
+                return;
+            }
+            if let Some(id) = e.attr("id") {
+                let text: Vec<String> = child_elements(e)
+                    .filter(|c| c.name != "title")
+                    .map(|c| c.text())
+                    .collect();
+                let text = collapse(&text.join(" "));
+                if !text.is_empty() {
+                    notes.insert(id.to_string(), text);
+                }
+            }
+        });
+    }
+    notes
+}
+
+struct Fb2Reader {
+    note_bodies: HashMap<String, String>,
+    notes: Vec<String>,
+    builder: DocumentBuilder,
+}
+
+impl Fb2Reader {
+    /// Reads titles and paragraphs; `depth` is the section nesting level.
+    fn read_blocks(&mut self, container: &Element, depth: u8) {
+        for e in child_elements(container) {
+            match e.name.as_str() {
+                name if SKIPPED_ELEMENTS.contains(&name) => {}
+                "title" => {
+                    let lines: Vec<String> = child_elements(e)
+                        .map(|line| self.inline_text(line))
+                        .collect();
+                    self.builder.heading(depth.clamp(1, 6), &lines.join(" "));
+                }
+                "section" => self.read_blocks(e, depth.saturating_add(1)),
+                name if PARAGRAPH_ELEMENTS.contains(&name) => {
+                    let text = self.inline_text(e);
+                    self.builder.paragraph(&text);
+                }
+                // epigraph, poem, stanza, cite, annotation, table, tr
+                _ => self.read_blocks(e, depth),
+            }
+        }
+    }
+
+    /// Text of a paragraph, with note links replaced by markers.
+    fn inline_text(&mut self, e: &Element) -> String {
+        let mut out = String::new();
+        self.collect_inline(e, &mut out);
+        out
+    }
+
+    fn collect_inline(&mut self, e: &Element, out: &mut String) {
+        for child in &e.children {
+            match child {
+                Node::Text(text) => out.push_str(text),
+                Node::Element(child) if child.name == "a" => match self.note_for(child) {
+                    Some(note) => {
+                        self.notes.push(note);
+                        out.push_str(&note_ref_marker(self.notes.len() - 1));
+                    }
+                    None => self.collect_inline(child, out),
+                },
+                Node::Element(child) => self.collect_inline(child, out),
+            }
+        }
+    }
+
+    /// Resolves a link to the text of the note it points at.
+    fn note_for(&self, link: &Element) -> Option<String> {
+        let target = ["l:href", "xlink:href", "href"]
+            .iter()
+            .find_map(|name| link.attr(name))?
+            .strip_prefix('#')?;
+        self.note_bodies.get(target).cloned()
+    }
+}
+
+fn child_elements(e: &Element) -> impl Iterator<Item = &Element> {
+    e.children.iter().filter_map(|child| match child {
+        Node::Element(e) => Some(e),
+        Node::Text(_) => None,
+    })
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOK: &str = r##"<?xml version="1.0" encoding="utf-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+  <description>
+    <title-info>
+      <author><first-name>Anton</first-name><last-name>Chekhov</last-name></author>
+      <book-title>Short Stories</book-title>
+      <lang>en</lang>
+    </title-info>
+  </description>
+  <body>
+    <title><p>Short Stories</p></title>
+    <section>
+      <title><p>Chapter I</p><p>The Bet</p></title>
+      <epigraph><p>Quiet words.</p><text-author>Anon</text-author></epigraph>
+      <p>It was a <emphasis>dark</emphasis> autumn night<a l:href="#n1" type="note">[1]</a>.</p>
+      <empty-line/>
+      <section>
+        <title><p>Part One</p></title>
+        <poem><stanza><v>First line</v><v>Second line</v></stanza></poem>
+      </section>
+    </section>
+  </body>
+  <body name="notes">
+    <section id="n1"><title><p>1</p></title><p>Fifteen years earlier.</p></section>
+  </body>
+  <binary id="cover.jpg" content-type="image/jpeg">AAAA</binary>
+</FictionBook>"##;
+
+    fn words(doc: &LoadedDocument) -> Vec<&str> {
+        doc.tokens
+            .iter()
+            .filter(|t| !t.text.is_empty())
+            .map(|t| t.text)
+            .collect()
+    }
+
+    #[test]
+    fn test_fb2_sections_and_text() {
+        let doc = parse_fb2(BOOK.as_bytes(), "fb2:test".to_string()).unwrap();
+        let titles: Vec<(&str, u8)> = doc
+            .sections
+            .iter()
+            .map(|s| (s.title.as_str(), s.level))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                ("Short Stories", 1),
+                ("Chapter I The Bet", 1),
+                ("Part One", 2)
+            ]
+        );
+
+        let words = words(&doc);
+        assert!(words.contains(&"Anon"));
+        assert_eq!(words.last(), Some(&"line"));
+        assert!(!words.contains(&"AAAA"));
+        assert!(!words.contains(&"Fifteen"));
+    }
+
+    #[test]
+    fn test_fb2_notes() {
+        let doc = parse_fb2(BOOK.as_bytes(), "fb2:test".to_string()).unwrap();
+        let night = doc.tokens.iter().find(|t| t.text == "night").unwrap();
+        assert_eq!(night.note, Some("Fifteen years earlier."));
+        assert_eq!(night.punctuation.as_str(), ".");
+        assert!(!words(&doc).contains(&"[1]"));
+    }
+
+    #[test]
+    fn test_fb2_metadata() {
+        let doc = parse_fb2(BOOK.as_bytes(), "fb2:test".to_string()).unwrap();
+        assert_eq!(
+            doc.metadata,
+            Metadata {
+                title: Some("Short Stories".to_string()),
+                author: Some("Anton Chekhov".to_string()),
+                language: Some("en".to_string()),
+                ..Metadata::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_fb2_declared_encoding() {
+        let xml = "<?xml version=\"1.0\" encoding=\"windows-1251\"?>\
+            <FictionBook><body><section><p>Привет мир</p></section></body></FictionBook>";
+        let (bytes, _, _) = encoding_rs::WINDOWS_1251.encode(xml);
+        let doc = parse_fb2(&bytes, "fb2:test".to_string()).unwrap();
+        assert_eq!(words(&doc), vec!["Привет", "мир"]);
+    }
+
+    #[test]
+    fn test_fb2_rejects_other_xml() {
+        let result = parse_fb2(b"<html><body><p>Hi</p></body></html>", "fb2:x".to_string());
+        assert!(matches!(result, Err(LoadError::Fb2Parse(_))));
+    }
+
+    #[test]
+    fn test_fb2_load_nonexistent_file() {
+        let result = load("/nonexistent/path/book.fb2");
+        assert!(matches!(result, Err(LoadError::FileNotFound(_))));
+    }
+}
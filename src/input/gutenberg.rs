@@ -0,0 +1,313 @@
+//! Project Gutenberg boilerplate removal
+//!
+//! Gutenberg texts wrap the book in a licence header and footer thousands of
+//! words long, delimited by `*** START OF THE PROJECT GUTENBERG EBOOK ... ***`
+//! and `*** END OF THE PROJECT GUTENBERG EBOOK ... ***` lines. Everything
+//! outside the markers is dropped, along with the producer credits,
+//! transcriber's notes and table of contents that open most books.
+
+/// Paragraphs at the start of the book checked for producer credits.
+const CREDIT_PARAGRAPHS: usize = 8;
+
+/// Lines at the start of the book searched for a table of contents.
+const CONTENTS_SEARCH_LINES: usize = 600;
+
+/// Openings of producer credit paragraphs, uppercase.
+const CREDIT_PREFIXES: &[&str] = &[
+    "PRODUCED BY",
+    "E-TEXT PREPARED BY",
+    "ETEXT PREPARED BY",
+    "THIS ETEXT WAS PRODUCED BY",
+    "THIS EBOOK WAS PRODUCED BY",
+    "TRANSCRIBED FROM",
+];
+
+/// Book text with the Gutenberg wrapper removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trimmed {
+    pub text: String,
+    /// What was removed, for display to the reader.
+    pub notice: String,
+}
+
+/// Strips the licence header and footer and the book's front matter.
+///
+/// Returns `None` for texts without a `START OF THE PROJECT GUTENBERG` marker,
+/// which are left untouched.
+pub fn strip_boilerplate(text: &str) -> Option<Trimmed> {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.iter().position(|line| is_marker(line, "START"))? + 1;
+    let end = lines[start..]
+        .iter()
+        .position(|line| is_marker(line, "END"))
+        .map_or(lines.len(), |offset| start + offset);
+
+    let mut body = &lines[start..end];
+    body = skip_credits(body);
+    body = skip_contents(body);
+    let kept = drop_transcriber_notes(body);
+
+    let text = kept.join("\n").trim().to_string();
+    let removed = word_count(&lines) - text.split_whitespace().count();
+    Some(Trimmed {
+        text,
+        notice: format!(
+            "Removed Project Gutenberg licence and front matter ({} words); \
+             --keep-boilerplate keeps them",
+            removed
+        ),
+    })
+}
+
+/// Matches `*** START OF THE PROJECT GUTENBERG EBOOK` and its variants
+/// (`THIS`, no asterisks), and the older `End of Project Gutenberg's` footer.
+fn is_marker(line: &str, kind: &str) -> bool {
+    let normalized = line
+        .replace('*', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase();
+    [
+        "OF THE PROJECT GUTENBERG",
+        "OF THIS PROJECT GUTENBERG",
+        "OF PROJECT GUTENBERG",
+    ]
+    .iter()
+    .any(|rest| normalized.starts_with(&format!("{} {}", kind, rest)))
+}
+
+/// Skips blank lines and producer credit paragraphs at the start.
+fn skip_credits<'a>(mut body: &'a [&'a str]) -> &'a [&'a str] {
+    for _ in 0..CREDIT_PARAGRAPHS {
+        body = trim_blank_start(body);
+        let paragraph_end = body
+            .iter()
+            .position(|line| line.trim().is_empty())
+            .unwrap_or(body.len());
+        let first = body
+            .first()
+            .map_or(String::new(), |l| l.trim().to_uppercase());
+        if !CREDIT_PREFIXES
+            .iter()
+            .any(|prefix| first.starts_with(prefix))
+        {
+            break;
+        }
+        body = &body[paragraph_end..];
+    }
+    trim_blank_start(body)
+}
+
+/// Skips a table of contents near the start, up to the first heading it lists.
+///
+/// The body is found by matching the words of the first entry, so that
+/// `CHAPTER I.  Down the Rabbit-Hole  1` in the contents finds `CHAPTER I.`
+/// in the text. Contents whose first entry never recurs are kept.
+fn skip_contents<'a>(body: &'a [&'a str]) -> &'a [&'a str] {
+    let search = &body[..body.len().min(CONTENTS_SEARCH_LINES)];
+    let Some(heading) = search.iter().position(|line| {
+        matches!(
+            words(line).join(" ").as_str(),
+            "contents" | "table of contents"
+        )
+    }) else {
+        return body;
+    };
+
+    let Some(first_entry) = body[heading + 1..]
+        .iter()
+        .position(|line| !line.trim().is_empty())
+        .map(|offset| heading + 1 + offset)
+    else {
+        return body;
+    };
+    let entry = words(body[first_entry]);
+
+    let resumes = (first_entry + 1..body.len()).find(|&i| {
+        let candidate = words(body[i]);
+        body[i - 1].trim().is_empty()
+            && !candidate.is_empty()
+            && (entry.starts_with(&candidate) || candidate.starts_with(&entry))
+    });
+    match resumes {
+        Some(index) => &body[index..],
+        None => body,
+    }
+}
+
+/// Removes transcriber's note paragraphs, including bracketed notes that run
+/// over several paragraphs and a `TRANSCRIBER'S NOTES` heading's paragraph.
+fn drop_transcriber_notes<'a>(body: &[&'a str]) -> Vec<&'a str> {
+    let mut kept = Vec::with_capacity(body.len());
+    let mut paragraphs = body.split(|line| line.trim().is_empty());
+    let mut first = true;
+
+    while let Some(paragraph) = paragraphs.next() {
+        if paragraph.is_empty() {
+            continue;
+        }
+        let opening = paragraph[0].trim_start().trim_start_matches('[');
+        if words(opening).starts_with(&["transcriber".to_string(), "s".to_string()]) {
+            let text = paragraph.join(" ");
+            if paragraph[0].trim_start().starts_with('[') {
+                // Drop through the closing bracket
+                let mut closed = text.contains(']');
+                while !closed {
+                    match paragraphs.next() {
+                        Some(next) => closed = next.iter().any(|line| line.contains(']')),
+                        None => break,
+                    }
+                }
+            } else if paragraph.len() == 1 && words(&text).len() <= 3 {
+                // A heading: its note is the next paragraph
+                paragraphs.next();
+            }
+            continue;
+        }
+
+        if !first {
+            kept.push("");
+        }
+        kept.extend_from_slice(paragraph);
+        first = false;
+    }
+    kept
+}
+
+fn trim_blank_start<'a>(body: &'a [&'a str]) -> &'a [&'a str] {
+    let start = body
+        .iter()
+        .position(|line| !line.trim().is_empty())
+        .unwrap_or(body.len());
+    &body[start..]
+}
+
+/// Lowercase alphanumeric words of a line, for loose comparisons.
+fn words(line: &str) -> Vec<String> {
+    line.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn word_count(lines: &[&str]) -> usize {
+    lines
+        .iter()
+        .map(|line| line.split_whitespace().count())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOK: &str = "\u{FEFF}The Project Gutenberg eBook of Alice's Adventures in Wonderland
+
+This ebook is for the use of anyone anywhere in the United States and
+most other parts of the world at no cost and with almost no restrictions
+whatsoever.
+
+Title: Alice's Adventures in Wonderland
+
+*** START OF THE PROJECT GUTENBERG EBOOK ALICE'S ADVENTURES IN WONDERLAND ***
+
+Produced by Arthur DiBianca and David Widger
+
+[Transcriber's Note: Obvious typographical errors have been
+corrected.
+
+The original spelling has been retained.]
+
+Alice's Adventures in Wonderland
+
+by Lewis Carroll
+
+Contents
+
+ CHAPTER I.     Down the Rabbit-Hole
+ CHAPTER II.    The Pool of Tears
+
+CHAPTER I.
+Down the Rabbit-Hole
+
+Alice was beginning to get very tired of sitting by her sister on the
+bank, and of having nothing to do.
+
+CHAPTER II.
+The Pool of Tears
+
+Curiouser and curiouser!
+
+*** END OF THE PROJECT GUTENBERG EBOOK ALICE'S ADVENTURES IN WONDERLAND ***
+
+Updated editions will replace the previous one--the old editions will
+be renamed.
+";
+
+    #[test]
+    fn test_strips_header_footer_and_front_matter() {
+        let trimmed = strip_boilerplate(BOOK).unwrap();
+        assert!(trimmed
+            .text
+            .starts_with("CHAPTER I.\nDown the Rabbit-Hole\n\nAlice"));
+        assert!(trimmed.text.ends_with("Curiouser and curiouser!"));
+        assert!(!trimmed.text.contains("Gutenberg"));
+        assert!(!trimmed.text.contains("Transcriber"));
+        assert!(trimmed.notice.contains("words"));
+    }
+
+    #[test]
+    fn test_non_gutenberg_text_untouched() {
+        assert_eq!(strip_boilerplate("Just a story.\n\nThe end."), None);
+    }
+
+    #[test]
+    fn test_marker_variants() {
+        assert!(is_marker(
+            "***START OF THIS PROJECT GUTENBERG EBOOK FOO***",
+            "START"
+        ));
+        assert!(is_marker(
+            "*** END OF THE PROJECT GUTENBERG EBOOK FOO ***",
+            "END"
+        ));
+        assert!(is_marker(
+            "End of Project Gutenberg's Alice, by Lewis Carroll",
+            "END"
+        ));
+        assert!(!is_marker("The end of the chapter.", "END"));
+    }
+
+    #[test]
+    fn test_contents_kept_when_entries_do_not_recur() {
+        let body = [
+            "CONTENTS",
+            "",
+            "I. The Beginning",
+            "",
+            "Chapter One",
+            "",
+            "Text.",
+        ];
+        assert_eq!(skip_contents(&body), &body[..]);
+    }
+
+    #[test]
+    fn test_transcriber_note_heading_and_paragraph() {
+        let body = [
+            "TRANSCRIBER'S NOTES",
+            "",
+            "Hyphenation has been standardised.",
+            "",
+            "Story text.",
+        ];
+        assert_eq!(drop_transcriber_notes(&body), vec!["Story text."]);
+    }
+
+    #[test]
+    fn test_missing_end_marker_keeps_rest() {
+        let text = "Licence\n*** START OF THE PROJECT GUTENBERG EBOOK X ***\nAll of it.";
+        assert_eq!(strip_boilerplate(text).unwrap().text, "All of it.");
+    }
+}
@@ -0,0 +1,573 @@
+//! HTML article loader
+//!
+//! Saved web pages carry far more than the article: navigation, ads, share
+//! buttons, comment sections and footers. The loader finds the main content
+//! with a readability-style heuristic:
+//!
+//! 1. Remove elements that are never content (scripts, forms, navigation) and
+//!    elements whose class or id marks them as page chrome.
+//! 2. Score every paragraph-like block by its length and comma count, and
+//!    credit the score to its parent and (halved) grandparent.
+//! 3. Pick the ancestor with the best score after discounting link-heavy
+//!    text, then add siblings that score close to it.
+//!
+//! Headings and paragraphs of the selected content are kept as structure.
+//!
+//! Pages are decoded in the encoding a `<meta charset>` near the top
+//! declares, falling back to the same detection as plain text.
+
+use super::markup::{self, Element, Node};
+use super::structure::DocumentBuilder;
+use super::{encoding, LoadError, LoadOptions, LoadedDocument};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Bytes searched for a `<meta charset>`, as browsers do.
+const META_PRESCAN_BYTES: usize = 1024;
+
+/// Elements removed before scoring: never part of an article's text.
+const REMOVED_ELEMENTS: &[&str] = &[
+    "aside", "button", "canvas", "footer", "form", "iframe", "input", "nav", "noscript", "object",
+    "select", "svg", "textarea",
+];
+
+/// Class/id fragments of page chrome (checked before `MAYBE_CONTENT`).
+const UNLIKELY_CANDIDATES: &[&str] = &[
+    "ad-break",
+    "advert",
+    "banner",
+    "breadcrumb",
+    "combx",
+    "comment",
+    "community",
+    "cookie",
+    "disqus",
+    "footer",
+    "gdpr",
+    "header",
+    "menu",
+    "newsletter",
+    "popup",
+    "promo",
+    "related",
+    "remark",
+    "replies",
+    "share",
+    "shoutbox",
+    "sidebar",
+    "skyscraper",
+    "social",
+    "sponsor",
+    "subscribe",
+];
+
+/// Class/id fragments that keep an element even if it looks unlikely.
+const MAYBE_CONTENT: &[&str] = &[
+    "article", "body", "column", "content", "main", "post", "story",
+];
+
+/// Class/id fragments that raise or lower a candidate's score.
+const POSITIVE_HINTS: &[&str] = &[
+    "article", "blog", "body", "content", "entry", "main", "page", "post", "story", "text",
+];
+const NEGATIVE_HINTS: &[&str] = &[
+    "byline", "caption", "comment", "foot", "hidden", "meta", "more", "outbrain", "shopping",
+    "sidebar", "sponsor", "tags", "widget",
+];
+
+/// Paragraphs shorter than this many characters are not scored.
+const MIN_PARAGRAPH_LENGTH: usize = 25;
+
+/// Blocks with more than this share of their text in links are navigation.
+const MAX_LINK_DENSITY: f64 = 0.5;
+
+/// Load the main article text from an HTML file.
+///
+/// Purpose: Reads saved web articles without their page chrome.
+/// Big Picture: Enables `@article.html` in the command deck and `speedy article.html`.
+/// Connections: Parses with input::markup and builds tokens and sections via
+/// input::structure.
+pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let bytes = std::fs::read(path)?;
+    load_bytes(&bytes, path, &LoadOptions::default())
+}
+
+/// Load HTML already read into memory; `path` is only used to name the source.
+///
+/// `options.encoding` overrides both a byte order mark and `<meta charset>`.
+pub fn load_bytes(
+    data: &[u8],
+    path: &Path,
+    options: &LoadOptions,
+) -> Result<LoadedDocument, LoadError> {
+    let source = format!("html:{}", path.display());
+    let forced = options.encoding.or_else(|| {
+        Encoding::for_bom(data)
+            .is_none()
+            .then(|| declared_charset(data))
+            .flatten()
+    });
+    let decoded =
+        encoding::decode(data, forced).ok_or_else(|| LoadError::InvalidEncoding(source.clone()))?;
+
+    let builder = extract_article(&decoded.text);
+    if builder.is_empty() {
+        return Err(LoadError::HtmlParse(
+            "No readable content found in HTML".to_string(),
+        ));
+    }
+
+    let mut doc = builder.build(source, &[])?;
+    doc.metadata.encoding = Some(decoded.encoding.name().to_string());
+    Ok(doc)
+}
+
+/// The encoding named by a `<meta charset>` or `<meta http-equiv>` tag at the
+/// top of the page.
+///
+/// A page whose meta tag can be read as ASCII is not UTF-16, whatever it
+/// says, so UTF-16 labels mean UTF-8 here as in browsers.
+fn declared_charset(data: &[u8]) -> Option<&'static Encoding> {
+    let head = &data[..data.len().min(META_PRESCAN_BYTES)];
+    let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+    head.split("<meta").skip(1).find_map(|tag| {
+        let tag = tag.split('>').next().unwrap_or_default();
+        let value = tag.split("charset=").nth(1)?;
+        let label = value
+            .trim_start_matches(['"', '\'', ' '])
+            .split(['"', '\'', ' ', ';', '/'])
+            .next()?;
+        let encoding = Encoding::for_label(label.as_bytes())?;
+        Some(match encoding {
+            e if e == UTF_16LE || e == UTF_16BE => UTF_8,
+            e => e,
+        })
+    })
+}
+
+/// Extracts the article content of an HTML page as headings and paragraphs.
+fn extract_article(html: &str) -> DocumentBuilder {
+    let mut root = markup::parse(html);
+    let title = page_title(&root);
+
+    root.rewrite(&mut |e| is_chrome(e).then(Vec::new));
+
+    let body = root
+        .find(&|e| e.name == "body")
+        .cloned()
+        .unwrap_or_else(|| root.clone());
+
+    let content = select_content(&body);
+
+    let mut builder = DocumentBuilder::new();
+    let has_h1 = content
+        .iter()
+        .any(|e| e.name == "h1" || e.find(&|c| c.name == "h1").is_some());
+    if let (Some(title), false) = (title, has_h1) {
+        builder.heading(1, &title);
+    }
+    for element in &content {
+        emit_block(element, &mut builder);
+    }
+    builder
+}
+
+/// Returns true for elements that are page chrome rather than content.
+fn is_chrome(e: &Element) -> bool {
+    if REMOVED_ELEMENTS.contains(&e.name.as_str()) {
+        return true;
+    }
+    if matches!(e.name.as_str(), "body" | "article" | "main" | "a") {
+        return false;
+    }
+    let hints = class_and_id(e);
+    if e.attr_contains("role", "navigation") || e.attr_contains("role", "complementary") {
+        return true;
+    }
+    UNLIKELY_CANDIDATES.iter().any(|u| hints.contains(u))
+        && !MAYBE_CONTENT.iter().any(|m| hints.contains(m))
+}
+
+fn class_and_id(e: &Element) -> String {
+    format!(
+        "{} {}",
+        e.attr("class").unwrap_or_default(),
+        e.attr("id").unwrap_or_default()
+    )
+    .to_lowercase()
+}
+
+/// The page title from `<title>`, without a trailing site name.
+fn page_title(root: &Element) -> Option<String> {
+    let title = root
+        .find(&|e| e.name == "title")?
+        .children
+        .iter()
+        .find_map(|n| match n {
+            Node::Text(text) => Some(markup::decode_entities(text)),
+            Node::Element(_) => None,
+        })?;
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    for separator in [" | ", " - ", " – ", " — ", " :: "] {
+        if let Some((head, _site)) = title.rsplit_once(separator) {
+            if head.split_whitespace().count() >= 3 {
+                return Some(head.to_string());
+            }
+        }
+    }
+    (!title.is_empty()).then_some(title)
+}
+
+/// Finds the elements that make up the article: the best-scoring candidate
+/// and the siblings that score close to it.
+fn select_content(body: &Element) -> Vec<Element> {
+    let scores = score_candidates(body);
+
+    let best = scores
+        .iter()
+        .map(|(path, score)| (path, score * (1.0 - link_density(element_at(body, path)))))
+        .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.len().cmp(&a.0.len())));
+
+    let Some((best_path, best_score)) = best else {
+        return vec![body.clone()];
+    };
+    if best_path.is_empty() {
+        return vec![body.clone()];
+    }
+
+    let parent_path = &best_path[..best_path.len() - 1];
+    let parent = element_at(body, parent_path);
+    let threshold = (best_score * 0.2).max(10.0);
+
+    parent
+        .children
+        .iter()
+        .enumerate()
+        .filter_map(|(i, node)| match node {
+            Node::Element(e) => Some((i, e)),
+            Node::Text(_) => None,
+        })
+        .filter(|(i, sibling)| {
+            let mut path = parent_path.to_vec();
+            path.push(*i);
+            if &path == best_path {
+                return true;
+            }
+            let score = scores.get(&path).copied().unwrap_or(0.0);
+            if score * (1.0 - link_density(sibling)) >= threshold {
+                return true;
+            }
+            if sibling.name == "p" {
+                let text = sibling.text();
+                let density = link_density(sibling);
+                let length = text.chars().count();
+                return (length > 80 && density < 0.25)
+                    || (length > 0 && density == 0.0 && text.ends_with('.'));
+            }
+            false
+        })
+        .map(|(_, e)| e.clone())
+        .collect()
+}
+
+/// Scores ancestors of paragraph-like blocks, keyed by child-index path.
+fn score_candidates(body: &Element) -> HashMap<Vec<usize>, f64> {
+    let mut scores: HashMap<Vec<usize>, f64> = HashMap::new();
+    let mut paragraphs: Vec<(Vec<usize>, f64)> = Vec::new();
+    collect_paragraphs(body, &mut Vec::new(), &mut paragraphs);
+
+    for (path, content_score) in paragraphs {
+        for (depth, divider) in [(1, 1.0), (2, 2.0)] {
+            if path.len() < depth {
+                break;
+            }
+            let ancestor = path[..path.len() - depth].to_vec();
+            let initial = initial_score(element_at(body, &ancestor));
+            *scores.entry(ancestor).or_insert(initial) += content_score / divider;
+        }
+    }
+    scores
+}
+
+/// Collects paragraph-like blocks with their content score.
+fn collect_paragraphs(e: &Element, path: &mut Vec<usize>, out: &mut Vec<(Vec<usize>, f64)>) {
+    for (i, child) in e.children.iter().enumerate() {
+        let Node::Element(child) = child else {
+            continue;
+        };
+        path.push(i);
+        let is_paragraph = matches!(child.name.as_str(), "p" | "pre" | "td")
+            || (child.name == "div" && !has_block_children(child));
+        if is_paragraph {
+            let text = child.text();
+            let length = text.chars().count();
+            if length >= MIN_PARAGRAPH_LENGTH {
+                let commas = text.matches([',', '，', '、']).count();
+                let score = 1.0 + commas as f64 + (length as f64 / 100.0).min(3.0);
+                out.push((path.clone(), score));
+            }
+        } else {
+            collect_paragraphs(child, path, out);
+        }
+        path.pop();
+    }
+}
+
+/// Base score of a candidate from its tag and class/id hints.
+fn initial_score(e: &Element) -> f64 {
+    let tag_score = match e.name.as_str() {
+        "article" | "main" => 10.0,
+        "div" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+    let hints = class_and_id(e);
+    let mut class_score = 0.0;
+    if POSITIVE_HINTS.iter().any(|h| hints.contains(h)) {
+        class_score += 25.0;
+    }
+    if NEGATIVE_HINTS.iter().any(|h| hints.contains(h)) {
+        class_score -= 25.0;
+    }
+    tag_score + class_score
+}
+
+fn element_at<'a>(root: &'a Element, path: &[usize]) -> &'a Element {
+    path.iter().fold(root, |e, &i| match &e.children[i] {
+        Node::Element(child) => child,
+        Node::Text(_) => e,
+    })
+}
+
+fn has_block_children(e: &Element) -> bool {
+    e.children
+        .iter()
+        .any(|n| matches!(n, Node::Element(child) if child.is_block() && child.name != "br"))
+}
+
+/// Share of an element's text that sits inside links.
+fn link_density(e: &Element) -> f64 {
+    let total = e.text().chars().count();
+    if total == 0 {
+        return 0.0;
+    }
+    let mut linked = 0;
+    e.walk(&mut |child| {
+        if child.name == "a" {
+            linked += child.text().chars().count();
+        }
+    });
+    // Nested links are counted twice; cap so the ratio stays meaningful
+    (linked as f64 / total as f64).min(1.0)
+}
+
+/// Emits an element's headings and paragraphs into the builder.
+pub(crate) fn emit_block(e: &Element, builder: &mut DocumentBuilder) {
+    if let Some(level) = heading_level(e) {
+        builder.heading(level, &e.text());
+        return;
+    }
+
+    let is_list_or_box = matches!(
+        e.name.as_str(),
+        "ul" | "ol" | "dl" | "div" | "section" | "table"
+    );
+    if is_list_or_box && link_density(e) > MAX_LINK_DENSITY {
+        return;
+    }
+
+    if !has_block_children(e) {
+        builder.paragraph(&e.text());
+        return;
+    }
+
+    // Mixed content: runs of inline nodes between blocks form paragraphs
+    let mut inline = String::new();
+    for child in &e.children {
+        match child {
+            Node::Element(child) if child.is_block() && child.name != "br" => {
+                builder.paragraph(&inline);
+                inline.clear();
+                emit_block(child, builder);
+            }
+            Node::Element(child) if child.name == "br" => {
+                builder.paragraph(&inline);
+                inline.clear();
+            }
+            Node::Element(child) => {
+                inline.push(' ');
+                inline.push_str(&child.text());
+                inline.push(' ');
+            }
+            Node::Text(text) => inline.push_str(text),
+        }
+    }
+    builder.paragraph(&inline);
+}
+
+fn heading_level(e: &Element) -> Option<u8> {
+    let digit = e.name.strip_prefix('h')?;
+    match digit.parse::<u8>() {
+        Ok(level @ 1..=6) => Some(level),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r##"<!DOCTYPE html>
+        <html><head><title>How Rivers Shape Valleys | Example News</title>
+        <script>var tracking = "do not read";</script></head>
+        <body>
+          <nav><a href="/">Home</a> <a href="/world">World</a> <a href="/science">Science</a></nav>
+          <div class="ad-banner">Buy our product today, it is great, really.</div>
+          <div id="main-content">
+            <article>
+              <h2>The slow work of water</h2>
+              <p>Rivers carve valleys over millions of years, grinding rock into sand, silt and clay.</p>
+              <p>Floods move most of the material, while quiet years do little, a pattern seen worldwide.</p>
+              <h2>Meanders</h2>
+              <p>As a river matures, it swings from side to side, cutting outer banks and building inner ones.</p>
+              <ul class="share"><li><a href="#">Share on X</a></li><li><a href="#">Email</a></li></ul>
+            </article>
+          </div>
+          <div class="sidebar"><h3>Related</h3><ul><li><a href="/a">Other story about rivers and lakes</a></li></ul></div>
+          <footer>Copyright 2024 Example News. All rights reserved, everywhere.</footer>
+        </body></html>"##;
+
+    fn lines(builder: DocumentBuilder) -> (Vec<String>, Vec<(String, u8)>) {
+        let doc = builder.build("test".to_string(), &[]).unwrap();
+        let mut words = vec![Vec::new()];
+        for t in doc.tokens.iter() {
+            if t.text.is_empty() && t.punctuation.is_newline() {
+                words.push(Vec::new());
+            } else {
+                words.last_mut().unwrap().push(t.text);
+            }
+        }
+        let text: Vec<String> = words.iter().map(|line| line.join(" ")).collect();
+        let sections = doc
+            .sections
+            .into_iter()
+            .map(|s| (s.title, s.level))
+            .collect();
+        (text, sections)
+    }
+
+    #[test]
+    fn test_extracts_article_and_drops_chrome() {
+        let (text, _) = lines(extract_article(ARTICLE));
+        let joined = text.join("\n");
+
+        assert!(joined.contains("Rivers carve valleys"));
+        assert!(joined.contains("cutting outer banks"));
+        for noise in [
+            "Home",
+            "Buy our product",
+            "Share on X",
+            "Other story",
+            "Copyright",
+            "tracking",
+        ] {
+            assert!(!joined.contains(noise), "should drop {:?}", noise);
+        }
+    }
+
+    #[test]
+    fn test_keeps_headings_as_sections() {
+        let (text, sections) = lines(extract_article(ARTICLE));
+
+        assert_eq!(
+            sections,
+            vec![
+                ("How Rivers Shape Valleys".to_string(), 1),
+                ("The slow work of water".to_string(), 2),
+                ("Meanders".to_string(), 2),
+            ]
+        );
+        assert_eq!(text[0], "How Rivers Shape Valleys");
+        assert_eq!(text[1], "The slow work of water");
+    }
+
+    #[test]
+    fn test_picks_densest_block_without_semantic_markup() {
+        let html = r#"<body>
+            <div class="top"><a href="/">Logo</a> <a href="/x">Menu item</a></div>
+            <div class="wrapper">
+              <div class="c1"><p>A real paragraph of text, with commas, that goes on for a while.</p>
+              <p>Another long paragraph follows, with more commas, clauses, and words in it.</p>
+              <p>And a third one, to make the score clear, because scores add up here.</p></div>
+              <div class="c2"><p>Short teaser.</p><a href="/more">Read more stories</a></div>
+            </div></body>"#;
+        let (text, _) = lines(extract_article(html));
+
+        assert_eq!(text.len(), 3);
+        assert!(text[0].starts_with("A real paragraph"));
+    }
+
+    #[test]
+    fn test_mixed_inline_and_block_content() {
+        let html =
+            "<body><div>Loose text <b>with bold</b><br>next line<p>Para</p>tail</div></body>";
+        let mut builder = DocumentBuilder::new();
+        emit_block(&markup::parse(html), &mut builder);
+        let (text, _) = lines(builder);
+        assert_eq!(
+            text,
+            vec!["Loose text with bold", "next line", "Para", "tail"]
+        );
+    }
+
+    #[test]
+    fn test_page_title_strips_site_name() {
+        let root = markup::parse("<title>A Long Article Title - Site</title>");
+        assert_eq!(page_title(&root).as_deref(), Some("A Long Article Title"));
+        let root = markup::parse("<title>Short - Site</title>");
+        assert_eq!(page_title(&root).as_deref(), Some("Short - Site"));
+    }
+
+    #[test]
+    fn test_meta_charset_decodes_page() {
+        let page = b"<html><head><meta http-equiv=\"Content-Type\" \
+                     content=\"text/html; charset=ISO-8859-1\"></head>\
+                     <body><p>Un caf\xe9 noir.</p></body></html>";
+        let doc = load_bytes(page, Path::new("a.html"), &LoadOptions::default()).unwrap();
+        assert_eq!(doc.tokens.get(1).unwrap().text, "café");
+        assert_eq!(doc.metadata.encoding.as_deref(), Some("windows-1252"));
+
+        assert_eq!(
+            declared_charset(b"<meta charset='utf-16le'/><p>Hi</p>"),
+            Some(UTF_8)
+        );
+        assert_eq!(declared_charset(b"<p>charset=koi8-r</p>"), None);
+    }
+
+    #[test]
+    fn test_forced_encoding_overrides_meta_charset() {
+        let page = "<meta charset=\"windows-1252\"><p>Ещё раз.</p>".as_bytes();
+        let options = LoadOptions {
+            encoding: Some(UTF_8),
+            ..LoadOptions::default()
+        };
+        let doc = load_bytes(page, Path::new("a.html"), &options).unwrap();
+        assert_eq!(doc.tokens.get(0).unwrap().text, "Ещё");
+    }
+
+    #[test]
+    fn test_html_load_nonexistent_file() {
+        let result = load("/nonexistent/path/article.html");
+        assert!(matches!(result, Err(LoadError::FileNotFound(_))));
+    }
+}
@@ -0,0 +1,604 @@
+//! Lenient HTML/XHTML parsing for document loaders
+//!
+//! Builds a small element tree from (X)HTML chapter content so loaders can
+//! reason about structure (footnote asides, note references, block elements)
+//! instead of stripping tags character by character. The parser never fails:
+//! unknown or mismatched markup is tolerated the way a browser would.
+
+/// A node in the parsed markup tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+/// An element with its attributes and children.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Element {
+    /// Lowercase tag name (namespace prefix kept, e.g. `epub:switch`).
+    pub name: String,
+    /// Attributes in source order; names are lowercase, values entity-decoded.
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+/// Elements that never have content or an end tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose content is raw text up to the matching end tag.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+/// Elements that start a new line of text when flattened.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "body",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+
+/// Elements whose content is never part of the readable text.
+const HIDDEN_ELEMENTS: &[&str] = &["head", "script", "style", "template", "noscript"];
+
+impl Element {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the value of an attribute, if present.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns true if a whitespace-separated attribute (such as `class`,
+    /// `role` or `epub:type`) contains the given value.
+    pub fn attr_contains(&self, name: &str, value: &str) -> bool {
+        self.attr(name)
+            .map(|v| {
+                v.split_whitespace()
+                    .any(|part| part.eq_ignore_ascii_case(value))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns true if this is a block-level element.
+    pub fn is_block(&self) -> bool {
+        BLOCK_ELEMENTS.contains(&self.name.as_str())
+    }
+
+    /// Returns the concatenated text of all descendants, whitespace collapsed.
+    pub fn text(&self) -> String {
+        let mut raw = String::new();
+        collect_text(self, &mut raw);
+        raw.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Finds the first descendant element (depth-first) matching a predicate.
+    pub fn find(&self, predicate: &dyn Fn(&Element) -> bool) -> Option<&Element> {
+        for child in &self.children {
+            if let Node::Element(element) = child {
+                if predicate(element) {
+                    return Some(element);
+                }
+                if let Some(found) = element.find(predicate) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+
+    /// Visits every descendant element depth-first, parents before children.
+    pub fn walk(&self, visit: &mut dyn FnMut(&Element)) {
+        for child in &self.children {
+            if let Node::Element(element) = child {
+                visit(element);
+                element.walk(visit);
+            }
+        }
+    }
+
+    /// Rewrites descendants bottom-up: the callback may return a list of
+    /// replacement nodes for an element, or `None` to keep it as is.
+    pub fn rewrite(&mut self, replace: &mut dyn FnMut(&Element) -> Option<Vec<Node>>) {
+        let children = std::mem::take(&mut self.children);
+        for child in children {
+            match child {
+                Node::Element(mut element) => match replace(&element) {
+                    Some(nodes) => self.children.extend(nodes),
+                    None => {
+                        element.rewrite(replace);
+                        self.children.push(Node::Element(element));
+                    }
+                },
+                text => self.children.push(text),
+            }
+        }
+    }
+
+    /// Flattens the tree to plain text: one line per block element, inline
+    /// whitespace collapsed, blank lines removed.
+    pub fn to_plain_text(&self) -> String {
+        let mut out = String::new();
+        flatten(self, &mut out);
+        out.lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn collect_text(element: &Element, out: &mut String) {
+    if HIDDEN_ELEMENTS.contains(&element.name.as_str()) {
+        return;
+    }
+    for child in &element.children {
+        match child {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(child) => {
+                if child.is_block() {
+                    out.push(' ');
+                }
+                collect_text(child, out);
+                if child.is_block() {
+                    out.push(' ');
+                }
+            }
+        }
+    }
+}
+
+fn flatten(element: &Element, out: &mut String) {
+    if HIDDEN_ELEMENTS.contains(&element.name.as_str()) {
+        return;
+    }
+    for child in &element.children {
+        match child {
+            Node::Text(text) => out.push_str(&text.replace('\n', " ")),
+            Node::Element(child) => {
+                if child.is_block() {
+                    out.push('\n');
+                }
+                flatten(child, out);
+                if child.is_block() {
+                    out.push('\n');
+                }
+            }
+        }
+    }
+}
+
+/// Parses (X)HTML into a tree rooted at a synthetic `#document` element.
+pub fn parse(input: &str) -> Element {
+    let mut stack: Vec<Element> = vec![Element::new("#document")];
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            push_text(&mut stack, rest);
+            break;
+        };
+        if lt > 0 {
+            push_text(&mut stack, &rest[..lt]);
+            rest = &rest[lt..];
+        }
+
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").unwrap_or(after.len());
+            push_raw_text(&mut stack, &after[..end]);
+            rest = after.get(end + 3..).unwrap_or("");
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after.find('>').unwrap_or(after.len());
+            let name = after[..end].trim().to_ascii_lowercase();
+            close_element(&mut stack, &name);
+            rest = after.get(end + 1..).unwrap_or("");
+        } else {
+            match parse_tag(&rest[1..]) {
+                Some((element, self_closing, consumed)) => {
+                    rest = &rest[1 + consumed..];
+                    let name = element.name.clone();
+                    close_implied(&mut stack, &name);
+                    if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+                        append_child(&mut stack, Node::Element(element));
+                    } else if RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+                        let closing = format!("</{}", name);
+                        let end = find_ignore_case(rest, &closing).unwrap_or(rest.len());
+                        let mut element = element;
+                        element.children.push(Node::Text(rest[..end].to_string()));
+                        append_child(&mut stack, Node::Element(element));
+                        rest = &rest[end..];
+                        rest = rest.find('>').map_or("", |gt| &rest[gt + 1..]);
+                    } else {
+                        stack.push(element);
+                    }
+                }
+                None => {
+                    // A lone '<' that does not start a tag is literal text.
+                    push_raw_text(&mut stack, "<");
+                    rest = &rest[1..];
+                }
+            }
+        }
+    }
+
+    while stack.len() > 1 {
+        let element = stack.pop().unwrap();
+        append_child(&mut stack, Node::Element(element));
+    }
+    stack.pop().unwrap()
+}
+
+/// Parses a start tag body (after `<`). Returns the element, whether it was
+/// self-closing, and the number of bytes consumed including the `>`.
+fn parse_tag(input: &str) -> Option<(Element, bool, usize)> {
+    let name_len = input
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .unwrap_or(input.len());
+    let name = &input[..name_len];
+    if name.is_empty() || !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let mut element = Element::new(&name.to_ascii_lowercase());
+    let mut pos = name_len;
+    let bytes = input.as_bytes();
+
+    loop {
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if pos >= bytes.len() {
+            return Some((element, false, pos));
+        }
+        match bytes[pos] {
+            b'>' => return Some((element, false, pos + 1)),
+            b'/' => {
+                pos += 1;
+                if bytes.get(pos) == Some(&b'>') {
+                    return Some((element, true, pos + 1));
+                }
+            }
+            _ => {
+                let start = pos;
+                while pos < bytes.len()
+                    && !bytes[pos].is_ascii_whitespace()
+                    && !matches!(bytes[pos], b'=' | b'>' | b'/')
+                {
+                    pos += 1;
+                }
+                let key = input[start..pos].to_ascii_lowercase();
+                while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                    pos += 1;
+                }
+                let mut value = String::new();
+                if bytes.get(pos) == Some(&b'=') {
+                    pos += 1;
+                    while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                        pos += 1;
+                    }
+                    match bytes.get(pos) {
+                        Some(&quote @ (b'"' | b'\'')) => {
+                            let value_start = pos + 1;
+                            let value_end = input[value_start..]
+                                .find(quote as char)
+                                .map_or(input.len(), |end| value_start + end);
+                            value = decode_entities(&input[value_start..value_end]);
+                            pos = (value_end + 1).min(input.len());
+                        }
+                        _ => {
+                            let value_start = pos;
+                            while pos < bytes.len()
+                                && !bytes[pos].is_ascii_whitespace()
+                                && bytes[pos] != b'>'
+                            {
+                                pos += 1;
+                            }
+                            value = decode_entities(&input[value_start..pos]);
+                        }
+                    }
+                }
+                if !key.is_empty() {
+                    element.attrs.push((key, value));
+                }
+            }
+        }
+    }
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .to_ascii_lowercase()
+        .find(&needle.to_ascii_lowercase())
+}
+
+fn append_child(stack: &mut [Element], node: Node) {
+    if let Some(parent) = stack.last_mut() {
+        parent.children.push(node);
+    }
+}
+
+fn push_text(stack: &mut [Element], text: &str) {
+    push_raw_text(stack, &decode_entities(text));
+}
+
+fn push_raw_text(stack: &mut [Element], text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(parent) = stack.last_mut() {
+        if let Some(Node::Text(existing)) = parent.children.last_mut() {
+            existing.push_str(text);
+            return;
+        }
+        parent.children.push(Node::Text(text.to_string()));
+    }
+}
+
+/// Closes the nearest open element with this name, implicitly closing any
+/// elements opened inside it. Stray end tags are ignored.
+fn close_element(stack: &mut Vec<Element>, name: &str) {
+    let Some(position) = stack.iter().rposition(|e| e.name == name) else {
+        return;
+    };
+    if position == 0 {
+        return;
+    }
+    while stack.len() > position {
+        let element = stack.pop().unwrap();
+        append_child(stack, Node::Element(element));
+    }
+}
+
+/// Closes elements whose end tag HTML lets authors omit, before opening
+/// `name`: a `<p>` ends at the next block, a `<li>` at the next item, and
+/// table rows and cells at the next row or cell.
+fn close_implied(stack: &mut Vec<Element>, name: &str) {
+    let (siblings, scope): (&[&str], &[&str]) = match name {
+        "li" => (&["li"], &["ul", "ol"]),
+        "dt" | "dd" => (&["dt", "dd"], &["dl"]),
+        "tr" => (&["tr"], &["table", "tbody", "thead", "tfoot"]),
+        "td" | "th" => (&["td", "th"], &["tr", "table"]),
+        "option" => (&["option"], &["select", "datalist"]),
+        _ => (&[], &[]),
+    };
+
+    let open = stack
+        .iter()
+        .rposition(|e| siblings.contains(&e.name.as_str()) || scope.contains(&e.name.as_str()));
+    if let Some(position) = open {
+        if position > 0 && siblings.contains(&stack[position].name.as_str()) {
+            let close = stack[position].name.clone();
+            close_element(stack, &close);
+        }
+    }
+
+    // Any block start closes an open paragraph if only inline elements
+    // were opened inside it
+    if BLOCK_ELEMENTS.contains(&name) && name != "br" {
+        if let Some(position) = stack.iter().rposition(|e| e.name == "p") {
+            if position > 0 && stack[position + 1..].iter().all(|e| !e.is_block()) {
+                close_element(stack, "p");
+            }
+        }
+    }
+}
+
+/// Decodes character references and the common named entities.
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let end = rest[1..]
+            .find(|c: char| c == ';' || c == '&' || c.is_whitespace())
+            .map(|i| i + 1);
+        match end {
+            Some(semi) if rest.as_bytes()[semi] == b';' => {
+                match decode_entity(&rest[1..semi]) {
+                    Some(decoded) => out.push(decoded),
+                    None => out.push_str(&rest[..=semi]),
+                }
+                rest = &rest[semi + 1..];
+            }
+            _ => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(numeric) = entity.strip_prefix('#') {
+        let code = match numeric.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => numeric.parse::<u32>().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    let c = match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "shy" => '\u{AD}',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "deg" => '°',
+        "middot" => '·',
+        "bull" => '•',
+        "dagger" => '†',
+        "Dagger" => '‡',
+        "sect" => '§',
+        "para" => '¶',
+        "times" => '×',
+        "eacute" => 'é',
+        "egrave" => 'è',
+        "aacute" => 'á',
+        "agrave" => 'à',
+        "uuml" => 'ü',
+        "ouml" => 'ö',
+        "auml" => 'ä',
+        "szlig" => 'ß',
+        "ccedil" => 'ç',
+        _ => return None,
+    };
+    Some(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nested_elements() {
+        let root =
+            parse("<html><body><p class=\"intro\">Hello <b>bold</b> world</p></body></html>");
+        let p = root.find(&|e| e.name == "p").unwrap();
+        assert_eq!(p.attr("class"), Some("intro"));
+        assert_eq!(p.text(), "Hello bold world");
+    }
+
+    #[test]
+    fn test_parse_attributes_unquoted_and_namespaced() {
+        let root = parse("<aside epub:type='footnote' id=fn1>Note</aside>");
+        let aside = root.find(&|e| e.name == "aside").unwrap();
+        assert_eq!(aside.attr("epub:type"), Some("footnote"));
+        assert_eq!(aside.attr("id"), Some("fn1"));
+        assert!(aside.attr_contains("epub:type", "footnote"));
+    }
+
+    #[test]
+    fn test_parse_skips_comments_doctype_and_scripts() {
+        let root = parse(
+            "<!DOCTYPE html><?xml version=\"1.0\"?><!-- hidden --><p>Shown</p><script>var a = '<p>';</script>",
+        );
+        assert_eq!(root.to_plain_text(), "Shown");
+    }
+
+    #[test]
+    fn test_parse_tolerates_unclosed_and_stray_tags() {
+        let root = parse("<div><p>One<p>Two</span></div>");
+        assert!(root.to_plain_text().contains("One"));
+        assert!(root.to_plain_text().contains("Two"));
+    }
+
+    #[test]
+    fn test_to_plain_text_one_line_per_block() {
+        let root = parse("<h1>Title</h1><p>First\n  paragraph</p><p>Second<br/>line</p>");
+        assert_eq!(root.to_plain_text(), "Title\nFirst paragraph\nSecond\nline");
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(decode_entities("Tom &amp; Jerry"), "Tom & Jerry");
+        assert_eq!(decode_entities("&#8220;hi&#x201D;"), "“hi”");
+        assert_eq!(decode_entities("a &unknown; b"), "a &unknown; b");
+        assert_eq!(decode_entities("fish & chips"), "fish & chips");
+    }
+
+    #[test]
+    fn test_rewrite_replaces_elements() {
+        let mut root = parse("<p>Keep <span class=\"x\">drop</span> this</p>");
+        root.rewrite(&mut |e| {
+            if e.attr_contains("class", "x") {
+                Some(vec![])
+            } else {
+                None
+            }
+        });
+        assert_eq!(root.to_plain_text(), "Keep this");
+    }
+
+    #[test]
+    fn test_implied_end_tags() {
+        let root = parse("<p>One<p>Two<ul><li>A<li>B</ul><table><tr><td>x<td>y<tr><td>z</table>");
+        let names: Vec<&str> = root
+            .children
+            .iter()
+            .filter_map(|n| match n {
+                Node::Element(e) => Some(e.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, vec!["p", "p", "ul", "table"]);
+        assert_eq!(root.to_plain_text(), "One\nTwo\nA\nB\nx\ny\nz");
+
+        let Some(Node::Element(table)) = root.children.last() else {
+            panic!("expected table");
+        };
+        assert_eq!(table.children.len(), 2);
+    }
+
+    #[test]
+    fn test_paragraph_not_closed_by_inline_or_nested_block() {
+        let root = parse("<p>Some <em>inline</em> text</p><div><p>In div</div>after");
+        assert_eq!(root.to_plain_text(), "Some inline text\nIn div\nafter");
+        let Some(Node::Element(div)) = root.children.get(1) else {
+            panic!("expected div");
+        };
+        assert_eq!(div.name, "div");
+        assert_eq!(div.children.len(), 1);
+    }
+}
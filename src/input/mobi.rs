@@ -0,0 +1,525 @@
+//! MOBI/AZW3 ebook loader
+//!
+//! MOBI files are Palm databases: record 0 holds the PalmDOC and MOBI
+//! headers plus the EXTH metadata block, and the following records hold the
+//! book's HTML, compressed with PalmDOC or HUFF/CDIC. AZW3 (KF8) books use
+//! the same layout, so both are read the same way. Plain PalmDOC e-texts
+//! (`TEXtREAd`) carry no markup and are read as text. DRM-protected books
+//! are rejected.
+//!
+//! The NCX/INDX index is not read, so `<mbp:pagebreak/>` tags split the book
+//! into chapters: each starts a section and `#ch3` selects the third.
+
+use super::compressed::MAX_DECOMPRESSED;
+use super::html::emit_block;
+use super::markup::{self, Element, Node};
+use super::palmdoc::{decompress_palmdoc, read_u16, read_u32, HuffDecoder};
+use super::structure::DocumentBuilder;
+use super::{LoadError, LoadOptions, LoadRange, LoadedDocument, Metadata, RangeUnit};
+use encoding_rs::WINDOWS_1252;
+use std::path::Path;
+
+/// Length of the Palm database header before the record list.
+const PDB_HEADER_LEN: usize = 78;
+
+const COMPRESSION_NONE: u16 = 1;
+const COMPRESSION_PALMDOC: u16 = 2;
+const COMPRESSION_HUFF: u16 = 17480;
+
+/// MOBI text encoding value for UTF-8; anything else is CP1252.
+const ENCODING_UTF8: u32 = 65001;
+
+const EXTH_AUTHOR: u32 = 100;
+const EXTH_UPDATED_TITLE: u32 = 503;
+const EXTH_LANGUAGE: u32 = 524;
+
+/// Load a MOBI, AZW or AZW3 file.
+///
+/// Purpose: Opens DRM-free Kindle-format books with headings and metadata.
+/// Big Picture: Enables `@book.mobi` in the command deck.
+/// Connections: Decompresses via input::palmdoc, parses HTML with
+/// input::markup and builds the document via input::structure.
+pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let data = std::fs::read(path)?;
+    load_bytes(&data, path, &LoadOptions::default())
+}
+
+/// Load a MOBI already read into memory, reading only the chapters in
+/// `options.range` if one is given; `path` is only used to name the source.
+pub fn load_bytes(
+    data: &[u8],
+    path: &Path,
+    options: &LoadOptions,
+) -> Result<LoadedDocument, LoadError> {
+    parse_mobi(data, format!("mobi:{}", path.display()), options.range)
+}
+
+fn parse_mobi(
+    data: &[u8],
+    source: String,
+    range: Option<LoadRange>,
+) -> Result<LoadedDocument, LoadError> {
+    if let Some(range) = range.filter(|r| r.unit != RangeUnit::Chapter) {
+        return Err(LoadError::InvalidRange(format!(
+            "{} selects pages; use #ch<first>-<last> for MOBI chapters",
+            range
+        )));
+    }
+    let book = MobiBook::parse(data).map_err(LoadError::MobiParse)?;
+    let text = book.text().map_err(LoadError::MobiParse)?;
+
+    let mut builder = DocumentBuilder::new();
+    if book.is_html {
+        let chapters: Vec<Element> = split_pagebreaks(&text)
+            .into_iter()
+            .map(markup::parse)
+            .filter(|root| {
+                let mut scratch = DocumentBuilder::new();
+                emit_bodies(root, &mut scratch);
+                !scratch.is_empty()
+            })
+            .collect();
+        let read = match range {
+            Some(range) => range.indices(chapters.len())?,
+            None => 0..chapters.len(),
+        };
+        for (idx, root) in chapters.iter().enumerate().take(read.end).skip(read.start) {
+            if chapters.len() > 1 {
+                builder.section(&format!("Section {}", idx + 1));
+            }
+            emit_bodies(root, &mut builder);
+        }
+    } else {
+        if let Some(range) = range {
+            range.indices(1)?;
+        }
+        for line in text.split(['\r', '\n']) {
+            builder.paragraph(line);
+        }
+    }
+
+    if builder.is_empty() {
+        return Err(LoadError::MobiParse(
+            "No text content found in book".to_string(),
+        ));
+    }
+
+    builder.build_with_metadata(source, &[], book.metadata)
+}
+
+/// Splits book HTML at `<mbp:pagebreak/>` tags.
+fn split_pagebreaks(html: &str) -> Vec<&str> {
+    let lower = html.to_ascii_lowercase();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    while let Some(found) = lower[start..].find("<mbp:pagebreak") {
+        let tag = start + found;
+        parts.push(&html[start..tag]);
+        start = lower[tag..]
+            .find('>')
+            .map_or(html.len(), |end| tag + end + 1);
+    }
+    parts.push(&html[start..]);
+    parts
+}
+
+/// Emits every `<body>`; KF8 text holds one HTML file per part.
+fn emit_bodies(root: &Element, builder: &mut DocumentBuilder) {
+    let mut bodies = Vec::new();
+    collect_bodies(root, &mut bodies);
+    if bodies.is_empty() {
+        emit_block(root, builder);
+    }
+    for body in bodies {
+        emit_block(body, builder);
+    }
+}
+
+fn collect_bodies<'a>(e: &'a Element, out: &mut Vec<&'a Element>) {
+    for child in &e.children {
+        if let Node::Element(child) = child {
+            if child.name == "body" {
+                out.push(child);
+            } else {
+                collect_bodies(child, out);
+            }
+        }
+    }
+}
+
+/// The parts of a Palm database book needed to extract its text.
+struct MobiBook<'a> {
+    records: Vec<&'a [u8]>,
+    compression: u16,
+    text_records: usize,
+    /// Flags for trailing entries appended to each text record.
+    extra_flags: u16,
+    huff_records: Option<(usize, usize)>,
+    utf8: bool,
+    is_html: bool,
+    metadata: Metadata,
+}
+
+impl<'a> MobiBook<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, String> {
+        if data.len() < PDB_HEADER_LEN {
+            return Err("File too short for a Palm database".to_string());
+        }
+        let kind = &data[60..68];
+        let is_html = match kind {
+            b"BOOKMOBI" => true,
+            b"TEXtREAd" => false,
+            _ => return Err("Not a MOBI or PalmDOC file".to_string()),
+        };
+
+        let records = split_records(data)?;
+        let header = *records.first().ok_or("Book has no records")?;
+
+        let compression = read_u16(header, 0)?;
+        let text_records = read_u16(header, 8)? as usize;
+        if read_u16(header, 12)? != 0 {
+            return Err("DRM-protected books are not supported".to_string());
+        }
+
+        let mut book = Self {
+            records,
+            compression,
+            text_records,
+            extra_flags: 0,
+            huff_records: None,
+            utf8: false,
+            is_html,
+            metadata: Metadata::default(),
+        };
+
+        if header.get(16..20) == Some(b"MOBI") {
+            book.read_mobi_header(header)?;
+        } else {
+            book.is_html = false;
+        }
+        Ok(book)
+    }
+
+    fn read_mobi_header(&mut self, header: &[u8]) -> Result<(), String> {
+        let header_len = read_u32(header, 20)? as usize;
+        let version = read_u32(header, 36)?;
+        self.utf8 = read_u32(header, 28)? == ENCODING_UTF8;
+
+        if self.compression == COMPRESSION_HUFF {
+            let first = read_u32(header, 0x70)? as usize;
+            let count = read_u32(header, 0x74)? as usize;
+            self.huff_records = Some((first, count));
+        }
+        if version >= 5 && header_len >= 0xE4 {
+            self.extra_flags = read_u16(header, 0xF2)?;
+        }
+
+        let name_offset = read_u32(header, 0x54)? as usize;
+        let name_len = read_u32(header, 0x58)? as usize;
+        if let Some(name) = header.get(name_offset..name_offset + name_len) {
+            self.metadata.title = non_empty(self.decode(name));
+        }
+
+        let has_exth = read_u32(header, 0x80)? & 0x40 != 0;
+        if has_exth {
+            self.read_exth(header, 16 + header_len);
+        }
+        Ok(())
+    }
+
+    /// Reads author, title and language from the EXTH block.
+    fn read_exth(&mut self, header: &[u8], start: usize) {
+        if header.get(start..start + 4) != Some(b"EXTH") {
+            return;
+        }
+        let Ok(count) = read_u32(header, start + 8) else {
+            return;
+        };
+
+        let mut authors = Vec::new();
+        let mut pos = start + 12;
+        for _ in 0..count {
+            let (Ok(kind), Ok(len)) = (read_u32(header, pos), read_u32(header, pos + 4)) else {
+                break;
+            };
+            let len = len as usize;
+            let Some(value) = header.get(pos + 8..pos + len.max(8)) else {
+                break;
+            };
+            let value = non_empty(self.decode(value));
+            match kind {
+                EXTH_AUTHOR => authors.extend(value),
+                EXTH_UPDATED_TITLE if value.is_some() => self.metadata.title = value,
+                EXTH_LANGUAGE => self.metadata.language = value,
+                _ => {}
+            }
+            pos += len.max(8);
+        }
+        if !authors.is_empty() {
+            self.metadata.author = Some(authors.join(", "));
+        }
+    }
+
+    /// Decompresses and decodes the book's text records.
+    fn text(&self) -> Result<String, String> {
+        let mut huff = match self.huff_records {
+            Some((first, count)) => {
+                let tables = self
+                    .records
+                    .get(first..first + count.max(1))
+                    .ok_or("HUFF records out of range")?;
+                Some(HuffDecoder::new(tables[0], &tables[1..])?)
+            }
+            None => None,
+        };
+
+        let mut bytes = Vec::new();
+        for record in self.records.iter().skip(1).take(self.text_records) {
+            let record = strip_trailing_entries(record, self.extra_flags);
+            match self.compression {
+                COMPRESSION_NONE => bytes.extend_from_slice(record),
+                COMPRESSION_PALMDOC => bytes.extend(decompress_palmdoc(record)),
+                COMPRESSION_HUFF => match huff.as_mut() {
+                    Some(decoder) => bytes.extend(decoder.decompress(record)?),
+                    None => return Err("Missing HUFF tables".to_string()),
+                },
+                other => return Err(format!("Unknown compression type {}", other)),
+            }
+            if bytes.len() as u64 > MAX_DECOMPRESSED {
+                return Err(format!("Text expands past {} MiB", MAX_DECOMPRESSED >> 20));
+            }
+        }
+        Ok(self.decode(&bytes))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        if self.utf8 {
+            String::from_utf8_lossy(bytes).into_owned()
+        } else {
+            WINDOWS_1252
+                .decode_without_bom_handling(bytes)
+                .0
+                .into_owned()
+        }
+    }
+}
+
+/// Splits a Palm database into its records using the record list.
+fn split_records(data: &[u8]) -> Result<Vec<&[u8]>, String> {
+    let count = read_u16(data, 76)? as usize;
+    let mut offsets = Vec::with_capacity(count + 1);
+    for i in 0..count {
+        offsets.push(read_u32(data, PDB_HEADER_LEN + i * 8)? as usize);
+    }
+    offsets.push(data.len());
+
+    offsets
+        .windows(2)
+        .map(|w| {
+            data.get(w[0]..w[1])
+                .ok_or_else(|| "Record offsets out of range".to_string())
+        })
+        .collect()
+}
+
+/// Removes the trailing entries MOBI appends to text records.
+///
+/// Each flag bit above bit 0 marks an entry whose size is stored as a
+/// backward-read variable-length integer at the end of the record; bit 0
+/// marks multibyte-character overlap bytes.
+fn strip_trailing_entries(record: &[u8], flags: u16) -> &[u8] {
+    let mut end = record.len();
+    let mut bits = flags >> 1;
+    while bits != 0 {
+        if bits & 1 != 0 {
+            let size = trailing_entry_size(&record[..end]);
+            end = end.saturating_sub(size);
+        }
+        bits >>= 1;
+    }
+    if flags & 1 != 0 && end > 0 {
+        let size = (record[end - 1] & 0x03) as usize + 1;
+        end = end.saturating_sub(size);
+    }
+    &record[..end]
+}
+
+fn trailing_entry_size(data: &[u8]) -> usize {
+    let mut size = 0;
+    for &byte in &data[data.len().saturating_sub(4)..] {
+        if byte & 0x80 != 0 {
+            size = 0;
+        }
+        size = (size << 7) | (byte & 0x7F) as usize;
+    }
+    size
+}
+
+fn non_empty(text: String) -> Option<String> {
+    let text = text.trim().trim_matches('\0').trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a BOOKMOBI database with uncompressed text and EXTH records.
+    fn build_mobi(html: &str, exth: &[(u32, &str)]) -> Vec<u8> {
+        let mut exth_block = b"EXTH".to_vec();
+        let body_len: usize = exth.iter().map(|(_, v)| v.len() + 8).sum();
+        exth_block.extend_from_slice(&(12 + body_len as u32).to_be_bytes());
+        exth_block.extend_from_slice(&(exth.len() as u32).to_be_bytes());
+        for (kind, value) in exth {
+            exth_block.extend_from_slice(&kind.to_be_bytes());
+            exth_block.extend_from_slice(&(value.len() as u32 + 8).to_be_bytes());
+            exth_block.extend_from_slice(value.as_bytes());
+        }
+
+        let name = b"Fallback Name";
+        let mut header = vec![0u8; 16 + 0xE8];
+        header[0..2].copy_from_slice(&COMPRESSION_NONE.to_be_bytes());
+        header[4..8].copy_from_slice(&(html.len() as u32).to_be_bytes());
+        header[8..10].copy_from_slice(&1u16.to_be_bytes());
+        header[16..20].copy_from_slice(b"MOBI");
+        header[20..24].copy_from_slice(&0xE8u32.to_be_bytes());
+        header[28..32].copy_from_slice(&ENCODING_UTF8.to_be_bytes());
+        header[36..40].copy_from_slice(&6u32.to_be_bytes());
+        header[0x80..0x84].copy_from_slice(&0x40u32.to_be_bytes());
+        header[0xF2..0xF4].copy_from_slice(&2u16.to_be_bytes());
+        header.extend_from_slice(&exth_block);
+        let name_offset = header.len() as u32;
+        header[0x54..0x58].copy_from_slice(&name_offset.to_be_bytes());
+        header[0x58..0x5C].copy_from_slice(&(name.len() as u32).to_be_bytes());
+        header.extend_from_slice(name);
+
+        // One text record with a 3-byte trailing entry (flag bit 1)
+        let mut text = html.as_bytes().to_vec();
+        text.extend_from_slice(&[0xAA, 0xBB, 0x83]);
+
+        let records = [header, text];
+        let mut data = vec![0u8; PDB_HEADER_LEN];
+        data[60..68].copy_from_slice(b"BOOKMOBI");
+        data[76..78].copy_from_slice(&(records.len() as u16).to_be_bytes());
+        let mut offset = PDB_HEADER_LEN + records.len() * 8;
+        for record in &records {
+            data.extend_from_slice(&(offset as u32).to_be_bytes());
+            data.extend_from_slice(&[0; 4]);
+            offset += record.len();
+        }
+        for record in &records {
+            data.extend_from_slice(record);
+        }
+        data
+    }
+
+    const BOOK: &str = "<html><head><guide></guide></head><body>\
+        <h1>Chapter One</h1><p>It was a bright cold day.</p><mbp:pagebreak/>\
+        <h2>Chapter Two</h2><p>The clocks were striking thirteen.</p></body></html>";
+
+    #[test]
+    fn test_mobi_text_and_sections() {
+        let data = build_mobi(BOOK, &[]);
+        let doc = parse_mobi(&data, "mobi:test".to_string(), None).unwrap();
+
+        let words: Vec<&str> = doc
+            .tokens
+            .iter()
+            .filter(|t| !t.text.is_empty())
+            .map(|t| t.text)
+            .collect();
+        assert_eq!(words.first(), Some(&"Chapter"));
+        assert_eq!(words.last(), Some(&"thirteen"));
+
+        let titles: Vec<(&str, u8)> = doc
+            .sections
+            .iter()
+            .map(|s| (s.title.as_str(), s.level))
+            .collect();
+        assert_eq!(titles, vec![("Chapter One", 1), ("Chapter Two", 2)]);
+    }
+
+    #[test]
+    fn test_mobi_pagebreaks_split_chapters() {
+        let book = "<html><body><p>Opening words.</p><mbp:pagebreak/>\
+            <div><p>Middle words.</p><MBP:PAGEBREAK /></div>\
+            <h1>Ending</h1><p>Last words.</p></body></html>";
+        let data = build_mobi(book, &[]);
+        let doc = parse_mobi(&data, "mobi:test".to_string(), None).unwrap();
+        let titles: Vec<&str> = doc.sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["Section 1", "Section 2", "Ending"]);
+        assert_eq!(
+            doc.tokens.get(doc.sections[1].token_index).unwrap().text,
+            "Middle"
+        );
+
+        let range = LoadRange::parse("ch2-3");
+        let doc = parse_mobi(&data, "mobi:test".to_string(), range).unwrap();
+        let words: Vec<&str> = doc
+            .tokens
+            .iter()
+            .filter(|t| !t.text.is_empty())
+            .map(|t| t.text)
+            .collect();
+        assert_eq!(words, vec!["Middle", "words", "Ending", "Last", "words"]);
+        assert_eq!(doc.sections.len(), 2);
+
+        let pages = parse_mobi(&data, "mobi:test".to_string(), LoadRange::parse("p1"));
+        assert!(matches!(pages, Err(LoadError::InvalidRange(_))));
+        let past_end = parse_mobi(&data, "mobi:test".to_string(), LoadRange::parse("ch4"));
+        assert!(matches!(past_end, Err(LoadError::InvalidRange(_))));
+    }
+
+    #[test]
+    fn test_mobi_metadata() {
+        let data = build_mobi(
+            BOOK,
+            &[
+                (EXTH_AUTHOR, "Orwell, George"),
+                (EXTH_UPDATED_TITLE, "Nineteen Eighty-Four"),
+                (EXTH_LANGUAGE, "en"),
+            ],
+        );
+        let doc = parse_mobi(&data, "mobi:test".to_string(), None).unwrap();
+        assert_eq!(doc.metadata.title.as_deref(), Some("Nineteen Eighty-Four"));
+        assert_eq!(doc.metadata.author.as_deref(), Some("Orwell, George"));
+        assert_eq!(doc.metadata.language.as_deref(), Some("en"));
+
+        let doc = parse_mobi(&build_mobi(BOOK, &[]), "mobi:test".to_string(), None).unwrap();
+        assert_eq!(doc.metadata.title.as_deref(), Some("Fallback Name"));
+    }
+
+    #[test]
+    fn test_mobi_rejects_drm_and_other_files() {
+        let mut data = build_mobi(BOOK, &[]);
+        let header_start = read_u32(&data, PDB_HEADER_LEN).unwrap() as usize;
+        data[header_start + 13] = 2;
+        assert!(matches!(
+            parse_mobi(&data, "mobi:test".to_string(), None),
+            Err(LoadError::MobiParse(msg)) if msg.contains("DRM")
+        ));
+
+        let result = parse_mobi(&[0; 100], "mobi:test".to_string(), None);
+        assert!(matches!(result, Err(LoadError::MobiParse(_))));
+    }
+
+    #[test]
+    fn test_strip_trailing_entries() {
+        // Entry of size 3 (flag bit 1), then one multibyte overlap byte (bit 0)
+        let record = [b'a', b'b', 0x00, 0x00, 0xAA, 0x83];
+        assert_eq!(strip_trailing_entries(&record, 0b11), b"ab");
+        assert_eq!(strip_trailing_entries(&record, 0), &record);
+    }
+
+    #[test]
+    fn test_mobi_load_nonexistent_file() {
+        let result = load("/nonexistent/path/book.mobi");
+        assert!(matches!(result, Err(LoadError::FileNotFound(_))));
+    }
+}
@@ -1,29 +1,196 @@
+use crate::reading::store::{StoreFull, TokenStore};
+use package::PackageError;
+pub use range::{LoadRange, RangeUnit};
+use std::io;
 use std::path::PathBuf;
+pub use structure::{DocumentBuilder, Section};
+pub use subtitle::Cue;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum LoadError {
-    #[error("PDF parse error: {0}")]
-    PdfParse(String),
+    #[error("I/O error")]
+    Io(#[from] io::Error),
+
+    #[error("PDF parse error")]
+    PdfParse(#[from] pdf_extract::Error),
+
+    #[error("Could not extract PDF text")]
+    PdfText(#[from] pdf_extract::OutputError),
 
     #[error("EPUB parse error: {0}")]
     EpubParse(String),
 
-    #[error("Clipboard error: {0}")]
-    Clipboard(String),
+    #[error("Invalid EPUB")]
+    Epub(#[from] ::epub::doc::DocError),
+
+    #[error("Could not read document package")]
+    Package(#[from] PackageError),
+
+    #[error("Clipboard error")]
+    Clipboard(#[from] arboard::Error),
 
     #[error("File not found: {0}")]
     FileNotFound(PathBuf),
 
     #[error("Unsupported format: {0}")]
     UnsupportedFormat(String),
+
+    #[error("HTML parse error: {0}")]
+    HtmlParse(String),
+
+    #[error("Text read error: {0}")]
+    TextRead(String),
+
+    #[error("Invalid range: {0}")]
+    InvalidRange(String),
+
+    #[error("DOCX parse error: {0}")]
+    DocxParse(String),
+
+    #[error("ODT parse error: {0}")]
+    OdtParse(String),
+
+    #[error("MOBI parse error: {0}")]
+    MobiParse(String),
+
+    #[error("FB2 parse error: {0}")]
+    Fb2Parse(String),
+
+    #[error("Subtitle parse error: {0}")]
+    SubtitleParse(String),
+
+    #[error("Notebook parse error: {0}")]
+    NotebookParse(String),
+
+    #[error("RST parse error: {0}")]
+    RstParse(String),
+
+    #[error("Org parse error: {0}")]
+    OrgParse(String),
+
+    #[error("Email parse error: {0}")]
+    EmailParse(String),
+
+    #[error("Decompression error")]
+    Decompress(#[source] io::Error),
+
+    #[error("Could not detect character encoding: {0}")]
+    InvalidEncoding(String),
+
+    #[error("Invalid file pattern: {0}")]
+    InvalidPattern(String),
+
+    #[error("Document too large")]
+    TooLarge(#[from] StoreFull),
+
+    #[error("Loading cancelled")]
+    Cancelled,
+}
+
+impl LoadError {
+    /// Advice for fixing the error, if there is any to give.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            LoadError::UnsupportedFormat(_) => Some(
+                "Supported formats: .pdf, .epub, .mobi, .azw3, .fb2, .html, .docx, .odt, \
+                 .ipynb, .rst, .org, .eml, .mbox, .srt, .vtt, .txt, .md, any of them gzip, \
+                 bzip2 or zstd compressed; @@ reads the clipboard",
+            ),
+            LoadError::InvalidEncoding(_) => {
+                Some("Start speedy with --encoding <NAME> to choose one")
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Options applied by loaders that support partial loading.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// Restricts loading to a page or chapter range.
+    pub range: Option<LoadRange>,
+    /// Character encoding of text inputs, overriding detection.
+    pub encoding: Option<&'static encoding_rs::Encoding>,
+    /// Keeps Project Gutenberg licence text and front matter.
+    pub keep_boilerplate: bool,
+}
+
+/// Bibliographic details recorded by the source format, if any.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    /// Language tag as given by the file, e.g. `en` or `ru-RU`.
+    pub language: Option<String>,
+    /// Character encoding plain text was decoded from, e.g. `windows-1252`.
+    pub encoding: Option<String>,
+}
+
+impl Metadata {
+    /// Human-readable "Title by Author" label, if a title is known.
+    pub fn label(&self) -> Option<String> {
+        let title = self.title.as_deref()?;
+        Some(match self.author.as_deref() {
+            Some(author) => format!("{} by {}", title, author),
+            None => title.to_string(),
+        })
+    }
 }
 
 pub struct LoadedDocument {
-    pub tokens: Vec<crate::reading::token::Token>,
+    pub tokens: TokenStore,
     pub source: String,
+    /// Headings in reading order; empty for formats without structure.
+    pub sections: Vec<Section>,
+    pub metadata: Metadata,
+    /// Subtitle cue timings; empty for other formats.
+    pub cues: Vec<Cue>,
+    /// Things the loader changed that the reader should know about, such as
+    /// removed boilerplate.
+    pub notices: Vec<String>,
+}
+
+impl LoadedDocument {
+    /// A document with no sections, cues, notices or metadata.
+    pub fn new(tokens: impl Into<TokenStore>, source: impl Into<String>) -> Self {
+        LoadedDocument {
+            tokens: tokens.into(),
+            source: source.into(),
+            sections: Vec::new(),
+            metadata: Metadata::default(),
+            cues: Vec::new(),
+            notices: Vec::new(),
+        }
+    }
 }
 
+pub mod background;
 pub mod clipboard;
+pub mod code;
+pub mod compressed;
+pub mod dispatch;
+pub mod docx;
+pub mod email;
+pub mod encoding;
 pub mod epub;
+pub mod fb2;
+pub mod gutenberg;
+pub mod html;
+pub mod json;
+pub mod markup;
+pub mod mobi;
+pub mod notebook;
+pub mod notes;
+pub mod odt;
+pub mod org;
+pub mod package;
+pub mod palmdoc;
 pub mod pdf;
+pub mod pdf_cleanup;
+pub mod queue;
+pub mod range;
+pub mod rst;
+pub mod structure;
+pub mod subtitle;
+pub mod text;
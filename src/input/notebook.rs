@@ -0,0 +1,444 @@
+//! Jupyter notebook loader
+//!
+//! Reads only the prose of an `.ipynb` file: the Markdown cells, in order.
+//! Code cells, their outputs and raw cells are skipped. Markdown headings
+//! (and the heading cells of version 3 notebooks) become sections; fenced
+//! code, display math, tables and HTML tags inside Markdown are dropped.
+
+use super::json::{self, Value};
+use super::structure::DocumentBuilder;
+use super::{LoadError, LoadedDocument, Metadata};
+use std::path::Path;
+
+/// Load a notebook file.
+///
+/// Purpose: Reads analysis write-ups without their code and output.
+/// Big Picture: Enables `@analysis.ipynb` in the command deck.
+/// Connections: Parses JSON with input::json and builds the document via
+/// input::structure.
+pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let data = std::fs::read(path)?;
+    load_bytes(&data, path)
+}
+
+/// Load a notebook already read into memory; `path` is only used to name the
+/// source.
+pub fn load_bytes(data: &[u8], path: &Path) -> Result<LoadedDocument, LoadError> {
+    let text = std::str::from_utf8(data)
+        .map_err(|_| LoadError::NotebookParse("Notebook is not valid UTF-8".to_string()))?;
+    let root = json::parse(text.trim_start_matches('\u{FEFF}'))
+        .map_err(|e| LoadError::NotebookParse(format!("Invalid JSON: {}", e)))?;
+
+    // Version 4 keeps cells at the top level, version 3 in worksheets
+    let cells: Vec<&Value> = match root.get("cells").and_then(Value::as_array) {
+        Some(cells) => cells.iter().collect(),
+        None => root
+            .get("worksheets")
+            .and_then(Value::as_array)
+            .ok_or_else(|| LoadError::NotebookParse("No cells found".to_string()))?
+            .iter()
+            .filter_map(|sheet| sheet.get("cells").and_then(Value::as_array))
+            .flatten()
+            .collect(),
+    };
+
+    let mut builder = DocumentBuilder::new();
+    let mut first_heading = None;
+    for cell in cells {
+        let source = cell_source(cell);
+        match cell.get("cell_type").and_then(Value::as_str) {
+            Some("markdown") => read_markdown(&source, &mut builder, &mut first_heading),
+            Some("heading") => {
+                let level = cell.get("level").and_then(Value::as_f64).unwrap_or(1.0);
+                let title = inline_text(source.trim());
+                record_heading(&mut first_heading, &builder, level as u8, &title);
+                builder.heading(level.clamp(1.0, 6.0) as u8, &title);
+            }
+            _ => {}
+        }
+    }
+
+    if builder.is_empty() {
+        return Err(LoadError::NotebookParse(
+            "No Markdown cells with text found".to_string(),
+        ));
+    }
+
+    let mut doc = builder.build(format!("notebook:{}", path.display()), &[])?;
+    doc.metadata = notebook_metadata(&root);
+    if doc.metadata.title.is_none() {
+        doc.metadata.title = first_heading;
+    }
+    Ok(doc)
+}
+
+/// A cell's source, stored either as one string or as a list of lines.
+fn cell_source(cell: &Value) -> String {
+    match cell.get("source").or_else(|| cell.get("input")) {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Title and authors from the notebook metadata, where some tools put them.
+fn notebook_metadata(root: &Value) -> Metadata {
+    let metadata = root.get("metadata");
+    let field = |key: &str| metadata.and_then(|m| m.get(key));
+
+    let authors: Vec<&str> = field("authors")
+        .and_then(Value::as_array)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|author| {
+            author
+                .get("name")
+                .and_then(Value::as_str)
+                .or(author.as_str())
+        })
+        .collect();
+    Metadata {
+        title: field("title")
+            .and_then(Value::as_str)
+            .filter(|t| !t.trim().is_empty())
+            .map(str::to_string),
+        author: (!authors.is_empty()).then(|| authors.join(", ")),
+        ..Metadata::default()
+    }
+}
+
+/// Remembers the document's first heading as its title if nothing precedes
+/// it and it is top-level.
+fn record_heading(first: &mut Option<String>, builder: &DocumentBuilder, level: u8, title: &str) {
+    if first.is_none() && level == 1 && builder.is_empty() && !title.is_empty() {
+        *first = Some(title.to_string());
+    }
+}
+
+/// Adds the headings and paragraphs of a Markdown cell.
+fn read_markdown(text: &str, builder: &mut DocumentBuilder, first_heading: &mut Option<String>) {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut in_math = false;
+    let mut skip_underline = false;
+
+    let flush = |paragraph: &mut Vec<&str>, builder: &mut DocumentBuilder| {
+        if !paragraph.is_empty() {
+            builder.paragraph(&inline_text(&paragraph.join(" ")));
+            paragraph.clear();
+        }
+    };
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if in_math {
+            in_math = !trimmed.ends_with("$$");
+            continue;
+        }
+        if std::mem::take(&mut skip_underline) {
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            flush(&mut paragraph, builder);
+            fence = Some(marker);
+            continue;
+        }
+        if let Some(math) = trimmed.strip_prefix("$$") {
+            flush(&mut paragraph, builder);
+            in_math = math.is_empty() || !math.ends_with("$$");
+            continue;
+        }
+
+        let heading = atx_heading(trimmed).or_else(|| {
+            // Setext heading: a line underlined by === or ---
+            let underline = lines.get(i + 1).map(|next| next.trim());
+            let level = underline.and_then(setext_level)?;
+            let starts_block = paragraph.is_empty() && !trimmed.is_empty() && !is_rule(trimmed);
+            starts_block.then_some((level, trimmed))
+        });
+        if let Some((level, title)) = heading {
+            flush(&mut paragraph, builder);
+            skip_underline = atx_heading(trimmed).is_none();
+            let title = inline_text(title);
+            record_heading(first_heading, builder, level, &title);
+            builder.heading(level, &title);
+            continue;
+        }
+
+        if trimmed.is_empty()
+            || trimmed.starts_with('|')
+            || is_rule(trimmed)
+            || setext_level(trimmed).is_some()
+            || (paragraph.is_empty() && line.starts_with("    "))
+        {
+            // Blank lines, tables, rules and indented code end a paragraph
+            flush(&mut paragraph, builder);
+            continue;
+        }
+
+        let content = trimmed.trim_start_matches('>').trim_start();
+        match list_item(content) {
+            Some(item) => {
+                flush(&mut paragraph, builder);
+                paragraph.push(item);
+            }
+            None => paragraph.push(content),
+        }
+    }
+    flush(&mut paragraph, builder);
+}
+
+/// `## Title` → (2, "Title"); closing hashes are dropped.
+fn atx_heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((level as u8, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+/// Level of a setext underline: `===` is 1 and `---` is 2.
+fn setext_level(line: &str) -> Option<u8> {
+    if line.len() >= 2 && line.chars().all(|c| c == '=') {
+        Some(1)
+    } else if line.len() >= 2 && line.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Thematic breaks: `***`, `___`, `---` or `- - -`.
+fn is_rule(line: &str) -> bool {
+    let marks: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3
+        && ['*', '_', '-']
+            .iter()
+            .any(|&m| marks.chars().all(|c| c == m))
+}
+
+/// Text of a bullet, numbered or task list item.
+fn list_item(line: &str) -> Option<&str> {
+    let rest = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|bullet| line.strip_prefix(bullet))
+        .or_else(|| {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            let rest = line[digits..]
+                .strip_prefix(". ")
+                .or_else(|| line[digits..].strip_prefix(") "));
+            rest.filter(|_| digits > 0)
+        })?
+        .trim_start();
+    Some(
+        ["[ ] ", "[x] ", "[X] "]
+            .iter()
+            .find_map(|task| rest.strip_prefix(task))
+            .unwrap_or(rest),
+    )
+}
+
+/// Reduces inline Markdown to its text.
+///
+/// Images and HTML tags are dropped, links keep their text, and emphasis,
+/// code and inline math markers are removed: `**Retention** rose in
+/// [Q3](report.html) for $n > 40$` reads "Retention rose in Q3 for n > 40".
+fn inline_text(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if chars.get(i + 1).is_some_and(|c| c.is_ascii_punctuation()) => {
+                out.push(chars[i + 1]);
+                i += 2;
+            }
+            '!' if chars.get(i + 1) == Some(&'[') => {
+                // Image: skip alt text and target
+                i = link_end(&chars, i + 1).unwrap_or(i + 2);
+            }
+            '[' => match link_end(&chars, i) {
+                Some(end) => {
+                    let close = i + chars[i..].iter().position(|&c| c == ']').unwrap_or(0);
+                    out.extend(&chars[i + 1..close]);
+                    i = end;
+                }
+                None => {
+                    out.push('[');
+                    i += 1;
+                }
+            },
+            '<' if chars[i..].starts_with(&['<', '!', '-', '-']) => {
+                // HTML comment
+                let text: String = chars[i..].iter().collect();
+                i += text
+                    .find("-->")
+                    .map_or(chars.len() - i, |end| text[..end + 3].chars().count());
+            }
+            '<' => match chars[i..].iter().position(|&c| c == '>') {
+                // HTML tag or autolink: keep an autolink's address
+                Some(len)
+                    if chars
+                        .get(i + 1)
+                        .is_some_and(|c| c.is_alphabetic() || *c == '/') =>
+                {
+                    let inner: String = chars[i + 1..i + len].iter().collect();
+                    if inner.contains("://") {
+                        out.push_str(&inner);
+                    }
+                    i += len + 1;
+                }
+                _ => {
+                    out.push('<');
+                    i += 1;
+                }
+            },
+            '*' if i > 0 && chars[i - 1] == ' ' && chars.get(i + 1) == Some(&' ') => {
+                // A lone asterisk, as in `2 * 3`
+                out.push('*');
+                i += 1;
+            }
+            '*' | '`' | '$' => i += 1,
+            '_' if !(i > 0
+                && chars[i - 1].is_alphanumeric()
+                && chars.get(i + 1).is_some_and(|c| c.is_alphanumeric())) =>
+            {
+                i += 1;
+            }
+            '~' if chars.get(i + 1) == Some(&'~') => i += 2,
+            c => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// End of a `[text](target)` or `[text][ref]` link starting at `start`.
+fn link_end(chars: &[char], start: usize) -> Option<usize> {
+    let close = start + chars[start..].iter().position(|&c| c == ']')?;
+    let closing = match chars.get(close + 1) {
+        Some('(') => ')',
+        Some('[') => ']',
+        _ => return None,
+    };
+    let end = close + 1 + chars[close + 1..].iter().position(|&c| c == closing)?;
+    Some(end + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_fixture() -> LoadedDocument {
+        load("tests/test_files/analysis.ipynb").unwrap()
+    }
+
+    fn words(doc: &LoadedDocument) -> String {
+        doc.tokens
+            .iter()
+            .map(|t| t.text)
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_markdown_cells_only() {
+        let doc = load_fixture();
+        let text = words(&doc);
+        assert!(text.starts_with("Churn Analysis"));
+        assert!(text.contains("Retention rose in Q3 for n > 40 cohorts"));
+        assert!(text.contains("Customers who finished onboarding stayed longer"));
+        assert!(!text.contains("import"));
+        assert!(!text.contains("DataFrame"));
+        assert!(!text.contains("plot.png"));
+        assert!(!text.contains("groupby"));
+        assert!(!text.contains("frac"));
+        assert_eq!(doc.metadata.title.as_deref(), Some("Churn Analysis"));
+        assert_eq!(doc.metadata.author.as_deref(), Some("Dana Reyes"));
+    }
+
+    #[test]
+    fn test_headings_become_sections() {
+        let doc = load_fixture();
+        let outline: Vec<_> = doc
+            .sections
+            .iter()
+            .map(|s| (s.title.as_str(), s.level))
+            .collect();
+        assert_eq!(
+            outline,
+            vec![
+                ("Churn Analysis", 1),
+                ("Method", 2),
+                ("Findings", 2),
+                ("Next steps", 3),
+            ]
+        );
+        assert_eq!(
+            doc.tokens.get(doc.sections[2].token_index).unwrap().text,
+            "Findings"
+        );
+    }
+
+    #[test]
+    fn test_version_3_heading_cells() {
+        let notebook = br##"{"nbformat": 3, "worksheets": [{"cells": [
+            {"cell_type": "heading", "level": 1, "source": ["Old ", "notebook"]},
+            {"cell_type": "code", "input": "print(1)"},
+            {"cell_type": "markdown", "source": "Still *readable*."}
+        ]}]}"##;
+        let doc = load_bytes(notebook, Path::new("old.ipynb")).unwrap();
+        assert_eq!(words(&doc), "Old notebook Still readable");
+        assert_eq!(doc.sections[0].level, 1);
+    }
+
+    #[test]
+    fn test_invalid_notebooks() {
+        let result = load_bytes(b"{\"cells\": [", Path::new("broken.ipynb"));
+        assert!(
+            matches!(result, Err(LoadError::NotebookParse(msg)) if msg.starts_with("Invalid JSON"))
+        );
+
+        let code_only = br#"{"cells": [{"cell_type": "code", "source": "x = 1"}]}"#;
+        let result = load_bytes(code_only, Path::new("code.ipynb"));
+        assert!(matches!(result, Err(LoadError::NotebookParse(_))));
+    }
+
+    #[test]
+    fn test_inline_markdown() {
+        assert_eq!(
+            inline_text("**Retention** rose in [Q3](report.html) ![chart](plot.png) for $n > 40$"),
+            "Retention rose in Q3 for n > 40"
+        );
+        assert_eq!(
+            inline_text("Use `snake_case` names, <b>not</b> ~~camel~~ \\*stars\\* <!-- todo -->"),
+            "Use snake_case names, not camel *stars*"
+        );
+        assert_eq!(
+            inline_text("2 * 3 <https://example.com>"),
+            "2 * 3 https://example.com"
+        );
+    }
+}
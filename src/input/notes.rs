@@ -0,0 +1,225 @@
+//! Footnote and endnote attachment
+//!
+//! Loaders remove note bodies from the reading stream and replace each note
+//! reference with an inline marker (see [`note_ref_marker`]). During
+//! tokenization, [`NoteAttacher`] strips the markers and attaches the note
+//! text to the token that carried the reference, so stray note numbers never
+//! interrupt a sentence.
+
+use crate::reading::timing::{detect_sentence_boundary, TokenSink};
+use crate::reading::token::Token;
+
+/// Private-use characters delimiting a note reference in extracted text.
+const NOTE_REF_START: char = '\u{E000}';
+const NOTE_REF_END: char = '\u{E001}';
+
+/// Separator used when several notes are referenced from the same word.
+const NOTE_SEPARATOR: &str = "\n\n";
+
+/// Returns the inline marker for the note at `index` in the loader's note list.
+///
+/// The leading space puts the marker in its own whitespace-separated word, so
+/// trailing punctuation of the referencing word is tokenized normally.
+pub fn note_ref_marker(index: usize) -> String {
+    format!(" {}{}{}", NOTE_REF_START, index, NOTE_REF_END)
+}
+
+/// Removes note reference markers from text that is not tokenized, such as
+/// heading titles.
+pub fn strip_note_markers(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(NOTE_REF_START) {
+        out.push_str(rest[..start].trim_end());
+        match rest[start..].find(NOTE_REF_END) {
+            Some(end) => rest = &rest[start + end + NOTE_REF_END.len_utf8()..],
+            None => rest = "",
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Splits a marker word into the note index and any text glued after it.
+fn parse_marker(text: &str) -> Option<(usize, &str)> {
+    let body = text.strip_prefix(NOTE_REF_START)?;
+    let end = body.find(NOTE_REF_END)?;
+    let index = body[..end].parse().ok()?;
+    Some((index, &body[end + NOTE_REF_END.len_utf8()..]))
+}
+
+fn add_note(token: &mut Token, note: &str) {
+    match &mut token.note {
+        Some(existing) => {
+            existing.push_str(NOTE_SEPARATOR);
+            existing.push_str(note);
+        }
+        None => token.note = Some(note.to_string()),
+    }
+}
+
+/// Removes note reference markers from a token stream and attaches the
+/// referenced note text to the preceding word.
+///
+/// Punctuation that followed the reference (as in `word[1].`) moves back onto
+/// the referencing word, and the sentence-start flag of the following token is
+/// recomputed so sentence navigation is unaffected by the removed markers.
+/// Markers pointing outside `notes` are dropped silently.
+pub fn attach_notes(tokens: Vec<Token>, notes: &[String]) -> Vec<Token> {
+    let mut attacher = NoteAttacher::new(Vec::with_capacity(tokens.len()), notes);
+    for token in tokens {
+        attacher.push(token);
+    }
+    attacher.into_inner()
+}
+
+/// [`attach_notes`] as tokens are produced: a [`TokenSink`] that passes
+/// words on to `inner` with the markers removed.
+pub struct NoteAttacher<'a, S> {
+    inner: S,
+    notes: &'a [String],
+    /// Notes referenced before any word, for the first one.
+    pending_notes: Vec<&'a str>,
+    recompute_next: bool,
+}
+
+impl<'a, S: TokenSink> NoteAttacher<'a, S> {
+    pub fn new(inner: S, notes: &'a [String]) -> Self {
+        Self {
+            inner,
+            notes,
+            pending_notes: Vec::new(),
+            recompute_next: false,
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: TokenSink> TokenSink for NoteAttacher<'_, S> {
+    fn push(&mut self, mut token: Token) {
+        if let Some((index, rest)) = parse_marker(&token.text) {
+            let note = self.notes.get(index).map(String::as_str);
+            let rest = rest.to_string();
+
+            match self.inner.last_word_mut() {
+                Some(previous) => {
+                    if let Some(note) = note {
+                        add_note(previous, note);
+                    }
+                    if rest.is_empty() {
+                        previous.punctuation.append(&mut token.punctuation);
+                    }
+                }
+                None => self.pending_notes.extend(note),
+            }
+
+            self.recompute_next = true;
+            if rest.is_empty() {
+                return;
+            }
+            token.text = rest;
+        }
+
+        if self.recompute_next {
+            let word = if token.is_newline() {
+                ""
+            } else {
+                token.text.as_str()
+            };
+            token.is_sentence_start = detect_sentence_boundary(self.inner.last(), word);
+            self.recompute_next = false;
+        }
+        if !token.is_newline() {
+            for note in self.pending_notes.drain(..) {
+                add_note(&mut token, note);
+            }
+        }
+        self.inner.push(token);
+    }
+
+    fn last(&self) -> Option<&Token> {
+        self.inner.last()
+    }
+
+    fn last_mut(&mut self) -> Option<&mut Token> {
+        self.inner.last_mut()
+    }
+
+    fn last_word_mut(&mut self) -> Option<&mut Token> {
+        self.inner.last_word_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reading::timing::tokenize_text;
+
+    #[test]
+    fn test_attach_note_to_referencing_word() {
+        let text = format!("A claim{} is made.", note_ref_marker(0));
+        let tokens = attach_notes(tokenize_text(&text), &["Source: X.".to_string()]);
+
+        let words: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(words, vec!["A", "claim", "is", "made"]);
+        assert_eq!(tokens[1].note.as_deref(), Some("Source: X."));
+        assert!(tokens.iter().filter(|t| t.note.is_some()).count() == 1);
+    }
+
+    #[test]
+    fn test_punctuation_after_reference_moves_back() {
+        let text = format!("It ends here{}. Next sentence", note_ref_marker(0));
+        let tokens = attach_notes(tokenize_text(&text), &["Note.".to_string()]);
+
+        assert_eq!(tokens[2].text, "here");
+        assert_eq!(tokens[2].punctuation, vec!['.']);
+        assert_eq!(tokens[2].note.as_deref(), Some("Note."));
+        assert_eq!(tokens[3].text, "Next");
+        assert!(tokens[3].is_sentence_start);
+    }
+
+    #[test]
+    fn test_reference_after_period_keeps_sentence_boundary() {
+        let text = format!("First one.{} Second one.", note_ref_marker(0));
+        let tokens = attach_notes(tokenize_text(&text), &["Note.".to_string()]);
+
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[1].punctuation, vec!['.']);
+        assert!(tokens[2].is_sentence_start);
+    }
+
+    #[test]
+    fn test_multiple_notes_on_one_word() {
+        let text = format!("word{}{} end", note_ref_marker(0), note_ref_marker(1));
+        let notes = vec!["One.".to_string(), "Two.".to_string()];
+        let tokens = attach_notes(tokenize_text(&text), &notes);
+
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].note.as_deref(), Some("One.\n\nTwo."));
+    }
+
+    #[test]
+    fn test_unknown_note_index_is_dropped() {
+        let text = format!("word{} end", note_ref_marker(7));
+        let tokens = attach_notes(tokenize_text(&text), &[]);
+
+        assert_eq!(tokens.len(), 2);
+        assert!(tokens[0].note.is_none());
+    }
+
+    #[test]
+    fn test_strip_note_markers() {
+        let text = format!("Heading{} text{}", note_ref_marker(0), note_ref_marker(12));
+        assert_eq!(strip_note_markers(&text), "Heading text");
+        assert_eq!(strip_note_markers("plain"), "plain");
+    }
+
+    #[test]
+    fn test_text_without_markers_is_unchanged() {
+        let tokens = tokenize_text("Hello. World\nagain");
+        assert_eq!(attach_notes(tokens.clone(), &[]), tokens);
+    }
+}
@@ -0,0 +1,250 @@
+//! OpenDocument Text (ODT) loader
+//!
+//! Walks `office:text` in `content.xml`. Headings come from `text:h` and its
+//! outline level, notes from inline `text:note` elements. The tracked-changes
+//! log (which holds deleted text) and annotations (comments) are skipped.
+
+use super::markup::{self, Element, Node};
+use super::notes::note_ref_marker;
+use super::package::Package;
+use super::structure::DocumentBuilder;
+use super::{LoadError, LoadedDocument};
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+/// Elements whose content is never read.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "text:tracked-changes",
+    "office:annotation",
+    "office:annotation-end",
+    "text:note-citation",
+    "text:sequence-decls",
+    "text:variable-decls",
+    "text:user-field-decls",
+    "table:table-columns",
+    "table:table-column",
+    "draw:frame",
+];
+
+/// Load an ODT file.
+///
+/// Purpose: Reads LibreOffice documents with headings, paragraphs and notes.
+/// Big Picture: Enables `@notes.odt` in the command deck.
+/// Connections: Reads the package via input::package, parses XML with
+/// input::markup and builds the document via input::structure.
+pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let package = Package::open(path)?;
+    load_package(package, format!("odt:{}", path.display()))
+}
+
+/// Load an ODT already read into memory; `path` is only used to name the source.
+pub fn load_bytes(data: &[u8], path: &Path) -> Result<LoadedDocument, LoadError> {
+    let package = Package::from_reader(Cursor::new(data))?;
+    load_package(package, format!("odt:{}", path.display()))
+}
+
+fn load_package<R: Read + Seek>(
+    mut package: Package<R>,
+    source: String,
+) -> Result<LoadedDocument, LoadError> {
+    let content = package.require_part("content.xml")?;
+
+    let root = markup::parse(&content);
+    let mut reader = OdtReader {
+        notes: Vec::new(),
+        builder: DocumentBuilder::new(),
+    };
+    if let Some(text) = root.find(&|e| e.name == "office:text") {
+        reader.read_blocks(text);
+    }
+
+    if reader.builder.is_empty() {
+        return Err(LoadError::OdtParse(
+            "No text content found in document".to_string(),
+        ));
+    }
+    reader.builder.build(source, &reader.notes)
+}
+
+struct OdtReader {
+    notes: Vec<String>,
+    builder: DocumentBuilder,
+}
+
+impl OdtReader {
+    /// Reads block-level content: headings, paragraphs, lists, tables and sections.
+    fn read_blocks(&mut self, container: &Element) {
+        for child in &container.children {
+            let Node::Element(e) = child else {
+                continue;
+            };
+            match e.name.as_str() {
+                name if SKIPPED_ELEMENTS.contains(&name) => {}
+                "text:h" => {
+                    let level = e
+                        .attr("text:outline-level")
+                        .and_then(|v| v.parse::<u8>().ok())
+                        .unwrap_or(1);
+                    let text = self.inline_text(e);
+                    self.builder.heading(level, &text);
+                }
+                "text:p" => {
+                    let text = self.inline_text(e);
+                    self.builder.paragraph(&text);
+                }
+                _ => self.read_blocks(e),
+            }
+        }
+    }
+
+    /// Collects the text of a paragraph, replacing notes with markers.
+    fn inline_text(&mut self, e: &Element) -> String {
+        let mut out = String::new();
+        self.collect_inline(e, &mut out);
+        out
+    }
+
+    fn collect_inline(&mut self, e: &Element, out: &mut String) {
+        for child in &e.children {
+            match child {
+                Node::Text(text) => out.push_str(text),
+                Node::Element(child) => match child.name.as_str() {
+                    name if SKIPPED_ELEMENTS.contains(&name) => {}
+                    "text:s" => {
+                        let count = child
+                            .attr("text:c")
+                            .and_then(|c| c.parse::<usize>().ok())
+                            .unwrap_or(1);
+                        out.push_str(&" ".repeat(count.min(16)));
+                    }
+                    "text:tab" | "text:line-break" => out.push(' '),
+                    "text:note" => {
+                        if let Some(body) = child.find(&|c| c.name == "text:note-body") {
+                            let text = note_text(body);
+                            if !text.is_empty() {
+                                self.notes.push(text);
+                                out.push_str(&note_ref_marker(self.notes.len() - 1));
+                            }
+                        }
+                    }
+                    _ => self.collect_inline(child, out),
+                },
+            }
+        }
+    }
+}
+
+/// Text of a note body, paragraphs joined by spaces.
+fn note_text(body: &Element) -> String {
+    let mut reader = OdtReader {
+        notes: Vec::new(),
+        builder: DocumentBuilder::new(),
+    };
+    let mut paragraphs = Vec::new();
+    body.walk(&mut |e| {
+        if e.name == "text:p" || e.name == "text:h" {
+            paragraphs.push(reader.inline_text(e));
+        }
+    });
+    paragraphs
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::package::test_support::build_package;
+    use crate::input::package::PackageError;
+
+    const CONTENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+<office:body><office:text>
+  <text:tracked-changes>
+    <text:changed-region text:id="ct1"><text:deletion><office:change-info><dc:creator>A</dc:creator></office:change-info><text:p>removed words</text:p></text:deletion></text:changed-region>
+  </text:tracked-changes>
+  <text:h text:outline-level="1">Field Notes</text:h>
+  <text:p>We walked<text:s/>the <text:span text:style-name="T1">ridge</text:span><text:change text:change-id="ct1"/> at dawn<text:note text:id="n1" text:note-class="footnote"><text:note-citation>1</text:note-citation><text:note-body><text:p>Around 5 a.m.</text:p></text:note-body></text:note>.</text:p>
+  <text:p><office:annotation><dc:creator>B</dc:creator><text:p>Check this date</text:p></office:annotation>Weather was clear.</text:p>
+  <text:list><text:list-item><text:p>First item</text:p></text:list-item><text:list-item><text:p>Second item</text:p></text:list-item></text:list>
+  <text:h text:outline-level="2">Camp</text:h>
+  <table:table><table:table-column/><table:table-row><table:table-cell><text:p>Tent</text:p></table:table-cell></table:table-row></table:table>
+</office:text></office:body></office:document-content>"#;
+
+    fn load_test_document() -> LoadedDocument {
+        let package = build_package(&[("content.xml", CONTENT)]);
+        load_package(
+            Package::from_reader(package).unwrap(),
+            "odt:test".to_string(),
+        )
+        .unwrap()
+    }
+
+    fn words(doc: &LoadedDocument) -> Vec<&str> {
+        doc.tokens
+            .iter()
+            .filter(|t| !t.text.is_empty())
+            .map(|t| t.text)
+            .collect()
+    }
+
+    #[test]
+    fn test_odt_paragraphs_lists_and_tables() {
+        let doc = load_test_document();
+        let words = words(&doc);
+
+        assert_eq!(&words[..4], &["Field", "Notes", "We", "walked"]);
+        assert!(words.contains(&"item"));
+        assert!(words.contains(&"Tent"));
+    }
+
+    #[test]
+    fn test_odt_skips_tracked_deletions_and_annotations() {
+        let doc = load_test_document();
+        let words = words(&doc);
+
+        assert!(!words.contains(&"removed"));
+        assert!(!words.contains(&"Check"));
+        assert!(words.contains(&"Weather"));
+    }
+
+    #[test]
+    fn test_odt_headings_and_notes() {
+        let doc = load_test_document();
+        let sections: Vec<(&str, u8)> = doc
+            .sections
+            .iter()
+            .map(|s| (s.title.as_str(), s.level))
+            .collect();
+        assert_eq!(sections, vec![("Field Notes", 1), ("Camp", 2)]);
+
+        let dawn = doc.tokens.iter().find(|t| t.text == "dawn").unwrap();
+        assert_eq!(dawn.note, Some("Around 5 a.m."));
+        assert_eq!(dawn.punctuation.as_str(), ".");
+        assert!(!words(&doc).contains(&"1"));
+    }
+
+    #[test]
+    fn test_odt_missing_content() {
+        let package = build_package(&[("meta.xml", "<office:document-meta/>")]);
+        let result = load_package(Package::from_reader(package).unwrap(), "odt:x".to_string());
+        assert!(matches!(
+            result,
+            Err(LoadError::Package(PackageError::Missing(_)))
+        ));
+    }
+
+    #[test]
+    fn test_odt_load_nonexistent_file() {
+        let result = load("/nonexistent/path/notes.odt");
+        assert!(matches!(result, Err(LoadError::FileNotFound(_))));
+    }
+}
@@ -0,0 +1,518 @@
+//! Org mode loader
+//!
+//! Reads the prose of an `.org` file: headlines become headings (their level
+//! is the number of stars) and body text is read with emphasis and link
+//! markup reduced to text. TODO keywords, priorities, tags, property drawers,
+//! planning lines, source and example blocks, tables and comments are
+//! skipped, as are subtrees marked `COMMENT`.
+
+use super::structure::DocumentBuilder;
+use super::{encoding, LoadError, LoadOptions, LoadedDocument, Metadata};
+use std::path::Path;
+
+/// TODO keywords recognised without a `#+TODO:` line.
+const DEFAULT_TODO_KEYWORDS: &[&str] = &["TODO", "DONE"];
+
+/// Blocks whose content is prose; every other `#+BEGIN_` block is skipped.
+const PROSE_BLOCKS: &[&str] = &["QUOTE", "VERSE", "CENTER"];
+
+/// Planning keywords that open a line of timestamps under a headline.
+const PLANNING_KEYWORDS: &[&str] = &["SCHEDULED:", "DEADLINE:", "CLOSED:"];
+
+/// Characters that mark emphasis: bold, italic, underline, verbatim, code
+/// and strike-through.
+const EMPHASIS_MARKERS: &[char] = &['*', '/', '_', '=', '~', '+'];
+
+/// Load an Org file.
+///
+/// Purpose: Reads notes and write-ups kept in Org mode.
+/// Big Picture: Enables `@analysis.org` in the command deck.
+/// Connections: Decodes via input::encoding and builds the document via
+/// input::structure.
+pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let data = std::fs::read(path)?;
+    load_bytes(&data, path, &LoadOptions::default())
+}
+
+/// Load an Org file already read into memory; `path` is only used to name
+/// the source.
+pub fn load_bytes(
+    data: &[u8],
+    path: &Path,
+    options: &LoadOptions,
+) -> Result<LoadedDocument, LoadError> {
+    let source = format!("org:{}", path.display());
+    let decoded = encoding::decode(data, options.encoding)
+        .ok_or_else(|| LoadError::InvalidEncoding(source.clone()))?;
+
+    let mut reader = OrgReader {
+        todo_keywords: DEFAULT_TODO_KEYWORDS
+            .iter()
+            .map(|k| k.to_string())
+            .collect(),
+        ..OrgReader::default()
+    };
+    reader.read(&decoded.text);
+    if reader.builder.is_empty() {
+        return Err(LoadError::OrgParse(
+            "No text content found in document".to_string(),
+        ));
+    }
+
+    let mut doc = reader
+        .builder
+        .build_with_metadata(source, &[], reader.metadata)?;
+    doc.metadata.encoding = Some(decoded.encoding.name().to_string());
+    Ok(doc)
+}
+
+#[derive(Default)]
+struct OrgReader {
+    builder: DocumentBuilder,
+    metadata: Metadata,
+    todo_keywords: Vec<String>,
+    paragraph: Vec<String>,
+}
+
+impl OrgReader {
+    fn read(&mut self, text: &str) {
+        let lines: Vec<&str> = text.lines().collect();
+        let mut i = 0;
+
+        while i < lines.len() {
+            let line = lines[i];
+            let trimmed = line.trim();
+            i += 1;
+
+            if let Some(level) = headline_level(line) {
+                self.flush();
+                match self.headline_title(&line[level + 1..]) {
+                    Some(title) => self
+                        .builder
+                        .heading(level.min(u8::MAX as usize) as u8, &title),
+                    // A COMMENT subtree: skip to the next headline at its level
+                    None => {
+                        while i < lines.len() && headline_level(lines[i]).is_none_or(|l| l > level)
+                        {
+                            i += 1;
+                        }
+                    }
+                }
+                continue;
+            }
+
+            if trimmed.is_empty() {
+                self.flush();
+                continue;
+            }
+
+            if let Some(block) = block_name(trimmed, "#+BEGIN_") {
+                self.flush();
+                let end = format!("#+END_{}", block);
+                let body_end = lines[i..]
+                    .iter()
+                    .position(|l| l.trim().to_uppercase().starts_with(&end))
+                    .map_or(lines.len(), |offset| i + offset);
+                if PROSE_BLOCKS.contains(&block.as_str()) {
+                    for body_line in &lines[i..body_end] {
+                        if body_line.trim().is_empty() || block == "VERSE" {
+                            self.flush();
+                        }
+                        if !body_line.trim().is_empty() {
+                            self.paragraph.push(body_line.trim().to_string());
+                        }
+                    }
+                    self.flush();
+                }
+                i = (body_end + 1).min(lines.len());
+                continue;
+            }
+
+            if let Some(keyword_line) = trimmed.strip_prefix("#+") {
+                self.flush();
+                self.keyword(keyword_line);
+                continue;
+            }
+
+            if trimmed.starts_with("\\begin{") {
+                // LaTeX environment
+                self.flush();
+                let environment = trimmed.trim_start_matches("\\begin{");
+                let end = format!("\\end{{{}", environment.split('}').next().unwrap_or(""));
+                while i < lines.len() && !lines[i - 1].contains(&end) {
+                    i += 1;
+                }
+                continue;
+            }
+
+            if is_drawer_start(trimmed) {
+                self.flush();
+                while i < lines.len() && !lines[i - 1].trim().eq_ignore_ascii_case(":END:") {
+                    i += 1;
+                }
+                continue;
+            }
+
+            if trimmed == "#"
+                || trimmed.starts_with("# ")
+                || trimmed == ":"
+                || trimmed.starts_with(": ")
+                || trimmed.starts_with('|')
+                || trimmed.starts_with("+-")
+                || (trimmed.len() >= 5 && trimmed.chars().all(|c| c == '-'))
+                || PLANNING_KEYWORDS.iter().any(|k| trimmed.starts_with(k))
+            {
+                // Comments, fixed-width lines, tables, rules, planning
+                self.flush();
+                continue;
+            }
+
+            match list_item(trimmed) {
+                Some(item) => {
+                    self.flush();
+                    self.paragraph.push(item);
+                }
+                None => self.paragraph.push(trimmed.to_string()),
+            }
+        }
+        self.flush();
+    }
+
+    /// Cleans a headline of its keyword, priority, cookies and tags.
+    ///
+    /// Returns `None` for a `COMMENT` headline, whose subtree is not read.
+    fn headline_title(&self, headline: &str) -> Option<String> {
+        let mut words: Vec<&str> = headline.split_whitespace().collect();
+        if words
+            .first()
+            .is_some_and(|w| self.todo_keywords.iter().any(|k| k == w))
+        {
+            words.remove(0);
+        }
+        if words.first().is_some_and(|w| is_priority(w)) {
+            words.remove(0);
+        }
+        if words.first() == Some(&"COMMENT") {
+            return None;
+        }
+        if words.last().is_some_and(|w| is_tags(w)) {
+            words.pop();
+        }
+        words.retain(|w| !is_statistics_cookie(w));
+        Some(inline_text(&words.join(" ")))
+    }
+
+    /// Reads a `#+KEYWORD: value` line for metadata and TODO keywords.
+    fn keyword(&mut self, line: &str) {
+        let Some((key, value)) = line.split_once(':') else {
+            return;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            return;
+        }
+        match key.to_uppercase().as_str() {
+            "TITLE" => self.metadata.title = Some(inline_text(value)),
+            "AUTHOR" => self.metadata.author = Some(inline_text(value)),
+            "LANGUAGE" => self.metadata.language = Some(value.to_string()),
+            "TODO" | "SEQ_TODO" | "TYP_TODO" => {
+                let keywords = value
+                    .split_whitespace()
+                    .filter(|k| *k != "|")
+                    // `WAIT(w@/!)` declares the keyword `WAIT`
+                    .map(|k| k.split('(').next().unwrap_or(k).to_string());
+                self.todo_keywords.extend(keywords);
+            }
+            _ => {}
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.paragraph.is_empty() {
+            self.builder
+                .paragraph(&inline_text(&self.paragraph.join(" ")));
+            self.paragraph.clear();
+        }
+    }
+}
+
+/// Number of stars of a headline (`** Title`), which must start the line.
+fn headline_level(line: &str) -> Option<usize> {
+    let stars = line.chars().take_while(|&c| c == '*').count();
+    (stars > 0 && line[stars..].starts_with(' ')).then_some(stars)
+}
+
+/// The upper-cased name after `prefix` in `#+BEGIN_SRC rust`, if the line
+/// starts with it (case-insensitively).
+fn block_name(line: &str, prefix: &str) -> Option<String> {
+    let upper = line.to_uppercase();
+    let rest = upper.strip_prefix(prefix)?;
+    rest.split_whitespace().next().map(str::to_string)
+}
+
+/// Drawer openings like `:PROPERTIES:` or `:LOGBOOK:`.
+fn is_drawer_start(line: &str) -> bool {
+    line.len() > 2
+        && line.starts_with(':')
+        && line.ends_with(':')
+        && line[1..line.len() - 1]
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        && !line.eq_ignore_ascii_case(":END:")
+}
+
+fn is_priority(word: &str) -> bool {
+    word.len() == 4 && word.starts_with("[#") && word.ends_with(']')
+}
+
+/// Tags such as `:work:urgent:`.
+fn is_tags(word: &str) -> bool {
+    word.len() > 2
+        && word.starts_with(':')
+        && word.ends_with(':')
+        && word.split(':').all(|tag| {
+            tag.chars()
+                .all(|c| c.is_alphanumeric() || "_@#%".contains(c))
+        })
+}
+
+/// Progress cookies such as `[2/5]` or `[40%]`.
+fn is_statistics_cookie(word: &str) -> bool {
+    word.strip_prefix('[')
+        .and_then(|w| w.strip_suffix(']'))
+        .is_some_and(|inner| {
+            inner
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '/' || c == '%')
+        })
+}
+
+/// Returns the text of a list item without its bullet or checkbox; a
+/// description item `- term :: text` reads "term: text".
+fn list_item(line: &str) -> Option<String> {
+    let rest = ["- ", "+ ", "* "]
+        .iter()
+        .find_map(|bullet| line.strip_prefix(bullet))
+        .or_else(|| {
+            let (marker, rest) = line.split_once(' ')?;
+            let number = marker
+                .strip_suffix('.')
+                .or_else(|| marker.strip_suffix(')'))?;
+            let enumerator = !number.is_empty()
+                && (number.chars().all(|c| c.is_ascii_digit())
+                    || (number.len() == 1 && number.chars().all(|c| c.is_ascii_alphabetic())));
+            enumerator.then_some(rest)
+        })?
+        .trim_start();
+
+    let rest = ["[ ] ", "[X] ", "[x] ", "[-] "]
+        .iter()
+        .find_map(|checkbox| rest.strip_prefix(checkbox))
+        .unwrap_or(rest);
+    Some(match rest.split_once(" :: ") {
+        Some((term, description)) => format!("{}: {}", term, description),
+        None => rest.to_string(),
+    })
+}
+
+/// Reduces inline markup to its text.
+///
+/// Links keep their description (`[[https://...][the docs]]` reads "the
+/// docs"), emphasis markers are removed, and footnote references,
+/// timestamps and macros are dropped.
+fn inline_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(['[', '<', '{']) {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if let Some(link) = tail.strip_prefix("[[") {
+            if let Some(end) = link.find("]]") {
+                let inner = &link[..end];
+                out.push_str(match inner.split_once("][") {
+                    Some((_, description)) => description,
+                    None => inner.trim_start_matches("file:"),
+                });
+                rest = &link[end + 2..];
+                continue;
+            }
+        } else if tail.starts_with("[fn:") {
+            if let Some(end) = tail.find(']') {
+                // Footnote reference; drop the space before it
+                if out.ends_with(' ') {
+                    out.pop();
+                }
+                rest = &tail[end + 1..];
+                continue;
+            }
+        } else if tail.starts_with("{{{") {
+            if let Some(end) = tail.find("}}}") {
+                rest = &tail[end + 3..];
+                continue;
+            }
+        } else if let Some(end) = timestamp_end(tail) {
+            if out.ends_with(' ') {
+                out.pop();
+            }
+            rest = &tail[end..];
+            continue;
+        }
+        out.push_str(&tail[..1]);
+        rest = &tail[1..];
+    }
+    out.push_str(rest);
+
+    out.split_whitespace()
+        .map(strip_emphasis)
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Length of an active or inactive timestamp (`<2024-03-01 Fri>`,
+/// `[2024-03-01 Fri 10:00]`) at the start of `text`.
+fn timestamp_end(text: &str) -> Option<usize> {
+    let close = match text.chars().next()? {
+        '<' => '>',
+        '[' => ']',
+        _ => return None,
+    };
+    let end = text.find(close)?;
+    let inner = &text[1..end];
+    let date = inner.get(..10)?;
+    let is_date = date.chars().enumerate().all(|(i, c)| {
+        if i == 4 || i == 7 {
+            c == '-'
+        } else {
+            c.is_ascii_digit()
+        }
+    });
+    is_date.then_some(end + 1)
+}
+
+/// Removes emphasis markers around a word, keeping surrounding punctuation:
+/// `(*bold*),` reads "(bold),". Markers inside words (`snake_case`, paths
+/// like `/usr/bin`) are kept.
+fn strip_emphasis(word: &str) -> String {
+    let lead = word
+        .find(|c: char| !"(\"'{".contains(c))
+        .unwrap_or(word.len());
+    let trail = word
+        .rfind(|c: char| !".,;:!?)\"'}".contains(c))
+        .map_or(lead, |i| {
+            i + word[i..].chars().next().map_or(0, char::len_utf8)
+        });
+    if trail <= lead {
+        return word.to_string();
+    }
+    let (prefix, core, suffix) = (&word[..lead], &word[lead..trail], &word[trail..]);
+
+    let opened = core.starts_with(EMPHASIS_MARKERS) && core.chars().count() > 1;
+    let closed = core.ends_with(EMPHASIS_MARKERS) && core.chars().count() > 1;
+    let mut core = core;
+    if opened && (closed || !core[1..].contains(EMPHASIS_MARKERS)) {
+        core = &core[1..];
+    }
+    if closed && (opened || !core[..core.len() - 1].contains(EMPHASIS_MARKERS)) {
+        core = &core[..core.len() - 1];
+    }
+    format!("{}{}{}", prefix, core, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_fixture() -> LoadedDocument {
+        load("tests/test_files/analysis.org").unwrap()
+    }
+
+    fn words(doc: &LoadedDocument) -> String {
+        doc.tokens
+            .iter()
+            .map(|t| t.text)
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_headlines_become_sections() {
+        let doc = load_fixture();
+        let outline: Vec<_> = doc
+            .sections
+            .iter()
+            .map(|s| (s.title.as_str(), s.level))
+            .collect();
+        assert_eq!(
+            outline,
+            vec![
+                ("Background", 1),
+                ("Data sources", 2),
+                ("Findings", 1),
+                ("Follow-up work", 2),
+                ("Footnotes", 1),
+            ]
+        );
+        assert_eq!(doc.metadata.title.as_deref(), Some("Churn Analysis"));
+        assert_eq!(doc.metadata.author.as_deref(), Some("Dana Reyes"));
+    }
+
+    #[test]
+    fn test_only_prose_is_read() {
+        let text = words(&load_fixture());
+        assert!(text.contains("Customers who finished onboarding stayed longer"));
+        assert!(text.contains("See the retention dashboard for weekly numbers"));
+        assert!(text.contains("Measure what matters"));
+        assert!(!text.contains("SELECT"));
+        assert!(!text.contains("PROPERTIES"));
+        assert!(!text.contains("SCHEDULED"));
+        assert!(!text.contains("Scratch"));
+        assert!(!text.contains("cohort |"));
+        assert!(!text.contains("draft"));
+    }
+
+    #[test]
+    fn test_inline_markup() {
+        assert_eq!(
+            inline_text("A *bold* and /italic/ [[https://example.com][link]] (=code=)."),
+            "A bold and italic link (code)."
+        );
+        assert_eq!(
+            inline_text("Noted[fn:1] on <2024-03-01 Fri> in /usr/bin and snake_case."),
+            "Noted on in /usr/bin and snake_case."
+        );
+        assert_eq!(inline_text("*two words* here"), "two words here");
+    }
+
+    #[test]
+    fn test_headline_cleanup() {
+        let reader = OrgReader {
+            todo_keywords: vec!["TODO".to_string(), "WAIT".to_string()],
+            ..OrgReader::default()
+        };
+        assert_eq!(
+            reader.headline_title("WAIT [#A] Ship the report [1/3] :work:q3:"),
+            Some("Ship the report".to_string())
+        );
+        assert_eq!(reader.headline_title("COMMENT Scratch"), None);
+    }
+
+    #[test]
+    fn test_list_items() {
+        assert_eq!(list_item("- [X] done"), Some("done".to_string()));
+        assert_eq!(list_item("1. first"), Some("first".to_string()));
+        assert_eq!(
+            list_item("+ Churn :: customers who left"),
+            Some("Churn: customers who left".to_string())
+        );
+        assert_eq!(list_item("Plain text"), None);
+    }
+}
@@ -0,0 +1,135 @@
+//! ZIP package access for office document loaders
+//!
+//! DOCX and ODT files are ZIP archives of XML parts. Loaders open the package
+//! once and read the parts they need as text; failures are a [`PackageError`]
+//! that keeps the underlying I/O or ZIP error as its source.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek};
+use std::path::Path;
+use thiserror::Error;
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+#[derive(Error, Debug)]
+pub enum PackageError {
+    #[error("Could not open package")]
+    Open(#[source] io::Error),
+
+    #[error("Not a valid package")]
+    Archive(#[source] ZipError),
+
+    #[error("Could not read {name}")]
+    Entry {
+        name: String,
+        #[source]
+        source: ZipError,
+    },
+
+    #[error("Could not read {name}")]
+    Read {
+        name: String,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Missing {0} in package")]
+    Missing(String),
+}
+
+/// An opened ZIP package.
+pub struct Package<R: Read + Seek> {
+    archive: ZipArchive<R>,
+}
+
+impl Package<BufReader<File>> {
+    /// Opens a package file from disk.
+    pub fn open(path: &Path) -> Result<Self, PackageError> {
+        let file = File::open(path).map_err(PackageError::Open)?;
+        Self::from_reader(BufReader::new(file))
+    }
+}
+
+impl<R: Read + Seek> Package<R> {
+    /// Opens a package from any seekable reader.
+    pub fn from_reader(reader: R) -> Result<Self, PackageError> {
+        ZipArchive::new(reader)
+            .map(|archive| Self { archive })
+            .map_err(PackageError::Archive)
+    }
+
+    /// Reads a part as UTF-8 text, or `None` if the package does not contain it.
+    pub fn read_part(&mut self, name: &str) -> Result<Option<String>, PackageError> {
+        let mut entry = match self.archive.by_name(name) {
+            Ok(entry) => entry,
+            Err(ZipError::FileNotFound) => return Ok(None),
+            Err(source) => {
+                return Err(PackageError::Entry {
+                    name: name.to_string(),
+                    source,
+                })
+            }
+        };
+        let mut text = String::new();
+        entry
+            .read_to_string(&mut text)
+            .map_err(|source| PackageError::Read {
+                name: name.to_string(),
+                source,
+            })?;
+        Ok(Some(text))
+    }
+
+    /// Reads a part that must exist.
+    pub fn require_part(&mut self, name: &str) -> Result<String, PackageError> {
+        self.read_part(name)?
+            .ok_or_else(|| PackageError::Missing(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+
+    /// Builds an in-memory ZIP package from `(name, content)` parts.
+    pub fn build_package(parts: &[(&str, &str)]) -> Cursor<Vec<u8>> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in parts {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        let mut cursor = writer.finish().unwrap();
+        cursor.set_position(0);
+        cursor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::build_package;
+    use super::*;
+
+    #[test]
+    fn test_read_parts() {
+        let mut package =
+            Package::from_reader(build_package(&[("word/document.xml", "<doc/>")])).unwrap();
+        assert_eq!(
+            package.read_part("word/document.xml").unwrap().as_deref(),
+            Some("<doc/>")
+        );
+        assert_eq!(package.read_part("missing.xml").unwrap(), None);
+        assert!(matches!(
+            package.require_part("missing.xml"),
+            Err(PackageError::Missing(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_package() {
+        let result = Package::from_reader(std::io::Cursor::new(b"not a zip".to_vec()));
+        assert!(matches!(result, Err(PackageError::Archive(_))));
+    }
+}
@@ -0,0 +1,298 @@
+//! Text record decompression for Palm database ebooks (MOBI/AZW)
+//!
+//! MOBI text records use one of two schemes: PalmDOC, a byte-oriented LZ77
+//! variant, or HUFF/CDIC, a Huffman code over a phrase dictionary whose
+//! entries may themselves be compressed. Both work one record at a time.
+
+/// Decompresses a PalmDOC (LZ77) record.
+pub fn decompress_palmdoc(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() * 2);
+    let mut i = 0;
+
+    while i < data.len() {
+        let c = data[i];
+        i += 1;
+        match c {
+            // Copy the next `c` bytes literally
+            0x01..=0x08 => {
+                let end = (i + c as usize).min(data.len());
+                out.extend_from_slice(&data[i..end]);
+                i = end;
+            }
+            // Literal byte
+            0x00 | 0x09..=0x7F => out.push(c),
+            // Back-reference: 11 bits of distance, 3 bits of length - 3
+            0x80..=0xBF => {
+                let Some(&next) = data.get(i) else {
+                    break;
+                };
+                i += 1;
+                let pair = (u16::from(c) << 8 | u16::from(next)) & 0x3FFF;
+                let distance = (pair >> 3) as usize;
+                let length = (pair & 0x07) as usize + 3;
+                if distance == 0 || distance > out.len() {
+                    continue;
+                }
+                // Byte by byte: the source may overlap what is being written
+                let start = out.len() - distance;
+                for k in 0..length {
+                    out.push(out[start + k]);
+                }
+            }
+            // Space followed by an ASCII character
+            0xC0..=0xFF => {
+                out.push(b' ');
+                out.push(c ^ 0x80);
+            }
+        }
+    }
+    out
+}
+
+/// Decoder for HUFF/CDIC compressed records.
+///
+/// Built from the HUFF record (code tables) and the CDIC records (phrase
+/// dictionary) that follow it in the database.
+pub struct HuffDecoder {
+    /// Indexed by the top 8 bits of a code: (code length, terminal, max code).
+    cache: Vec<(u32, bool, u64)>,
+    min_codes: Vec<u64>,
+    max_codes: Vec<u64>,
+    /// Phrases and whether each is already decompressed.
+    dictionary: Vec<(Vec<u8>, bool)>,
+}
+
+/// Maximum recursion depth for nested dictionary phrases.
+const MAX_PHRASE_DEPTH: usize = 32;
+
+/// Most bytes one text record or dictionary phrase may expand to. Records
+/// hold 4 KiB of text; the cap stops phrases that double at every level
+/// from expanding exponentially.
+pub const MAX_RECORD_OUTPUT: usize = 64 * 1024;
+
+impl HuffDecoder {
+    pub fn new(huff: &[u8], cdics: &[&[u8]]) -> Result<Self, String> {
+        if huff.len() < 16 || &huff[0..8] != b"HUFF\x00\x00\x00\x18" {
+            return Err("Invalid HUFF record".to_string());
+        }
+        let cache_offset = read_u32(huff, 8)? as usize;
+        let codes_offset = read_u32(huff, 12)? as usize;
+
+        let mut cache = Vec::with_capacity(256);
+        for i in 0..256 {
+            let v = read_u32(huff, cache_offset + i * 4)?;
+            let code_len = v & 0x1F;
+            if code_len == 0 {
+                return Err("Invalid HUFF code length".to_string());
+            }
+            let max_code = ((u64::from(v >> 8) + 1) << (32 - code_len)) - 1;
+            cache.push((code_len, v & 0x80 != 0, max_code));
+        }
+
+        // Code lengths 1..=32 each have a (min, max) pair; index 0 is unused
+        let mut min_codes = vec![0u64];
+        let mut max_codes = vec![u64::from(u32::MAX)];
+        for code_len in 1..=32u32 {
+            let pos = codes_offset + (code_len as usize - 1) * 8;
+            let min = u64::from(read_u32(huff, pos)?);
+            let max = u64::from(read_u32(huff, pos + 4)?);
+            min_codes.push(min << (32 - code_len));
+            max_codes.push(((max + 1) << (32 - code_len)) - 1);
+        }
+
+        let mut dictionary = Vec::new();
+        for cdic in cdics {
+            if cdic.len() < 16 || &cdic[0..8] != b"CDIC\x00\x00\x00\x10" {
+                return Err("Invalid CDIC record".to_string());
+            }
+            let phrases = read_u32(cdic, 8)? as usize;
+            let bits = read_u32(cdic, 12)?.min(16);
+            let count = (1usize << bits).min(phrases.saturating_sub(dictionary.len()));
+            for i in 0..count {
+                let offset = read_u16(cdic, 16 + i * 2)? as usize;
+                let len = read_u16(cdic, 16 + offset)?;
+                let start = 18 + offset;
+                let end = start + (len & 0x7FFF) as usize;
+                let phrase = cdic
+                    .get(start..end)
+                    .ok_or("CDIC phrase out of bounds")?
+                    .to_vec();
+                dictionary.push((phrase, len & 0x8000 != 0));
+            }
+        }
+
+        Ok(Self {
+            cache,
+            min_codes,
+            max_codes,
+            dictionary,
+        })
+    }
+
+    /// Decompresses one text record.
+    pub fn decompress(&mut self, data: &[u8]) -> Result<Vec<u8>, String> {
+        self.unpack(data, 0)
+    }
+
+    fn unpack(&mut self, data: &[u8], depth: usize) -> Result<Vec<u8>, String> {
+        if depth > MAX_PHRASE_DEPTH {
+            return Err("HUFF phrases nested too deeply".to_string());
+        }
+
+        let mut padded = data.to_vec();
+        padded.extend_from_slice(&[0; 8]);
+        let mut bits_left = data.len() as i64 * 8;
+        let mut pos = 0;
+        let mut x = read_u64(&padded, pos);
+        let mut n: i64 = 32;
+        let mut out = Vec::new();
+
+        loop {
+            if n <= 0 {
+                pos += 4;
+                x = read_u64(&padded, pos);
+                n += 32;
+            }
+            let code = (x >> n) & 0xFFFF_FFFF;
+
+            let (mut code_len, terminal, mut max_code) = self.cache[(code >> 24) as usize];
+            if !terminal {
+                while code_len < 32 && code < self.min_codes[code_len as usize] {
+                    code_len += 1;
+                }
+                max_code = self.max_codes[code_len as usize];
+            }
+
+            n -= i64::from(code_len);
+            bits_left -= i64::from(code_len);
+            if bits_left < 0 {
+                break;
+            }
+
+            let index = (max_code.wrapping_sub(code) >> (32 - code_len)) as usize;
+            let (phrase, done) = self
+                .dictionary
+                .get(index)
+                .cloned()
+                .ok_or("HUFF code outside dictionary")?;
+            let phrase = if done {
+                phrase
+            } else {
+                let unpacked = self.unpack(&phrase, depth + 1)?;
+                self.dictionary[index] = (unpacked.clone(), true);
+                unpacked
+            };
+            if out.len() + phrase.len() > MAX_RECORD_OUTPUT {
+                return Err(format!(
+                    "HUFF record expands past {} bytes",
+                    MAX_RECORD_OUTPUT
+                ));
+            }
+            out.extend_from_slice(&phrase);
+        }
+        Ok(out)
+    }
+}
+
+pub(crate) fn read_u16(data: &[u8], pos: usize) -> Result<u16, String> {
+    data.get(pos..pos + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or_else(|| format!("Unexpected end of data at offset {}", pos))
+}
+
+pub(crate) fn read_u32(data: &[u8], pos: usize) -> Result<u32, String> {
+    data.get(pos..pos + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| format!("Unexpected end of data at offset {}", pos))
+}
+
+fn read_u64(data: &[u8], pos: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    if let Some(slice) = data.get(pos..pos + 8) {
+        bytes.copy_from_slice(slice);
+    }
+    u64::from_be_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palmdoc_literals_and_spaces() {
+        // 0x02 copies two bytes; 0xC1 is a space plus 'A'
+        let data = [b'H', b'i', 0x02, b'x', b'y', 0xC1];
+        assert_eq!(decompress_palmdoc(&data), b"Hixy A");
+    }
+
+    #[test]
+    fn test_palmdoc_back_reference() {
+        // Distance 3, length 3 repeats "abc"; overlapping copies extend runs
+        let data = [b'a', b'b', b'c', 0x80, 0x18, b'.', 0x80, 0x12];
+        assert_eq!(decompress_palmdoc(&data), b"abcabc.c.c.c");
+    }
+
+    /// Builds HUFF/CDIC records with 8-bit terminal codes, so byte `b` selects
+    /// phrase `255 - b`.
+    fn build_tables(phrases: &[(&[u8], bool)]) -> (Vec<u8>, Vec<u8>) {
+        let mut huff = b"HUFF\x00\x00\x00\x18".to_vec();
+        huff.extend_from_slice(&24u32.to_be_bytes());
+        huff.extend_from_slice(&(24u32 + 1024).to_be_bytes());
+        huff.extend_from_slice(&[0; 8]);
+        for _ in 0..256 {
+            huff.extend_from_slice(&((255u32 << 8) | 0x80 | 8).to_be_bytes());
+        }
+        huff.extend_from_slice(&[0; 256]);
+
+        let mut cdic = b"CDIC\x00\x00\x00\x10".to_vec();
+        cdic.extend_from_slice(&(phrases.len() as u32).to_be_bytes());
+        cdic.extend_from_slice(&8u32.to_be_bytes());
+        let mut offset = phrases.len() * 2;
+        let mut bodies = Vec::new();
+        for (phrase, literal) in phrases {
+            cdic.extend_from_slice(&(offset as u16).to_be_bytes());
+            let flag = if *literal { 0x8000 } else { 0 };
+            bodies.extend_from_slice(&(phrase.len() as u16 | flag).to_be_bytes());
+            bodies.extend_from_slice(phrase);
+            offset += phrase.len() + 2;
+        }
+        cdic.extend_from_slice(&bodies);
+        (huff, cdic)
+    }
+
+    #[test]
+    fn test_huff_decodes_phrases() {
+        let (huff, cdic) = build_tables(&[
+            (b"Hello ", true),
+            (b"world", true),
+            (&[255], false), // Compressed: expands to phrase 0
+        ]);
+        let mut decoder = HuffDecoder::new(&huff, &[&cdic]).unwrap();
+
+        assert_eq!(decoder.decompress(&[255, 254]).unwrap(), b"Hello world");
+        assert_eq!(decoder.decompress(&[253, 254]).unwrap(), b"Hello world");
+    }
+
+    #[test]
+    fn test_huff_caps_phrase_expansion() {
+        // Each phrase is its predecessor twice, doubling at every level
+        let mut phrases: Vec<(Vec<u8>, bool)> = vec![(b"ab".to_vec(), true)];
+        for i in 1..24u8 {
+            phrases.push((vec![255 - (i - 1), 255 - (i - 1)], false));
+        }
+        let phrases: Vec<(&[u8], bool)> = phrases.iter().map(|(p, l)| (&p[..], *l)).collect();
+        let (huff, cdic) = build_tables(&phrases);
+        let mut decoder = HuffDecoder::new(&huff, &[&cdic]).unwrap();
+
+        assert_eq!(decoder.decompress(&[254]).unwrap(), b"abab");
+        let err = decoder.decompress(&[255 - 23]).unwrap_err();
+        assert!(err.contains("expands past"), "{}", err);
+    }
+
+    #[test]
+    fn test_huff_rejects_bad_records() {
+        assert!(HuffDecoder::new(b"NOPE", &[]).is_err());
+        let (huff, _) = build_tables(&[]);
+        assert!(HuffDecoder::new(&huff, &[b"CDIC"]).is_err());
+    }
+}
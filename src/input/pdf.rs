@@ -1,5 +1,8 @@
-use super::{LoadError, LoadedDocument};
-use crate::engine::tokenize_text;
+use super::background::{ProgressUnit, Reporter};
+use super::notes::note_ref_marker;
+use super::pdf_cleanup::{reflow, strip_page_furniture};
+use super::structure::tokenize_document;
+use super::{LoadError, LoadOptions, LoadRange, LoadedDocument, RangeUnit};
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
@@ -9,8 +12,17 @@ use std::path::Path;
 /// Purpose: Provides PDF file content as input source per PRD Section 2.2.
 /// Big Picture: Enables @filename.pdf command in REPL to load PDF content.
 /// PRD Reference: Section 2.2 (PDF support), Section 7.1 (@filename command)
-/// Connections: Depends on engine::tokenize_text() for tokenization.
+/// Connections: Depends on engine::tokenize_text() for tokenization,
+/// input::pdf_cleanup for layout cleanup and input::notes for footnote attachment.
 pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    load_with_options(path, &LoadOptions::default())
+}
+
+/// Load a PDF, extracting only the pages in `options.range` if one is given.
+///
+/// Pages outside the range are never rendered, so loading a chapter of a
+/// very long document only costs parsing the cross-reference table.
+pub fn load_with_options(path: &str, options: &LoadOptions) -> Result<LoadedDocument, LoadError> {
     let path = Path::new(path);
 
     if !path.exists() {
@@ -18,24 +30,210 @@ pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
     }
 
     // Read PDF file into memory
-    let mut file = File::open(path).map_err(|e| LoadError::PdfParse(e.to_string()))?;
+    let mut file = File::open(path)?;
     let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)
-        .map_err(|e| LoadError::PdfParse(e.to_string()))?;
-
-    // Extract text from memory
-    pdf_extract::extract_text_from_mem(&buffer)
-        .map_err(|e| LoadError::PdfParse(e.to_string()))
-        .map(|text| LoadedDocument {
-            tokens: tokenize_text(&text),
-            source: format!("pdf:{}", path.display()),
-        })
+    file.read_to_end(&mut buffer)?;
+
+    load_bytes(&buffer, path, options)
+}
+
+/// Load a PDF already read into memory; `path` is only used to name the source.
+pub fn load_bytes(
+    buffer: &[u8],
+    path: &Path,
+    options: &LoadOptions,
+) -> Result<LoadedDocument, LoadError> {
+    load_bytes_reporting(buffer, path, options, &Reporter::silent())
+}
+
+/// [`load_bytes`] reporting each extracted page to `reporter`, which also
+/// receives the pages read so far as partial documents.
+pub fn load_bytes_reporting(
+    buffer: &[u8],
+    path: &Path,
+    options: &LoadOptions,
+    reporter: &Reporter,
+) -> Result<LoadedDocument, LoadError> {
+    let source = match options.range {
+        Some(range) => format!("pdf:{}#{}", path.display(), range),
+        None => format!("pdf:{}", path.display()),
+    };
+
+    // Extract page by page so footnotes can be matched within their page
+    let pages = extract_pages(buffer, options.range, |pages, total| {
+        reporter.progress(pages.len(), total, ProgressUnit::Pages);
+        if reporter.partial_due(pages.len(), total) {
+            if let Ok(partial) = build_document(pages, source.clone()) {
+                reporter.partial(partial);
+            }
+        }
+        reporter.check_cancelled()
+    })?;
+
+    build_document(&pages, source)
+}
+
+/// Cleans up extracted pages and tokenizes them.
+fn build_document(pages: &[String], source: String) -> Result<LoadedDocument, LoadError> {
+    // Drop headers, footers and page numbers first so they are not mistaken
+    // for footnote continuation lines, then reflow the remaining body text
+    let mut notes = Vec::new();
+    let pages: Vec<String> = strip_page_furniture(pages)
+        .iter()
+        .map(|page| extract_page_notes(page, &mut notes))
+        .collect();
+    let text = reflow(&pages);
+
+    Ok(LoadedDocument::new(
+        tokenize_document(&text, None, &notes)?,
+        source,
+    ))
+}
+
+/// Extracts the text of each page in `range`, or of every page if `None`.
+///
+/// `on_page` is called with the pages so far and the number to extract
+/// after each page; an error from it stops extraction, as does a page that
+/// cannot be rendered.
+fn extract_pages(
+    buffer: &[u8],
+    range: Option<LoadRange>,
+    mut on_page: impl FnMut(&[String], usize) -> Result<(), LoadError>,
+) -> Result<Vec<String>, LoadError> {
+    if let Some(range) = range.filter(|r| r.unit != RangeUnit::Page) {
+        return Err(LoadError::InvalidRange(format!(
+            "{} selects chapters; use #p<first>-<last> for PDF pages",
+            range
+        )));
+    }
+
+    let mut doc = pdf_extract::Document::load_mem(buffer)?;
+    if doc.is_encrypted() {
+        doc.decrypt("")?;
+    }
+
+    let page_numbers: Vec<u32> = doc.get_pages().keys().copied().collect();
+    let selected = match range {
+        Some(range) => &page_numbers[range.indices(page_numbers.len())?],
+        None => &page_numbers[..],
+    };
+
+    let mut pages = Vec::with_capacity(selected.len());
+    for &page_num in selected {
+        let mut text = String::new();
+        {
+            let mut output = pdf_extract::PlainTextOutput::new(&mut text);
+            pdf_extract::output_doc_page(&doc, &mut output, page_num)?;
+        }
+        pages.push(text);
+        on_page(&pages, selected.len())?;
+    }
+    Ok(pages)
+}
+
+/// Removes the footnote block at the bottom of a page and replaces the
+/// matching reference numbers in the body with note markers.
+///
+/// A footnote block starts at the first line in the lower two thirds of the
+/// page that begins with a label (`3 Text...`, `* Text...`) whose number also
+/// appears glued to a word in the text above it (`word3`, `claim.3`). Lines up
+/// to the next referenced label belong to the same note.
+fn extract_page_notes(page: &str, notes: &mut Vec<String>) -> String {
+    let lines: Vec<&str> = page.lines().collect();
+    let search_from = (lines.len() / 3).max(1);
+
+    let block_start = (search_from..lines.len()).find(|&i| {
+        note_label(lines[i])
+            .map(|(label, _)| find_glued_ref(&lines[..i].join("\n"), label).is_some())
+            .unwrap_or(false)
+    });
+
+    let Some(block_start) = block_start else {
+        return page.to_string();
+    };
+
+    let mut body = lines[..block_start].join("\n");
+    let mut page_notes: Vec<(&str, String)> = Vec::new();
+
+    for line in &lines[block_start..] {
+        match note_label(line) {
+            Some((label, text)) if find_glued_ref(&body, label).is_some() => {
+                page_notes.push((label, text.to_string()));
+            }
+            _ => {
+                if let Some((_, note)) = page_notes.last_mut() {
+                    let line = line.trim();
+                    if !line.is_empty() {
+                        note.push(' ');
+                        note.push_str(line);
+                    }
+                }
+            }
+        }
+    }
+
+    for (label, text) in page_notes {
+        if let Some(position) = find_glued_ref(&body, label) {
+            notes.push(text);
+            body.replace_range(
+                position..position + label.len(),
+                &note_ref_marker(notes.len() - 1),
+            );
+        }
+    }
+
+    body
+}
+
+/// Splits a footnote line into its label and text (`"3 See X."` → `("3", "See X.")`).
+fn note_label(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start();
+    let label_len = if line.starts_with(|c: char| c.is_ascii_digit()) {
+        line.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(line.len())
+    } else {
+        line.chars()
+            .next()
+            .filter(|c| matches!(c, '*' | '†' | '‡' | '§'))
+            .map_or(0, char::len_utf8)
+    };
+    if label_len == 0 || label_len > 3 {
+        return None;
+    }
+
+    let (label, rest) = line.split_at(label_len);
+    let text = rest.strip_prefix(['.', ')']).unwrap_or(rest);
+    if !text.starts_with(char::is_whitespace) || text.trim().is_empty() {
+        return None;
+    }
+    Some((label, text.trim()))
+}
+
+/// Finds a reference label glued to the end of a word, such as the `3` in
+/// `result.3` or the `*` in `word*`, returning its byte offset.
+fn find_glued_ref(body: &str, label: &str) -> Option<usize> {
+    let numeric = label.starts_with(|c: char| c.is_ascii_digit());
+    body.match_indices(label).map(|(i, _)| i).find(|&i| {
+        let before = body[..i].chars().next_back();
+        let after = body[i + label.len()..].chars().next();
+        let glued = match before {
+            Some(c) if numeric => {
+                c.is_alphabetic()
+                    || matches!(c, '.' | ',' | ';' | ':' | ')' | '"' | '\'' | '’' | '”')
+            }
+            Some(c) => !c.is_whitespace(),
+            None => false,
+        };
+        let ends = after.is_none_or(|c| !c.is_alphanumeric());
+        glued && ends
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::engine::Token;
+    use crate::engine::{tokenize_text, Token};
+    use crate::input::notes::attach_notes;
 
     /// Test that load returns FileNotFound for non-existent files.
     #[test]
@@ -48,14 +246,13 @@ mod tests {
     /// Test that LoadedDocument has correct source field for PDF.
     #[test]
     fn test_loaded_document_source_pdf() {
-        let doc = LoadedDocument {
-            tokens: vec![Token {
-                text: "test".to_string(),
-                punctuation: vec![],
+        let doc = LoadedDocument::new(
+            vec![Token {
                 is_sentence_start: true,
+                ..Token::new("test")
             }],
-            source: "pdf:/path/to/document.pdf".to_string(),
-        };
+            "pdf:/path/to/document.pdf",
+        );
 
         assert!(doc.source.starts_with("pdf:"));
     }
@@ -63,20 +260,17 @@ mod tests {
     /// Test that LoadedDocument tokens preserve punctuation from PDF text.
     #[test]
     fn test_loaded_document_tokens_preserve_punctuation() {
-        let doc = LoadedDocument {
-            tokens: tokenize_text("This is a test. It works!"),
-            source: "pdf:test.pdf".to_string(),
-        };
+        let doc = LoadedDocument::new(tokenize_text("This is a test. It works!"), "pdf:test.pdf");
 
         // Verify multiple sentences are tokenized correctly
         assert!(doc.tokens.len() >= 3);
         // First token should be sentence start
-        assert!(doc.tokens[0].is_sentence_start);
+        assert!(doc.tokens.get(0).unwrap().is_sentence_start);
         // Tokens with periods should mark next token as sentence start
         for (i, token) in doc.tokens.iter().enumerate() {
             if token.text == "test" && i < doc.tokens.len() - 1 {
                 assert!(
-                    doc.tokens[i + 1].is_sentence_start,
+                    doc.tokens.get(i + 1).unwrap().is_sentence_start,
                     "Token after 'test.' should be sentence start"
                 );
             }
@@ -86,9 +280,69 @@ mod tests {
     /// Test PDF-specific error type.
     #[test]
     fn test_pdf_parse_error() {
-        // This would test actual PDF parsing errors if we had a way to trigger them
-        // For now, we verify the error type structure
-        let err = LoadError::PdfParse("Invalid PDF structure".to_string());
-        assert!(matches!(err, LoadError::PdfParse(msg) if msg.contains("Invalid")));
+        let result = load_bytes(b"not a pdf", Path::new("x.pdf"), &LoadOptions::default());
+        let Err(err) = result else {
+            panic!("garbage loaded as a PDF");
+        };
+        assert!(matches!(err, LoadError::PdfParse(_)));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    /// Test that a page-bottom footnote is removed and attached to its reference.
+    #[test]
+    fn test_extract_page_notes_footnote_block() {
+        let page = "Results were significant.1 We continue the\n\
+                    argument on this page with more words.\n\
+                    Another body line here.\n\
+                    1 Smith, 2001, p. 4.\n\
+                    continued note text.";
+        let mut notes = Vec::new();
+        let body = extract_page_notes(page, &mut notes);
+
+        assert_eq!(
+            notes,
+            vec!["Smith, 2001, p. 4. continued note text.".to_string()]
+        );
+        assert!(!body.contains("Smith"));
+
+        let tokens = attach_notes(tokenize_text(&body), &notes);
+        let word = tokens.iter().find(|t| t.text == "significant").unwrap();
+        assert_eq!(word.punctuation, vec!['.']);
+        assert!(word.note.is_some());
+        assert!(
+            tokens[3].is_sentence_start,
+            "'We' should still start a sentence"
+        );
+    }
+
+    /// Test that numbered body lines without a matching reference are kept.
+    #[test]
+    fn test_extract_page_notes_keeps_numbered_body_lines() {
+        let page = "Steps to follow:\nfirst do this\n2 apples are needed\n3 pears too";
+        let mut notes = Vec::new();
+        let body = extract_page_notes(page, &mut notes);
+
+        assert!(notes.is_empty());
+        assert_eq!(body, page);
+    }
+
+    /// Test footnote label parsing.
+    #[test]
+    fn test_note_label() {
+        assert_eq!(note_label("3 See X."), Some(("3", "See X.")));
+        assert_eq!(note_label("12. Note"), Some(("12", "Note")));
+        assert_eq!(note_label("* Starred"), Some(("*", "Starred")));
+        assert_eq!(note_label("2001 was a year"), None);
+        assert_eq!(note_label("3"), None);
+        assert_eq!(note_label("Text"), None);
+    }
+
+    /// Test glued reference detection.
+    #[test]
+    fn test_find_glued_ref() {
+        assert_eq!(find_glued_ref("word3 more", "3"), Some(4));
+        assert_eq!(find_glued_ref("claim.\u{201D}12 more", "12"), Some(9));
+        assert_eq!(find_glued_ref("in 3 days", "3"), None);
+        assert_eq!(find_glued_ref("word31", "3"), None);
     }
 }
@@ -0,0 +1,478 @@
+//! Post-processing for text extracted from PDF pages
+//!
+//! `pdf_extract` returns the visual lines of each page, including running
+//! headers, footers and page numbers, with words hyphenated at line ends.
+//! Read word by word this turns into noise, so the PDF loader runs the pages
+//! through two steps before tokenization:
+//!
+//! 1. [`strip_page_furniture`] drops page numbers and the header and footer
+//!    lines that repeat from page to page.
+//! 2. [`reflow`] joins hyphenated line breaks and merges the visual lines into
+//!    paragraphs, one paragraph per output line.
+
+use std::collections::HashMap;
+
+/// Number of non-empty lines at the top and bottom of a page checked for furniture.
+const FURNITURE_LINES: usize = 2;
+
+/// Minimum number of pages a line must repeat on to count as a running header or footer.
+const MIN_REPEATS: usize = 3;
+
+/// Lines shorter than this fraction of the median line length end a paragraph
+/// when they end a sentence.
+const SHORT_LINE_RATIO: f64 = 0.7;
+
+/// Words after a line-end hyphen that indicate a suspended compound
+/// ("pre- and post-war") rather than a hyphenated word.
+const SUSPENDED_HYPHEN_WORDS: &[&str] = &["and", "or", "to", "nor", "und", "oder", "et", "ou"];
+
+/// Highest Roman page number recognised; front matter rarely runs longer, and
+/// the cap keeps words such as "mix" or "civil" from counting.
+const MAX_ROMAN_PAGE: u32 = 50;
+
+/// Roman numerals up to [`MAX_ROMAN_PAGE`], largest first.
+const ROMAN_DIGITS: &[(u32, &str)] = &[
+    (50, "l"),
+    (40, "xl"),
+    (10, "x"),
+    (9, "ix"),
+    (5, "v"),
+    (4, "iv"),
+    (1, "i"),
+];
+
+/// Removes page numbers and running headers/footers from each page.
+///
+/// Only the first and last [`FURNITURE_LINES`] non-empty lines of a page are
+/// candidates. A candidate is removed if it is a bare page number (`12`,
+/// `- 12 -`, `Page 12 of 40`, `xii`), if the identical line starts or ends at
+/// least [`MIN_REPEATS`] pages, or if it repeats with a number that advances
+/// with the page (`Journal of Things 34 (2)  117`).
+pub fn strip_page_furniture(pages: &[String]) -> Vec<String> {
+    let page_lines: Vec<Vec<&str>> = pages.iter().map(|p| p.lines().collect()).collect();
+    let min_repeats = MIN_REPEATS.min(pages.len()).max(2);
+
+    // Group edge lines by shape (digits masked) and remember where they occur
+    let mut groups: HashMap<String, Vec<(usize, &str)>> = HashMap::new();
+    for (page_index, lines) in page_lines.iter().enumerate() {
+        for line_index in edge_line_indices(lines) {
+            let line = lines[line_index].trim();
+            groups
+                .entry(furniture_key(line))
+                .or_default()
+                .push((page_index, line));
+        }
+    }
+
+    let furniture_keys: Vec<&String> = groups
+        .iter()
+        .filter(|(_, occurrences)| is_running_furniture(occurrences, min_repeats))
+        .map(|(key, _)| key)
+        .collect();
+
+    page_lines
+        .iter()
+        .map(|lines| {
+            let edges = edge_line_indices(lines);
+            lines
+                .iter()
+                .enumerate()
+                .filter(|(i, line)| {
+                    !edges.contains(i)
+                        || !(is_page_number(line)
+                            || furniture_keys.contains(&&furniture_key(line.trim())))
+                })
+                .map(|(_, line)| *line)
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect()
+}
+
+/// Indices of the first and last non-empty lines of a page.
+fn edge_line_indices(lines: &[&str]) -> Vec<usize> {
+    let non_empty: Vec<usize> = (0..lines.len())
+        .filter(|&i| !lines[i].trim().is_empty())
+        .collect();
+    let mut indices: Vec<usize> = non_empty.iter().take(FURNITURE_LINES).copied().collect();
+    for &i in non_empty.iter().rev().take(FURNITURE_LINES) {
+        if !indices.contains(&i) {
+            indices.push(i);
+        }
+    }
+    indices
+}
+
+/// Shape of a line for furniture matching: lowercase, whitespace collapsed,
+/// every run of digits replaced by `#`.
+fn furniture_key(line: &str) -> String {
+    let mut key = String::new();
+    let mut in_number = false;
+    for c in line
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+    {
+        if c.is_ascii_digit() {
+            if !in_number {
+                key.push('#');
+            }
+            in_number = true;
+        } else {
+            key.push(c);
+            in_number = false;
+        }
+    }
+    key
+}
+
+/// Decides whether a group of same-shaped edge lines is a running header or footer.
+fn is_running_furniture(occurrences: &[(usize, &str)], min_repeats: usize) -> bool {
+    let mut pages: Vec<usize> = occurrences.iter().map(|(page, _)| *page).collect();
+    pages.dedup();
+    if pages.len() < min_repeats {
+        return false;
+    }
+
+    let (first_page, first_line) = occurrences[0];
+    if occurrences.iter().all(|(_, line)| *line == first_line) {
+        return true;
+    }
+
+    // Lines that differ only by number are furniture when the number tracks
+    // the page number; chapter headings ("Chapter 3") do not.
+    let Some(first_number) = last_number(first_line) else {
+        return false;
+    };
+    let offset = first_number as i64 - first_page as i64;
+    let tracking = occurrences
+        .iter()
+        .filter(|(page, line)| last_number(line).map(|n| n as i64 - *page as i64) == Some(offset))
+        .count();
+    tracking * 3 >= occurrences.len() * 2
+}
+
+fn last_number(line: &str) -> Option<u64> {
+    line.split(|c: char| !c.is_ascii_digit())
+        .rfind(|part| !part.is_empty())
+        .and_then(|part| part.parse().ok())
+}
+
+/// Returns true for lines that only carry a page number.
+fn is_page_number(line: &str) -> bool {
+    let lower = line.trim().to_lowercase();
+    let text = lower
+        .trim_matches(|c: char| {
+            matches!(c, '-' | '–' | '—' | '|' | '[' | ']' | '(' | ')') || c.is_whitespace()
+        })
+        .trim_start_matches("page")
+        .trim_start_matches("p.")
+        .trim();
+    if text.is_empty() {
+        return false;
+    }
+
+    let number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+    match text.split_once(" of ").or_else(|| text.split_once('/')) {
+        Some((page, total)) => number(page.trim()) && number(total.trim()),
+        None => number(text) || roman_page(text).is_some(),
+    }
+}
+
+/// Value of a lowercase Roman numeral written the standard way, up to
+/// [`MAX_ROMAN_PAGE`]; `None` for anything else ("ill", "iiii", "ixi").
+fn roman_page(text: &str) -> Option<u32> {
+    if !text.chars().all(|c| matches!(c, 'i' | 'v' | 'x' | 'l')) {
+        return None;
+    }
+    (1..=MAX_ROMAN_PAGE).find(|&n| to_roman(n) == text)
+}
+
+fn to_roman(mut n: u32) -> String {
+    let mut numeral = String::new();
+    for &(digit, letters) in ROMAN_DIGITS {
+        while n >= digit {
+            numeral.push_str(letters);
+            n -= digit;
+        }
+    }
+    numeral
+}
+
+/// Joins hyphenated line breaks and merges visual lines into paragraphs.
+///
+/// Pages continue each other: a paragraph running over a page break stays one
+/// paragraph. A paragraph ends at a blank line, or after a line that ends a
+/// sentence while being clearly shorter than a full line. Short lines that
+/// stand between paragraphs (headings) are kept on their own.
+pub fn reflow(pages: &[String]) -> String {
+    let lines: Vec<&str> = pages
+        .iter()
+        .flat_map(|page| {
+            let lines: Vec<&str> = page.lines().map(str::trim).collect();
+            let start = lines
+                .iter()
+                .position(|l| !l.is_empty())
+                .unwrap_or(lines.len());
+            let end = lines
+                .iter()
+                .rposition(|l| !l.is_empty())
+                .map_or(start, |i| i + 1);
+            lines[start..end].to_vec()
+        })
+        .collect();
+
+    let full_line = median_line_length(&lines) as f64;
+    let is_short = |line: &str| (line.chars().count() as f64) < full_line * SHORT_LINE_RATIO;
+
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for line in lines {
+        if line.is_empty() {
+            flush_paragraph(&mut paragraphs, &mut current);
+            continue;
+        }
+
+        if current.is_empty() {
+            current.push_str(line);
+        } else if let Some(stem) = hyphenated_stem(&current, line) {
+            current.truncate(stem);
+            current.push_str(line);
+        } else {
+            current.push(' ');
+            current.push_str(line);
+        }
+
+        let ends_sentence = line.ends_with(['.', '?', '!', ':', '"', '”', '’', ')']);
+        let is_heading = current == line && !line.ends_with([',', ';', '-']);
+        if is_short(line) && (ends_sentence || is_heading) {
+            flush_paragraph(&mut paragraphs, &mut current);
+        }
+    }
+    flush_paragraph(&mut paragraphs, &mut current);
+
+    paragraphs
+        .iter()
+        .map(|p| join_split_hyphenation(p))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn flush_paragraph(paragraphs: &mut Vec<String>, current: &mut String) {
+    if !current.is_empty() {
+        paragraphs.push(std::mem::take(current));
+    }
+}
+
+fn median_line_length(lines: &[&str]) -> usize {
+    let mut lengths: Vec<usize> = lines
+        .iter()
+        .filter(|l| !l.is_empty())
+        .map(|l| l.chars().count())
+        .collect();
+    if lengths.is_empty() {
+        return 0;
+    }
+    lengths.sort_unstable();
+    lengths[lengths.len() / 2]
+}
+
+/// If `text` ends in a word broken by a hyphen that `next` continues, returns
+/// the byte length of `text` without the hyphen.
+fn hyphenated_stem(text: &str, next: &str) -> Option<usize> {
+    let mut chars = text.chars().rev();
+    let hyphen = chars.next()?;
+    if !matches!(hyphen, '-' | '\u{AD}' | '\u{2010}') {
+        return None;
+    }
+    if !chars.next().is_some_and(char::is_alphabetic) {
+        return None;
+    }
+    let next_word = next.split_whitespace().next()?;
+    if !next_word.starts_with(char::is_lowercase) {
+        return None;
+    }
+    let bare = next_word.trim_end_matches(|c: char| !c.is_alphanumeric());
+    if SUSPENDED_HYPHEN_WORDS.contains(&bare) {
+        return None;
+    }
+    Some(text.len() - hyphen.len_utf8())
+}
+
+/// Joins words split as `infor- mation` within a line.
+fn join_split_hyphenation(paragraph: &str) -> String {
+    let mut out = String::with_capacity(paragraph.len());
+    for word in paragraph.split(' ').filter(|w| !w.is_empty()) {
+        match hyphenated_stem(&out, word) {
+            Some(stem) => out.truncate(stem),
+            None if !out.is_empty() => out.push(' '),
+            None => {}
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pages(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_strip_repeated_header_and_page_numbers() {
+        let input = pages(&[
+            "A Study of Things\nFirst page body text.\n1",
+            "A Study of Things\nSecond page body text.\n2",
+            "A Study of Things\nThird page body text.\n3",
+        ]);
+        let cleaned = strip_page_furniture(&input);
+        assert_eq!(
+            cleaned,
+            pages(&[
+                "First page body text.",
+                "Second page body text.",
+                "Third page body text."
+            ])
+        );
+    }
+
+    #[test]
+    fn test_strip_footer_with_advancing_page_number() {
+        let input = pages(&[
+            "Body one.\nJournal of Things, Vol. 3, p. 117",
+            "Body two.\nJournal of Things, Vol. 3, p. 118",
+            "Body three.\nJournal of Things, Vol. 3, p. 119",
+        ]);
+        let cleaned = strip_page_furniture(&input);
+        assert_eq!(cleaned, pages(&["Body one.", "Body two.", "Body three."]));
+    }
+
+    #[test]
+    fn test_keep_chapter_headings_that_differ_by_number() {
+        let input = pages(&[
+            "Chapter 1\nBody one.",
+            "Chapter 4\nBody two.",
+            "Chapter 9\nBody three.",
+        ]);
+        assert_eq!(strip_page_furniture(&input), input);
+    }
+
+    #[test]
+    fn test_body_lines_are_never_furniture() {
+        let input = pages(&[
+            "Head\nline a\nrepeated middle\nline b\nFoot",
+            "Head\nline c\nrepeated middle\nline d\nFoot",
+            "Head\nline e\nrepeated middle\nline f\nFoot",
+        ]);
+        let cleaned = strip_page_furniture(&input);
+        assert_eq!(cleaned[0], "line a\nrepeated middle\nline b");
+    }
+
+    #[test]
+    fn test_is_page_number() {
+        assert!(is_page_number("12"));
+        assert!(is_page_number("- 12 -"));
+        assert!(is_page_number("Page 7 of 40"));
+        assert!(is_page_number("7/40"));
+        assert!(is_page_number("xii"));
+        assert!(!is_page_number("12 angry men"));
+        assert!(!is_page_number("Introduction"));
+        assert!(!is_page_number(""));
+    }
+
+    #[test]
+    fn test_roman_page_numbers() {
+        assert_eq!(roman_page("xii"), Some(12));
+        assert_eq!(roman_page("xliv"), Some(44));
+        assert_eq!(roman_page("l"), Some(50));
+        assert_eq!(roman_page("ix"), Some(9));
+        for word in [
+            "ill", "civil", "vii i", "iiii", "ixi", "vx", "li", "mix", "c", "",
+        ] {
+            assert_eq!(roman_page(word), None, "{}", word);
+        }
+        assert!(!is_page_number("Ill"));
+        assert!(!is_page_number("civil"));
+        assert!(is_page_number("- iv -"));
+    }
+
+    #[test]
+    fn test_reflow_joins_hyphenated_line_breaks() {
+        let input = pages(&["The system stores infor-\nmation about every user."]);
+        assert_eq!(
+            reflow(&input),
+            "The system stores information about every user."
+        );
+    }
+
+    #[test]
+    fn test_reflow_joins_split_hyphenation_within_line() {
+        let input = pages(&["The system stores infor- mation here."]);
+        assert_eq!(reflow(&input), "The system stores information here.");
+    }
+
+    #[test]
+    fn test_reflow_keeps_real_hyphens() {
+        let input = pages(&["A well-known result about pre- and post-war Europe-\nWide trade."]);
+        assert_eq!(
+            reflow(&input),
+            "A well-known result about pre- and post-war Europe- Wide trade."
+        );
+    }
+
+    #[test]
+    fn test_reflow_merges_lines_into_paragraphs() {
+        let input = pages(&["This is the first line of a paragraph that\n\
+             continues on the next visual line and then\n\
+             ends here.\n\
+             \n\
+             A second paragraph starts after the blank\n\
+             line and keeps going for a little while."]);
+        assert_eq!(
+            reflow(&input),
+            "This is the first line of a paragraph that continues on the next visual line and then ends here.\n\
+             A second paragraph starts after the blank line and keeps going for a little while."
+        );
+    }
+
+    #[test]
+    fn test_reflow_short_sentence_end_closes_paragraph() {
+        let input = pages(&["A paragraph with several long lines of text\n\
+             that wrap around across the page width.\n\
+             Short end.\n\
+             Next paragraph begins with another long line\n\
+             of text that wraps across the page width."]);
+        let text = reflow(&input);
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.lines().next().unwrap().ends_with("Short end."));
+    }
+
+    #[test]
+    fn test_reflow_paragraph_continues_across_pages() {
+        let input = pages(&[
+            "A paragraph that runs to the bottom of the\npage and is then hyphen-\n",
+            "\nated onto the next page where it finishes.",
+        ]);
+        assert_eq!(
+            reflow(&input),
+            "A paragraph that runs to the bottom of the page and is then hyphenated onto the next page where it finishes."
+        );
+    }
+
+    #[test]
+    fn test_reflow_keeps_headings_separate() {
+        let input = pages(&["Introduction\n\
+             The study of things has a long history that\n\
+             we summarise in the following few sections."]);
+        let text = reflow(&input);
+        assert_eq!(text.lines().next(), Some("Introduction"));
+        assert_eq!(text.lines().count(), 2);
+    }
+}
@@ -0,0 +1,168 @@
+//! Expanding directories and glob patterns into reading queues
+//!
+//! `@papers/` queues the supported documents in a directory and
+//! `@notes/*.md` or `@**/chapter.pdf` queues every match of a pattern.
+//! Paths are sorted in natural order, so `ch2.md` comes before `ch10.md`.
+
+use super::{dispatch, LoadError};
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+/// Returns true if `spec` contains glob metacharacters.
+pub fn is_pattern(spec: &str) -> bool {
+    spec.contains(['*', '?', '['])
+}
+
+/// Expands a directory or glob pattern into the documents to read, in order.
+///
+/// Returns `Ok(None)` for a plain file path, which is loaded on its own; an
+/// existing file such as `Book [2nd ed].txt` is never read as a pattern.
+/// Only files with a supported extension are queued; hidden files are skipped.
+pub fn expand(spec: &str) -> Result<Option<Vec<PathBuf>>, LoadError> {
+    let path = Path::new(spec);
+    let mut items: Vec<PathBuf> = if path.is_file() {
+        return Ok(None);
+    } else if is_pattern(spec) && !path.exists() {
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            require_literal_leading_dot: true,
+            ..glob::MatchOptions::new()
+        };
+        glob::glob_with(spec, options)
+            .map_err(|e| LoadError::InvalidPattern(format!("{}: {}", spec, e.msg)))?
+            .filter_map(Result::ok)
+            .collect()
+    } else if path.is_dir() {
+        std::fs::read_dir(spec)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| !is_hidden(path))
+            .collect()
+    } else {
+        return Ok(None);
+    };
+
+    items.retain(|path| path.is_file() && dispatch::is_supported_path(path));
+    if items.is_empty() {
+        return Err(LoadError::InvalidPattern(format!(
+            "No supported documents match {}",
+            spec
+        )));
+    }
+    items.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+    Ok(Some(items))
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+/// Compares strings with runs of digits ordered by value.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x = take_number(&mut a);
+                let y = take_number(&mut b);
+                // Compare by length first so numbers of any size work
+                let order = x
+                    .trim_start_matches('0')
+                    .len()
+                    .cmp(&y.trim_start_matches('0').len())
+                    .then_with(|| x.trim_start_matches('0').cmp(y.trim_start_matches('0')));
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let order = x.to_lowercase().cmp(y.to_lowercase());
+                if order != Ordering::Equal {
+                    return order;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+fn take_number(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut number = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        number.push(c);
+    }
+    number
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_dir(name: &str, files: &[&str]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("speedy-queue-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in files {
+            std::fs::write(dir.join(file), "Some words.").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_natural_order() {
+        let mut names = vec!["ch10.md", "ch2.md", "Ch1.md", "appendix.md", "ch02b.md"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            names,
+            vec!["appendix.md", "Ch1.md", "ch2.md", "ch02b.md", "ch10.md"]
+        );
+    }
+
+    #[test]
+    fn test_directory_queues_supported_files() {
+        let dir = fixture_dir("dir", &["b.txt", "a.md", "image.png", ".hidden.txt"]);
+        let items = expand(dir.to_str().unwrap()).unwrap().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<_> = items.iter().map(|p| p.file_name().unwrap()).collect();
+        assert_eq!(names, vec!["a.md", "b.txt"]);
+    }
+
+    #[test]
+    fn test_glob_pattern() {
+        let dir = fixture_dir("glob", &["ch10.md", "ch9.md", "notes.txt"]);
+        let pattern = format!("{}/*.md", dir.display());
+        let items = expand(&pattern).unwrap().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<_> = items.iter().map(|p| p.file_name().unwrap()).collect();
+        assert_eq!(names, vec!["ch9.md", "ch10.md"]);
+    }
+
+    #[test]
+    fn test_plain_file_is_not_a_queue() {
+        assert_eq!(expand("tests/test_files/test.txt").unwrap(), None);
+        assert!(is_pattern("**/chapter.pdf"));
+        assert!(!is_pattern("papers/"));
+    }
+
+    #[test]
+    fn test_existing_path_with_brackets_is_literal() {
+        let dir = fixture_dir("literal", &["Book [2nd ed].txt"]);
+        let file = dir.join("Book [2nd ed].txt");
+        let result = expand(file.to_str().unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[test]
+    fn test_pattern_without_matches() {
+        let result = expand("/nonexistent/*.md");
+        assert!(matches!(result, Err(LoadError::InvalidPattern(_))));
+    }
+}
@@ -0,0 +1,188 @@
+//! Page and chapter ranges for partial loading
+//!
+//! A load command can restrict a document to part of its pages or chapters
+//! by appending a fragment to the path: `@paper.pdf#p12-30`, `@book.epub#ch3`.
+//! Ranges are 1-based and inclusive; the end may be left open (`#p100-`).
+
+use super::LoadError;
+use std::fmt;
+use std::ops::Range;
+
+/// What a [`LoadRange`] counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeUnit {
+    /// PDF pages (`p`)
+    Page,
+    /// EPUB chapters from the table of contents (`ch`)
+    Chapter,
+}
+
+/// A 1-based, inclusive range of pages or chapters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadRange {
+    pub unit: RangeUnit,
+    pub start: usize,
+    /// Last page or chapter to load, `None` to read to the end.
+    pub end: Option<usize>,
+}
+
+impl LoadRange {
+    /// Parses a range fragment such as `p12-30`, `p5`, `p100-`, `ch3` or `ch3-5`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim().to_lowercase();
+        let (unit, bounds) = if let Some(rest) = spec.strip_prefix("ch") {
+            (RangeUnit::Chapter, rest.to_string())
+        } else if let Some(rest) = spec.strip_prefix('p') {
+            (RangeUnit::Page, rest.to_string())
+        } else {
+            return None;
+        };
+        Self::parse_bounds(unit, &bounds)
+    }
+
+    /// Parses the bounds of a range without a unit prefix (`12-30`, `3`, `100-`).
+    ///
+    /// Used by the `--pages` and `--chapters` command-line flags.
+    pub fn parse_bounds(unit: RangeUnit, bounds: &str) -> Option<Self> {
+        let number = |s: &str| s.trim().parse::<usize>().ok().filter(|&n| n > 0);
+
+        let (start, end) = match bounds.split_once('-') {
+            Some((start, end)) if end.trim().is_empty() => (number(start)?, None),
+            Some((start, end)) => (number(start)?, Some(number(end)?)),
+            None => {
+                let n = number(bounds)?;
+                (n, Some(n))
+            }
+        };
+
+        if end.is_some_and(|end| end < start) {
+            return None;
+        }
+        Some(Self { unit, start, end })
+    }
+
+    /// Converts the range to 0-based indices into a document with `count`
+    /// pages or chapters, clamping the end to the document length.
+    pub fn indices(&self, count: usize) -> Result<Range<usize>, LoadError> {
+        if self.start > count {
+            return Err(LoadError::InvalidRange(format!(
+                "{} starts after the last {} ({})",
+                self,
+                self.unit_name(),
+                count
+            )));
+        }
+        let end = self.end.map_or(count, |end| end.min(count));
+        Ok(self.start - 1..end)
+    }
+
+    fn unit_name(&self) -> &'static str {
+        match self.unit {
+            RangeUnit::Page => "page",
+            RangeUnit::Chapter => "chapter",
+        }
+    }
+}
+
+impl fmt::Display for LoadRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = match self.unit {
+            RangeUnit::Page => "p",
+            RangeUnit::Chapter => "ch",
+        };
+        match self.end {
+            Some(end) if end == self.start => write!(f, "{}{}", prefix, self.start),
+            Some(end) => write!(f, "{}{}-{}", prefix, self.start, end),
+            None => write!(f, "{}{}-", prefix, self.start),
+        }
+    }
+}
+
+/// Splits a load spec into the file path and an optional range fragment.
+///
+/// The part after the last `#` is only treated as a range if it parses as
+/// one, so paths that merely contain `#` still load as written.
+pub fn split_path(spec: &str) -> (&str, Option<LoadRange>) {
+    if let Some((path, fragment)) = spec.rsplit_once('#') {
+        if let Some(range) = LoadRange::parse(fragment) {
+            return (path, Some(range));
+        }
+    }
+    (spec, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(unit: RangeUnit, start: usize, end: Option<usize>) -> LoadRange {
+        LoadRange { unit, start, end }
+    }
+
+    #[test]
+    fn test_parse_page_ranges() {
+        assert_eq!(
+            LoadRange::parse("p12-30"),
+            Some(range(RangeUnit::Page, 12, Some(30)))
+        );
+        assert_eq!(
+            LoadRange::parse("p5"),
+            Some(range(RangeUnit::Page, 5, Some(5)))
+        );
+        assert_eq!(
+            LoadRange::parse("P100-"),
+            Some(range(RangeUnit::Page, 100, None))
+        );
+    }
+
+    #[test]
+    fn test_parse_chapter_ranges() {
+        assert_eq!(
+            LoadRange::parse("ch3"),
+            Some(range(RangeUnit::Chapter, 3, Some(3)))
+        );
+        assert_eq!(
+            LoadRange::parse("ch3-5"),
+            Some(range(RangeUnit::Chapter, 3, Some(5)))
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_ranges() {
+        assert_eq!(LoadRange::parse("p0"), None);
+        assert_eq!(LoadRange::parse("p30-12"), None);
+        assert_eq!(LoadRange::parse("chapter"), None);
+        assert_eq!(LoadRange::parse("x3"), None);
+        assert_eq!(LoadRange::parse("p"), None);
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for spec in ["p12-30", "p5", "p100-", "ch3", "ch3-5"] {
+            assert_eq!(LoadRange::parse(spec).unwrap().to_string(), spec);
+        }
+    }
+
+    #[test]
+    fn test_indices_clamp_to_document() {
+        let r = range(RangeUnit::Page, 12, Some(30));
+        assert_eq!(r.indices(900).unwrap(), 11..30);
+        assert_eq!(r.indices(20).unwrap(), 11..20);
+        assert_eq!(range(RangeUnit::Page, 3, None).indices(10).unwrap(), 2..10);
+        assert!(matches!(r.indices(5), Err(LoadError::InvalidRange(_))));
+    }
+
+    #[test]
+    fn test_split_path() {
+        assert_eq!(
+            split_path("paper.pdf#p12-30"),
+            ("paper.pdf", Some(range(RangeUnit::Page, 12, Some(30))))
+        );
+        assert_eq!(
+            split_path("book.epub#ch3"),
+            ("book.epub", Some(range(RangeUnit::Chapter, 3, Some(3))))
+        );
+        assert_eq!(split_path("notes#1.pdf"), ("notes#1.pdf", None));
+        assert_eq!(split_path("book.epub"), ("book.epub", None));
+    }
+}
@@ -0,0 +1,520 @@
+//! reStructuredText loader
+//!
+//! Reads the prose of an `.rst` file: section titles become headings, and
+//! paragraphs, list items and block quotes are read with their inline markup
+//! reduced to text. Directives, comments, footnote and link targets,
+//! literal blocks, doctests and tables are skipped.
+//!
+//! Section levels follow the order in which title adornment styles first
+//! appear, as in docutils: the first style seen is level 1.
+
+use super::structure::DocumentBuilder;
+use super::{encoding, LoadError, LoadOptions, LoadedDocument, Metadata};
+use std::path::Path;
+
+/// Characters docutils accepts for section adornments.
+const ADORNMENT_CHARS: &str = "=-`:'\"~^_*+#<>.";
+
+/// Bullets that start a list item.
+const BULLETS: &[&str] = &["- ", "* ", "+ ", "• "];
+
+/// Load an RST file.
+///
+/// Purpose: Reads documentation and write-ups kept as reStructuredText.
+/// Big Picture: Enables `@analysis.rst` in the command deck.
+/// Connections: Decodes via input::encoding and builds the document via
+/// input::structure.
+pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let data = std::fs::read(path)?;
+    load_bytes(&data, path, &LoadOptions::default())
+}
+
+/// Load an RST file already read into memory; `path` is only used to name
+/// the source.
+pub fn load_bytes(
+    data: &[u8],
+    path: &Path,
+    options: &LoadOptions,
+) -> Result<LoadedDocument, LoadError> {
+    let source = format!("rst:{}", path.display());
+    let decoded = encoding::decode(data, options.encoding)
+        .ok_or_else(|| LoadError::InvalidEncoding(source.clone()))?;
+
+    let mut reader = RstReader::default();
+    reader.read(&decoded.text);
+    if reader.builder.is_empty() {
+        return Err(LoadError::RstParse(
+            "No text content found in document".to_string(),
+        ));
+    }
+
+    let mut doc = reader.builder.build(source, &[])?;
+    doc.metadata = Metadata {
+        title: reader.title,
+        author: reader.author,
+        encoding: Some(decoded.encoding.name().to_string()),
+        ..Metadata::default()
+    };
+    Ok(doc)
+}
+
+#[derive(Default)]
+struct RstReader {
+    builder: DocumentBuilder,
+    /// Adornment styles (character, overlined) in order of first use.
+    styles: Vec<(char, bool)>,
+    paragraph: Vec<String>,
+    title: Option<String>,
+    author: Option<String>,
+}
+
+impl RstReader {
+    fn read(&mut self, text: &str) {
+        let lines: Vec<&str> = text.lines().collect();
+        let mut i = 0;
+
+        while i < lines.len() {
+            let line = lines[i];
+            let trimmed = line.trim();
+            let indent = indentation(line);
+
+            if trimmed.is_empty() {
+                self.flush();
+                i += 1;
+                continue;
+            }
+
+            if self.paragraph.is_empty() && indent == 0 {
+                if let Some(consumed) = self.section_title(&lines[i..]) {
+                    i += consumed;
+                    continue;
+                }
+                if is_adornment(trimmed) && trimmed.chars().count() >= 4 {
+                    // A transition (horizontal rule)
+                    i += 1;
+                    continue;
+                }
+            }
+
+            if trimmed.starts_with("..") && (trimmed == ".." || trimmed.starts_with(".. ")) {
+                // Directive, comment, target or footnote: skip its body too
+                self.flush();
+                i = skip_indented(&lines, i + 1, indent);
+                continue;
+            }
+
+            if self.paragraph.is_empty() && (trimmed.starts_with(">>>") || is_table_line(trimmed)) {
+                i = skip_block(&lines, i);
+                continue;
+            }
+
+            if self.paragraph.is_empty() && self.docinfo_field(trimmed) {
+                i += 1;
+                continue;
+            }
+
+            if let Some(item) = list_item(trimmed) {
+                self.flush();
+                self.paragraph.push(item.to_string());
+            } else if let Some(verse) = trimmed.strip_prefix("| ") {
+                // Line block: each line stands alone
+                self.flush();
+                self.paragraph.push(verse.to_string());
+                self.flush();
+            } else {
+                self.paragraph.push(trimmed.to_string());
+            }
+            i += 1;
+
+            // A paragraph ending in `::` introduces a literal block
+            if self.paragraph.last().is_some_and(|l| l.ends_with("::")) {
+                let last = self.paragraph.pop().unwrap_or_default();
+                let kept = last.trim_end_matches("::").trim_end();
+                if !kept.is_empty() {
+                    let colon = if last.ends_with(" ::") { "" } else { ":" };
+                    self.paragraph.push(format!("{}{}", kept, colon));
+                }
+                self.flush();
+                let next = lines[i..]
+                    .iter()
+                    .position(|l| !l.trim().is_empty())
+                    .map_or(lines.len(), |offset| i + offset);
+                if next < lines.len() && indentation(lines[next]) > indent {
+                    i = skip_indented(&lines, next, indent);
+                }
+            }
+        }
+        self.flush();
+    }
+
+    /// Recognises a title with an underline, or with an overline and
+    /// underline, at the start of `lines`; returns the lines it spans.
+    fn section_title(&mut self, lines: &[&str]) -> Option<usize> {
+        let first = lines[0].trim_end();
+        let (title, style, consumed) = if is_adornment(first) {
+            let title = lines.get(1)?.trim();
+            let under = lines.get(2)?.trim_end();
+            if title.is_empty() || under != first {
+                return None;
+            }
+            (title, (first.chars().next()?, true), 3)
+        } else {
+            let under = lines.get(1)?.trim_end();
+            // Short underlines are only accepted when they cover the title,
+            // so `--` under a word is not mistaken for one
+            if !is_adornment(under) || (under.chars().count() < 3 && under.len() < first.len()) {
+                return None;
+            }
+            (first.trim(), (under.chars().next()?, false), 2)
+        };
+
+        let level = match self.styles.iter().position(|&s| s == style) {
+            Some(index) => index + 1,
+            None => {
+                self.styles.push(style);
+                self.styles.len()
+            }
+        };
+        let title = inline_text(title);
+        if self.title.is_none() && level == 1 && self.builder.is_empty() {
+            self.title = Some(title.clone());
+        }
+        self.builder
+            .heading(level.min(u8::MAX as usize) as u8, &title);
+        Some(consumed)
+    }
+
+    /// Reads bibliographic fields (`:Author: Jane Doe`), which are metadata
+    /// rather than prose. Returns true if the line was a field.
+    fn docinfo_field(&mut self, line: &str) -> bool {
+        let Some(rest) = line.strip_prefix(':') else {
+            return false;
+        };
+        let Some((name, value)) = rest
+            .split_once(": ")
+            .or_else(|| rest.strip_suffix(':').map(|name| (name, "")))
+        else {
+            return false;
+        };
+        if name.is_empty() || name.contains('`') {
+            return false;
+        }
+        let value = value.trim();
+        match name.to_lowercase().as_str() {
+            "author" | "authors" if !value.is_empty() => {
+                self.author = Some(inline_text(value));
+            }
+            "title" if !value.is_empty() => self.title = Some(inline_text(value)),
+            _ => {}
+        }
+        true
+    }
+
+    fn flush(&mut self) {
+        if !self.paragraph.is_empty() {
+            self.builder
+                .paragraph(&inline_text(&self.paragraph.join(" ")));
+            self.paragraph.clear();
+        }
+    }
+}
+
+/// A line made of one repeated adornment character, e.g. `=====`.
+fn is_adornment(line: &str) -> bool {
+    let mut chars = line.chars();
+    match chars.next() {
+        Some(first) if ADORNMENT_CHARS.contains(first) => {
+            line.chars().count() >= 2 && chars.all(|c| c == first)
+        }
+        _ => false,
+    }
+}
+
+/// Grid table borders (`+---+`) and rows, and simple table borders
+/// (`=====  =====`).
+fn is_table_line(line: &str) -> bool {
+    (line.starts_with("+-") || line.starts_with("+="))
+        || (line.starts_with('=')
+            && line.contains(' ')
+            && line.chars().all(|c| c == '=' || c == ' '))
+}
+
+/// Returns the text of a bullet or enumerated list item.
+fn list_item(line: &str) -> Option<&str> {
+    if let Some(rest) = BULLETS.iter().find_map(|b| line.strip_prefix(b)) {
+        return Some(rest.trim_start());
+    }
+    // `1.`, `#.`, `a)`, `(iv)`
+    let (marker, rest) = line.split_once(' ')?;
+    let label = marker
+        .strip_prefix('(')
+        .and_then(|m| m.strip_suffix(')'))
+        .or_else(|| marker.strip_suffix('.'))
+        .or_else(|| marker.strip_suffix(')'))?;
+    let enumerator = label == "#"
+        || (!label.is_empty() && label.chars().all(|c| c.is_ascii_digit()))
+        || (label.len() == 1 && label.chars().all(|c| c.is_ascii_alphabetic()))
+        || (!label.is_empty() && label.chars().all(|c| "ivxlcdm".contains(c)));
+    enumerator.then(|| rest.trim_start())
+}
+
+/// Skips lines indented deeper than `indent`, and blank lines among them,
+/// from `start`; returns the index of the next line to read.
+fn skip_indented(lines: &[&str], start: usize, indent: usize) -> usize {
+    let mut i = start;
+    while i < lines.len() {
+        let line = lines[i];
+        if !line.trim().is_empty() && indentation(line) <= indent {
+            break;
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Skips lines up to the next blank line; tables may also contain blank
+/// rows between their borders, so a table ends at a blank line after a
+/// border.
+fn skip_block(lines: &[&str], start: usize) -> usize {
+    let table = is_table_line(lines[start].trim());
+    let mut i = start + 1;
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+        if trimmed.is_empty() && (!table || is_table_line(lines[i - 1].trim())) {
+            break;
+        }
+        i += 1;
+    }
+    i
+}
+
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 8 } else { 1 })
+        .sum()
+}
+
+/// Reduces inline markup to its text.
+///
+/// Emphasis, literals and interpreted text lose their markers; hyperlink
+/// references keep their text (`` `docs <https://...>`_ `` reads "docs");
+/// roles are dropped (`` :ref:`intro` `` reads "intro"); footnote and
+/// citation references such as `[1]_` are removed.
+fn inline_text(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let at_start = i == 0 || !chars[i - 1].is_alphanumeric();
+        match c {
+            '\\' => {
+                // Escaped character, or an escaped space that joins words
+                if let Some(&next) = chars.get(i + 1) {
+                    if next != ' ' {
+                        out.push(next);
+                    }
+                }
+                i += 2;
+            }
+            '*' | '`' | '|' if at_start => {
+                let marker_len = chars[i..].iter().take_while(|&&m| m == c).count().min(2);
+                let body_start = i + marker_len;
+                match find_closing(&chars, body_start, c, marker_len) {
+                    Some(end) => {
+                        let body: String = chars[body_start..end].iter().collect();
+                        out.push_str(&reference_text(&body));
+                        i = end + marker_len;
+                        // Trailing `_` or `__` of a reference
+                        while chars.get(i) == Some(&'_') {
+                            i += 1;
+                        }
+                    }
+                    None => {
+                        out.push(c);
+                        i += 1;
+                    }
+                }
+            }
+            ':' if at_start => match role_end(&chars, i) {
+                // `:role:` directly before interpreted text
+                Some(end) if chars.get(end) == Some(&'`') => i = end,
+                _ => {
+                    out.push(c);
+                    i += 1;
+                }
+            },
+            '[' => match footnote_ref_end(&chars, i) {
+                Some(end) => {
+                    // Drop the space before a removed reference
+                    if out.ends_with(' ') {
+                        out.pop();
+                    }
+                    i = end;
+                }
+                None => {
+                    out.push(c);
+                    i += 1;
+                }
+            },
+            '_' if i > 0
+                && chars[i - 1].is_alphanumeric()
+                && chars.get(i + 1).is_none_or(|n| !n.is_alphanumeric()) =>
+            {
+                // `word_` reference
+                i += 1;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Finds the closing marker of an inline span opened at `start`.
+fn find_closing(chars: &[char], start: usize, marker: char, len: usize) -> Option<usize> {
+    if chars.get(start).is_none_or(|c| c.is_whitespace()) {
+        return None;
+    }
+    (start + 1..chars.len()).find(|&i| {
+        chars[i..].iter().take(len).all(|&c| c == marker)
+            && i + len <= chars.len()
+            && !chars[i - 1].is_whitespace()
+            && chars
+                .get(i + len)
+                .is_none_or(|&next| next != marker && !next.is_alphanumeric())
+    })
+}
+
+/// Text of a hyperlink reference body: `docs <https://...>` reads "docs".
+fn reference_text(body: &str) -> String {
+    match body.rfind(" <") {
+        Some(index) if body.ends_with('>') => body[..index].to_string(),
+        _ if body.starts_with('<') && body.ends_with('>') => body[1..body.len() - 1].to_string(),
+        _ => body.to_string(),
+    }
+}
+
+/// End of a `:role:` prefix starting at `start`, if there is one.
+fn role_end(chars: &[char], start: usize) -> Option<usize> {
+    let name_len = chars[start + 1..]
+        .iter()
+        .take_while(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+        .count();
+    // The name may contain colons (`:py:func:`), so back up to the last one
+    let name: String = chars[start + 1..start + 1 + name_len].iter().collect();
+    let last_colon = name.rfind(':')?;
+    (last_colon > 0).then_some(start + 1 + last_colon + 1)
+}
+
+/// End of a footnote or citation reference such as `[1]_` or `[#note]_`.
+fn footnote_ref_end(chars: &[char], start: usize) -> Option<usize> {
+    let close = start + chars[start..].iter().position(|&c| c == ']')?;
+    let label: String = chars[start + 1..close].iter().collect();
+    let valid = !label.is_empty()
+        && !label.contains(char::is_whitespace)
+        && chars.get(close + 1) == Some(&'_');
+    valid.then_some(close + 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_fixture() -> LoadedDocument {
+        load("tests/test_files/analysis.rst").unwrap()
+    }
+
+    fn words(doc: &LoadedDocument) -> String {
+        doc.tokens
+            .iter()
+            .map(|t| t.text)
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_section_levels_follow_adornment_order() {
+        let doc = load_fixture();
+        let outline: Vec<_> = doc
+            .sections
+            .iter()
+            .map(|s| (s.title.as_str(), s.level))
+            .collect();
+        assert_eq!(
+            outline,
+            vec![
+                ("Churn Analysis", 1),
+                ("Method", 2),
+                ("Cohorts", 3),
+                ("Findings", 2),
+            ]
+        );
+        assert_eq!(doc.metadata.title.as_deref(), Some("Churn Analysis"));
+        assert_eq!(doc.metadata.author.as_deref(), Some("Dana Reyes"));
+        assert_eq!(
+            doc.tokens.get(doc.sections[1].token_index).unwrap().text,
+            "Method"
+        );
+    }
+
+    #[test]
+    fn test_directives_and_literal_blocks_skipped() {
+        let text = words(&load_fixture());
+        assert!(text.contains("We grouped customers by signup month"));
+        assert!(!text.contains("SELECT"));
+        assert!(!text.contains("figure"));
+        assert!(!text.contains("churn.png"));
+        assert!(!text.contains("reviewer"));
+        assert!(!text.contains("import"));
+        assert!(!text.contains("Segment"));
+        assert!(text.contains("Retention improved after onboarding changes"));
+    }
+
+    #[test]
+    fn test_inline_markup() {
+        assert_eq!(
+            inline_text("See the **full** `report <https://example.com>`_ and :ref:`cohorts`."),
+            "See the full report and cohorts."
+        );
+        assert_eq!(
+            inline_text("Rates fell sharply [1]_ in *Q3* (see Churn_) with ``n=40``."),
+            "Rates fell sharply in Q3 (see Churn) with n=40."
+        );
+        assert_eq!(inline_text("snake_case and 2 * 3"), "snake_case and 2 * 3");
+        assert_eq!(
+            inline_text("|project| uses :py:func:`load`"),
+            "project uses load"
+        );
+    }
+
+    #[test]
+    fn test_list_items() {
+        assert_eq!(list_item("- first"), Some("first"));
+        assert_eq!(list_item("#. auto"), Some("auto"));
+        assert_eq!(list_item("(iv) roman"), Some("roman"));
+        assert_eq!(list_item("12. twelve"), Some("twelve"));
+        assert_eq!(list_item("Dr. Smith"), None);
+    }
+
+    #[test]
+    fn test_empty_document_fails() {
+        let result = load_bytes(
+            b".. comment only\n",
+            Path::new("x.rst"),
+            &LoadOptions::default(),
+        );
+        assert!(matches!(result, Err(LoadError::RstParse(_))));
+    }
+}
@@ -0,0 +1,206 @@
+//! Document structure for structured loaders
+//!
+//! Formats with real markup (HTML, word-processor files, ebooks) know where
+//! headings and paragraphs are. [`DocumentBuilder`] collects that structure as
+//! one line of text per block and records each heading as a [`Section`]
+//! pointing at the token where it starts, so the reading stream and the
+//! outline stay in sync after tokenization.
+
+use super::notes::{strip_note_markers, NoteAttacher};
+use super::{LoadError, LoadedDocument, Metadata};
+use crate::reading::store::{TokenStore, TokenStoreBuilder};
+use crate::reading::timing::tokenize_into;
+
+/// A heading in a loaded document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub title: String,
+    /// Heading level, 1 for the top level (`<h1>`, a book chapter).
+    pub level: u8,
+    /// Index of the first token of the heading in `LoadedDocument::tokens`.
+    pub token_index: usize,
+}
+
+/// Accumulates headings and paragraphs into a [`LoadedDocument`].
+#[derive(Debug, Default)]
+pub struct DocumentBuilder {
+    text: String,
+    lines: usize,
+    headings: Vec<(usize, String, u8)>,
+}
+
+impl DocumentBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a heading as its own line and records it as a section.
+    pub fn heading(&mut self, level: u8, title: &str) {
+        let title = collapse_whitespace(title);
+        if title.is_empty() {
+            return;
+        }
+        // A heading right after a section break names that section
+        if self
+            .headings
+            .last()
+            .is_some_and(|(line, ..)| *line == self.lines)
+        {
+            self.headings.pop();
+        }
+        self.headings
+            .push((self.lines, strip_note_markers(&title), level));
+        self.push_line(&title);
+    }
+
+    /// Records a top-level section starting at the next block, for formats
+    /// that mark breaks without headings. If that block is a heading, the
+    /// section takes its title and level instead of `title`.
+    pub fn section(&mut self, title: &str) {
+        self.headings.push((self.lines, title.to_string(), 1));
+    }
+
+    /// Adds a paragraph as one line; inner line breaks are collapsed.
+    pub fn paragraph(&mut self, text: &str) {
+        let text = collapse_whitespace(text);
+        if !text.is_empty() {
+            self.push_line(&text);
+        }
+    }
+
+    /// Returns true if no text has been added.
+    pub fn is_empty(&self) -> bool {
+        self.lines == 0
+    }
+
+    /// Tokenizes the collected text, attaches notes referenced through
+    /// `notes::note_ref_marker`, and resolves heading positions.
+    pub fn build(self, source: String, notes: &[String]) -> Result<LoadedDocument, LoadError> {
+        self.build_with_metadata(source, notes, Metadata::default())
+    }
+
+    /// Like [`DocumentBuilder::build`], splitting sentences with the
+    /// abbreviations of the metadata's language.
+    pub fn build_with_metadata(
+        self,
+        source: String,
+        notes: &[String],
+        metadata: Metadata,
+    ) -> Result<LoadedDocument, LoadError> {
+        let tokens = tokenize_document(&self.text, metadata.language.as_deref(), notes)?;
+        let sections = locate_sections(&tokens, self.headings);
+        Ok(LoadedDocument {
+            tokens,
+            source,
+            sections,
+            metadata,
+            cues: Vec::new(),
+            notices: Vec::new(),
+        })
+    }
+
+    fn push_line(&mut self, line: &str) {
+        if self.lines > 0 {
+            self.text.push('\n');
+        }
+        self.text.push_str(line);
+        self.lines += 1;
+    }
+}
+
+/// Tokenizes a document's text straight into a [`TokenStore`], splitting
+/// sentences for `language` and attaching notes referenced through
+/// `notes::note_ref_marker`.
+pub fn tokenize_document(
+    text: &str,
+    language: Option<&str>,
+    notes: &[String],
+) -> Result<TokenStore, LoadError> {
+    let mut tokens = NoteAttacher::new(TokenStoreBuilder::new(), notes);
+    tokenize_into(text, language, &mut tokens);
+    Ok(tokens.into_inner().finish()?)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Maps headings given by line number to the token where that line starts.
+///
+/// `tokenize_text` emits one newline token per line break, so line `n`
+/// starts right after the `n`-th newline token.
+fn locate_sections(tokens: &TokenStore, headings: Vec<(usize, String, u8)>) -> Vec<Section> {
+    let mut line_starts = vec![0];
+    for (index, token) in tokens.iter().enumerate() {
+        if token.text.is_empty() && token.punctuation.is_newline() {
+            line_starts.push(index + 1);
+        }
+    }
+
+    headings
+        .into_iter()
+        .filter_map(|(line, title, level)| {
+            line_starts.get(line).map(|&token_index| Section {
+                title,
+                level,
+                token_index,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::notes::note_ref_marker;
+
+    #[test]
+    fn test_sections_point_at_heading_tokens() {
+        let mut builder = DocumentBuilder::new();
+        builder.heading(1, "Title");
+        builder.paragraph("First paragraph here.");
+        builder.heading(2, "Part  Two");
+        builder.paragraph("More\ntext.");
+        let doc = builder.build("test".to_string(), &[]).unwrap();
+
+        assert_eq!(doc.sections.len(), 2);
+        assert_eq!(doc.sections[1].title, "Part Two");
+        assert_eq!(doc.sections[1].level, 2);
+        assert_eq!(
+            doc.tokens.get(doc.sections[0].token_index).unwrap().text,
+            "Title"
+        );
+        assert_eq!(
+            doc.tokens.get(doc.sections[1].token_index).unwrap().text,
+            "Part"
+        );
+    }
+
+    #[test]
+    fn test_empty_blocks_are_skipped() {
+        let mut builder = DocumentBuilder::new();
+        builder.paragraph("  ");
+        builder.heading(1, "");
+        assert!(builder.is_empty());
+        builder.paragraph("Text");
+        let doc = builder.build("test".to_string(), &[]).unwrap();
+        assert_eq!(doc.tokens.len(), 1);
+        assert!(doc.sections.is_empty());
+    }
+
+    #[test]
+    fn test_sections_survive_note_markers() {
+        let mut builder = DocumentBuilder::new();
+        builder.paragraph(&format!("Claim{} made.", note_ref_marker(0)));
+        builder.heading(1, "Next");
+        let doc = builder
+            .build("test".to_string(), &["Note.".to_string()])
+            .unwrap();
+
+        assert_eq!(
+            doc.tokens.get(doc.sections[0].token_index).unwrap().text,
+            "Next"
+        );
+        assert!(doc.tokens.get(0).unwrap().note.is_some());
+    }
+}
@@ -0,0 +1,339 @@
+//! Subtitle loader (SRT and WebVTT)
+//!
+//! Cue numbers, timestamps, settings and styling tags are dropped; cue text
+//! flows together into one paragraph per scene, where a scene ends at a
+//! silence of at least [`SCENE_BREAK_MS`]. Each cue keeps its time window and
+//! the tokens it produced, so the reader can optionally replay words at the
+//! pace of the original dialogue instead of at a fixed WPM.
+
+use super::encoding;
+use super::markup::decode_entities;
+use super::structure::tokenize_document;
+use super::{LoadError, LoadOptions, LoadedDocument, Metadata};
+use crate::engine::tokenize_text;
+use std::ops::Range;
+use std::path::Path;
+
+/// A gap between cues at least this long starts a new scene (paragraph).
+pub const SCENE_BREAK_MS: u64 = 4000;
+
+/// Longest pause held after a cue's last word when following original timing.
+const MAX_HOLD_MS: u64 = 3000;
+
+/// Shortest time a word is shown when following original timing.
+const MIN_WORD_MS: u64 = 60;
+
+/// A subtitle cue's time window and the tokens it produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cue {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Indices into `LoadedDocument::tokens`.
+    pub tokens: Range<usize>,
+}
+
+/// Load an `.srt` or `.vtt` file.
+///
+/// Purpose: Reads lecture transcripts and dialogue from subtitle files.
+/// Big Picture: Enables `@talk.vtt`; cue timings drive the optional
+/// follow-original-timing mode (`--follow-timing`, `t` while reading).
+/// Connections: Tokenizes via engine::tokenize_text; App paces with
+/// [`cue_delay_ms`].
+pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let bytes = std::fs::read(path)?;
+    load_bytes(&bytes, path, &LoadOptions::default())
+}
+
+/// Load subtitles already read into memory; `path` is only used to name the source.
+///
+/// Subtitle files are often in a legacy encoding such as Windows-1252, so the
+/// encoding is detected (or taken from `options.encoding`) as for plain text.
+pub fn load_bytes(
+    data: &[u8],
+    path: &Path,
+    options: &LoadOptions,
+) -> Result<LoadedDocument, LoadError> {
+    let source = format!("subtitle:{}", path.display());
+    let decoded = encoding::decode(data, options.encoding)
+        .ok_or_else(|| LoadError::InvalidEncoding(source.clone()))?;
+
+    let mut doc = parse_subtitles(&decoded.text, source)?;
+    doc.metadata.encoding = Some(decoded.encoding.name().to_string());
+    Ok(doc)
+}
+
+fn parse_subtitles(input: &str, source: String) -> Result<LoadedDocument, LoadError> {
+    let cues = parse_cues(input);
+    if cues.is_empty() {
+        return Err(LoadError::SubtitleParse(
+            "No subtitle cues found".to_string(),
+        ));
+    }
+
+    // One line per scene; count each cue's tokens to map cues onto them
+    let mut text = String::new();
+    let mut word_counts = Vec::with_capacity(cues.len());
+    let mut last_end = None;
+    for (start_ms, end_ms, cue_text) in &cues {
+        if let Some(last_end) = last_end {
+            let gap = start_ms.saturating_sub(last_end);
+            text.push(if gap >= SCENE_BREAK_MS { '\n' } else { ' ' });
+        }
+        text.push_str(cue_text);
+        // The tokenizer splits "wait—what" and folds a lone dash into the
+        // word before it, so words are counted the way it counts them
+        word_counts.push(
+            tokenize_text(cue_text)
+                .iter()
+                .filter(|t| !t.is_newline())
+                .count(),
+        );
+        last_end = Some(*end_ms);
+    }
+
+    let tokens = tokenize_document(&text, None, &[])?;
+    let mut word_positions = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| !t.text.is_empty() || !t.punctuation.is_newline())
+        .map(|(index, _)| index);
+
+    let mut timed = Vec::with_capacity(cues.len());
+    for ((start_ms, end_ms, _), count) in cues.iter().zip(word_counts) {
+        let positions: Vec<usize> = word_positions.by_ref().take(count).collect();
+        if let (Some(&first), Some(&last)) = (positions.first(), positions.last()) {
+            timed.push(Cue {
+                start_ms: *start_ms,
+                end_ms: *end_ms,
+                tokens: first..last + 1,
+            });
+        }
+    }
+    drop(word_positions);
+
+    Ok(LoadedDocument {
+        tokens,
+        source,
+        sections: Vec::new(),
+        metadata: Metadata::default(),
+        cues: timed,
+        notices: Vec::new(),
+    })
+}
+
+/// Parses SRT or WebVTT into `(start_ms, end_ms, text)` cues in file order.
+///
+/// Both formats are blocks separated by blank lines with a `-->` timing line;
+/// WebVTT `NOTE`, `STYLE` and `REGION` blocks have none and are skipped.
+/// Rolling captions that repeat the previous cue's text are merged into it.
+fn parse_cues(input: &str) -> Vec<(u64, u64, String)> {
+    let input = input.trim_start_matches('\u{FEFF}').replace("\r\n", "\n");
+    let mut cues: Vec<(u64, u64, String)> = Vec::new();
+
+    for block in input.split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+        let Some(timing) = lines.next() else {
+            continue;
+        };
+        let Some((start, end)) = parse_timing_line(timing) else {
+            continue;
+        };
+
+        let text: Vec<String> = lines
+            .map(clean_cue_line)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let text = text.join(" ");
+        if text.is_empty() {
+            continue;
+        }
+
+        match cues.last_mut() {
+            Some(previous) if previous.2 == text => previous.1 = previous.1.max(end),
+            _ => cues.push((start, end, text)),
+        }
+    }
+    cues
+}
+
+/// Parses `00:01:02,500 --> 00:01:04,000` (SRT) or `01:02.500 --> ...` (VTT),
+/// ignoring trailing VTT cue settings.
+fn parse_timing_line(line: &str) -> Option<(u64, u64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    let start = parse_timestamp(start.trim())?;
+    let end = parse_timestamp(end)?;
+    Some((start, end.max(start)))
+}
+
+fn parse_timestamp(value: &str) -> Option<u64> {
+    let value = value.replace(',', ".");
+    let (clock, millis) = value.split_once('.').unwrap_or((&value, "0"));
+    let mut seconds = 0u64;
+    for part in clock.split(':') {
+        seconds = seconds * 60 + part.trim().parse::<u64>().ok()?;
+    }
+    let millis = format!("{:0<3}", millis.get(..3.min(millis.len()))?)
+        .parse::<u64>()
+        .ok()?;
+    Some(seconds * 1000 + millis)
+}
+
+/// Removes styling from a cue line: HTML-like tags (`<i>`, `<c.yellow>`,
+/// `<v Speaker>`, inline `<00:01.000>` timestamps), ASS overrides such as
+/// `{\an8}`, and the leading dash of dialogue lines.
+fn clean_cue_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '<' => {
+                for c in chars.by_ref() {
+                    if c == '>' {
+                        break;
+                    }
+                }
+            }
+            '{' if chars.peek() == Some(&'\\') => {
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+
+    let text = decode_entities(&out);
+    let text = text.trim();
+    let text = text
+        .strip_prefix("- ")
+        .or_else(|| text.strip_prefix("-\u{a0}"))
+        .unwrap_or(text);
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Display time for the token at `index` when following original timing.
+///
+/// Words share their cue's window evenly; the last word of a cue also holds
+/// through the silence before the next cue, up to [`MAX_HOLD_MS`]. Returns
+/// `None` for tokens outside any cue, which are paced by WPM.
+pub fn cue_delay_ms(cues: &[Cue], index: usize) -> Option<u64> {
+    let position = cues.partition_point(|cue| cue.tokens.end <= index);
+    let cue = cues
+        .get(position)
+        .filter(|cue| cue.tokens.contains(&index))?;
+
+    let words = cue.tokens.len() as u64;
+    let mut delay = ((cue.end_ms - cue.start_ms) / words).max(MIN_WORD_MS);
+    if index + 1 == cue.tokens.end {
+        if let Some(next) = cues.get(position + 1) {
+            delay += next.start_ms.saturating_sub(cue.end_ms).min(MAX_HOLD_MS);
+        }
+    }
+    Some(delay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRT: &str = "1\r\n00:00:01,000 --> 00:00:03,000\r\n<i>Hello there,</i>\r\n{\\an8}my friend.\r\n\r\n\
+        2\r\n00:00:03,500 --> 00:00:04,500\r\n- How are you?\r\n- <font color=\"red\">Fine.</font>\r\n\r\n\
+        3\r\n00:00:20,000 --> 00:00:22,000\r\nLater that day.\r\n";
+
+    const VTT: &str = "WEBVTT Kind: captions\n\n\
+        NOTE This is a comment\nspanning lines\n\n\
+        STYLE\n::cue { color: yellow }\n\n\
+        intro\n00:01.000 --> 00:02.000 align:start position:10%\n<v Ana>Welcome &amp; hello\n\n\
+        00:02.000 --> 00:03.000\n<v Ana>Welcome &amp; hello\n\n\
+        00:00:03.000 --> 00:00:05.000\nto the <c.yellow>lecture</c><00:00:04.000> today\n";
+
+    fn words(doc: &LoadedDocument) -> Vec<&str> {
+        doc.tokens
+            .iter()
+            .filter(|t| !t.text.is_empty())
+            .map(|t| t.text)
+            .collect()
+    }
+
+    #[test]
+    fn test_srt_strips_numbers_timestamps_and_tags() {
+        let doc = parse_subtitles(SRT, "subtitle:test".to_string()).unwrap();
+        assert_eq!(
+            words(&doc),
+            vec![
+                "Hello", "there", "my", "friend", "How", "are", "you", "Fine", "Later", "that",
+                "day"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scene_breaks_on_long_gaps() {
+        let doc = parse_subtitles(SRT, "subtitle:test".to_string()).unwrap();
+        let newlines: Vec<usize> = doc
+            .tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.text.is_empty())
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(newlines.len(), 1);
+        assert_eq!(doc.tokens.get(newlines[0] + 1).unwrap().text, "Later");
+    }
+
+    #[test]
+    fn test_vtt_skips_blocks_and_merges_rolling_captions() {
+        let doc = parse_subtitles(VTT, "subtitle:test".to_string()).unwrap();
+        assert_eq!(
+            words(&doc),
+            vec!["Welcome", "&", "hello", "to", "the", "lecture", "today"]
+        );
+        assert_eq!(doc.cues.len(), 2);
+        assert_eq!((doc.cues[0].start_ms, doc.cues[0].end_ms), (1000, 3000));
+        assert_eq!(doc.cues[1].tokens, 3..7);
+    }
+
+    #[test]
+    fn test_cue_delay_follows_original_timing() {
+        let doc = parse_subtitles(SRT, "subtitle:test".to_string()).unwrap();
+        // Cue 1: four words over 2000 ms, then a 500 ms pause before cue 2
+        assert_eq!(cue_delay_ms(&doc.cues, 0), Some(500));
+        assert_eq!(cue_delay_ms(&doc.cues, 3), Some(1000));
+        // Cue 2: four words over 1000 ms; the long silence is capped
+        assert_eq!(cue_delay_ms(&doc.cues, 7), Some(250 + MAX_HOLD_MS));
+        // The scene-break newline is outside every cue
+        assert_eq!(cue_delay_ms(&doc.cues, 8), None);
+    }
+
+    #[test]
+    fn test_cues_follow_tokenizer_splits() {
+        let srt = "1\n00:00:01,000 --> 00:00:02,000\nwait—what now\n\n\
+                   2\n00:00:02,000 --> 00:00:03,000\nsecond cue\n";
+        let doc = parse_subtitles(srt, "subtitle:test".to_string()).unwrap();
+        assert_eq!(words(&doc), vec!["wait", "what", "now", "second", "cue"]);
+        assert_eq!(doc.cues[0].tokens, 0..3);
+        assert_eq!(doc.cues[1].tokens, 3..5);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("01:02:03,450"), Some(3_723_450));
+        assert_eq!(parse_timestamp("02:03.5"), Some(123_500));
+        assert_eq!(parse_timestamp("bad"), None);
+    }
+
+    #[test]
+    fn test_no_cues() {
+        let result = parse_subtitles("WEBVTT\n\nNOTE nothing here\n", "subtitle:x".to_string());
+        assert!(matches!(result, Err(LoadError::SubtitleParse(_))));
+    }
+}
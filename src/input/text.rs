@@ -0,0 +1,172 @@
+use super::structure::tokenize_document;
+use super::{encoding, gutenberg};
+use super::{LoadError, LoadOptions, LoadedDocument, Metadata};
+use std::io::Read;
+use std::path::Path;
+
+/// Load a plain text file.
+///
+/// Purpose: Reads `.txt`/`.md` files and extensionless notes without conversion.
+/// Big Picture: Enables `@notes.txt` in the command deck and `speedy notes.txt` on the CLI.
+/// Connections: Depends on engine::tokenize_text() for tokenization and
+/// input::encoding for decoding.
+pub fn load(path: &str) -> Result<LoadedDocument, LoadError> {
+    load_with_options(path, &LoadOptions::default())
+}
+
+/// Load a plain text file, decoding it as `options.encoding` if one is given.
+pub fn load_with_options(path: &str, options: &LoadOptions) -> Result<LoadedDocument, LoadError> {
+    let path = Path::new(path);
+
+    if !path.exists() {
+        return Err(LoadError::FileNotFound(path.to_path_buf()));
+    }
+
+    let file = std::fs::File::open(path)?;
+    load_reader(file, &format!("text:{}", path.display()), options)
+}
+
+/// Load text already read into memory; `path` is only used to name the source.
+pub fn load_bytes(
+    data: &[u8],
+    path: &Path,
+    options: &LoadOptions,
+) -> Result<LoadedDocument, LoadError> {
+    load_reader(data, &format!("text:{}", path.display()), options)
+}
+
+/// Load piped text from standard input.
+///
+/// Must run before the terminal switches to raw mode: once stdin is consumed
+/// the TUI reads keys from the controlling terminal instead.
+pub fn load_stdin(options: &LoadOptions) -> Result<LoadedDocument, LoadError> {
+    load_reader(std::io::stdin().lock(), "stdin", options)
+}
+
+/// Reads all bytes from `reader`, decodes them and tokenizes the text.
+///
+/// The encoding is detected unless `options.encoding` overrides it, and is
+/// recorded in the document metadata. Project Gutenberg licence text is
+/// removed unless `options.keep_boilerplate` is set.
+pub fn load_reader<R: Read>(
+    mut reader: R,
+    source: &str,
+    options: &LoadOptions,
+) -> Result<LoadedDocument, LoadError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    let decoded = encoding::decode(&bytes, options.encoding)
+        .ok_or_else(|| LoadError::InvalidEncoding(source.to_string()))?;
+
+    if decoded.text.trim().is_empty() {
+        return Err(LoadError::TextRead(format!("No text in {}", source)));
+    }
+
+    let mut text = decoded.text;
+    let mut notices = Vec::new();
+    if !options.keep_boilerplate {
+        if let Some(trimmed) = gutenberg::strip_boilerplate(&text) {
+            text = trimmed.text;
+            notices.push(trimmed.notice);
+        }
+    }
+
+    Ok(LoadedDocument {
+        tokens: tokenize_document(&text, None, &[])?,
+        source: source.to_string(),
+        sections: Vec::new(),
+        metadata: Metadata {
+            encoding: Some(decoded.encoding.name().to_string()),
+            ..Metadata::default()
+        },
+        cues: Vec::new(),
+        notices,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_reader_tokenizes_text() {
+        let doc = load_reader(
+            "Piped text. Second line".as_bytes(),
+            "stdin",
+            &LoadOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(doc.source, "stdin");
+        assert_eq!(doc.tokens.get(0).unwrap().text, "Piped");
+        assert!(doc.tokens.get(2).unwrap().is_sentence_start);
+    }
+
+    #[test]
+    fn test_load_reader_rejects_empty_input() {
+        let result = load_reader(" \n\t".as_bytes(), "stdin", &LoadOptions::default());
+        assert!(matches!(result, Err(LoadError::TextRead(msg)) if msg.contains("stdin")));
+    }
+
+    #[test]
+    fn test_load_reader_rejects_undecodable_input() {
+        // A UTF-16 byte order mark followed by half a code unit
+        let result = load_reader(&[0xff, 0xfe, 0x41][..], "stdin", &LoadOptions::default());
+        assert!(matches!(result, Err(LoadError::InvalidEncoding(_))));
+    }
+
+    #[test]
+    fn test_load_reader_decodes_legacy_encodings() {
+        let text = "Café crème brûlée, s'il vous plaît. Très bien, merci.";
+        let (bytes, _, _) = encoding_rs::WINDOWS_1252.encode(text);
+        let doc = load_reader(&bytes[..], "stdin", &LoadOptions::default()).unwrap();
+        assert_eq!(doc.tokens.get(0).unwrap().text, "Café");
+        assert_eq!(doc.metadata.encoding.as_deref(), Some("windows-1252"));
+    }
+
+    #[test]
+    fn test_load_reader_encoding_override() {
+        let options = LoadOptions {
+            encoding: Some(encoding_rs::ISO_8859_2),
+            ..LoadOptions::default()
+        };
+        let doc = load_reader(&b"\xa3\xf3d\xbc"[..], "stdin", &options).unwrap();
+        assert_eq!(doc.tokens.get(0).unwrap().text, "Łódź");
+        assert_eq!(doc.metadata.encoding.as_deref(), Some("ISO-8859-2"));
+    }
+
+    #[test]
+    fn test_load_reader_strips_gutenberg_boilerplate() {
+        let book = "The Project Gutenberg eBook of Tales\n\
+            *** START OF THE PROJECT GUTENBERG EBOOK TALES ***\n\
+            Once upon a time.\n\
+            *** END OF THE PROJECT GUTENBERG EBOOK TALES ***\n\
+            Licence terms follow.\n";
+
+        let doc = load_reader(book.as_bytes(), "stdin", &LoadOptions::default()).unwrap();
+        assert_eq!(doc.tokens.get(0).unwrap().text, "Once");
+        assert_eq!(doc.tokens.iter().last().unwrap().text, "time");
+        assert_eq!(doc.notices.len(), 1);
+
+        let options = LoadOptions {
+            keep_boilerplate: true,
+            ..LoadOptions::default()
+        };
+        let doc = load_reader(book.as_bytes(), "stdin", &options).unwrap();
+        assert_eq!(doc.tokens.get(0).unwrap().text, "The");
+        assert!(doc.notices.is_empty());
+    }
+
+    #[test]
+    fn test_text_load_nonexistent_file() {
+        let result = load("/nonexistent/path/notes.txt");
+        assert!(matches!(result, Err(LoadError::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_text_load_file() {
+        let doc = load("tests/test_files/test.txt").unwrap();
+        assert!(doc.source.starts_with("text:"));
+        assert!(!doc.tokens.is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 pub mod app;
 pub mod audio;
+pub mod cli;
 pub mod engine;
 pub mod input;
 pub mod reading;
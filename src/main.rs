@@ -1,36 +1,32 @@
-mod app;
-mod audio;
-mod engine;
-mod input;
-mod reading;
-mod rendering;
-mod storage;
-mod ui;
-
-use crate::app::App;
-use crate::rendering::capability::{
+use speedy::app::App;
+use speedy::cli::{self, InputSource, StartPosition};
+use speedy::engine::error::{error_chain, SpeedyError};
+use speedy::input::text;
+use speedy::rendering::capability::{
     get_tui_fallback_warning, CapabilityDetector, GraphicsCapability,
 };
-use crate::rendering::font::{get_font, get_font_metrics};
-use crate::ui::TuiManager;
+use speedy::rendering::font::{get_font, get_font_metrics};
+use speedy::ui::TuiManager;
 use std::env;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Parse CLI arguments for force flags
-    let args: Vec<String> = env::args().collect();
-    let force_kitty = args.contains(&"--force-kitty".to_string());
-    let force_tui = args.contains(&"--force-tui".to_string());
-
-    // Check for mutually exclusive flags
-    if force_kitty && force_tui {
-        eprintln!("Error: --force-kitty and --force-tui are mutually exclusive");
-        std::process::exit(1);
+    let cli = match cli::parse_args(env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("{}", cli::USAGE);
+            std::process::exit(1);
+        }
+    };
+    if cli.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
     }
 
     // Detect terminal capability
     let detector = CapabilityDetector::new();
     let capability =
-        if let Some(override_cap) = detector.detect_from_override(force_kitty, force_tui) {
+        if let Some(override_cap) = detector.detect_from_override(cli.force_kitty, cli.force_tui) {
             override_cap
         } else {
             detector.detect()
@@ -54,6 +50,47 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let mut app = App::new();
+    app.load_options.encoding = cli.encoding;
+    app.load_options.keep_boilerplate = cli.keep_boilerplate;
+    app.follow_cue_timing = cli.follow_timing;
+    app.hyphenate = cli.hyphenate;
+    app.chunk = cli.chunk;
+    if let Some(pacing) = cli.pacing {
+        app.timing.pacing = pacing;
+    }
+    if let Some(wpm) = cli.wpm {
+        app.default_wpm = wpm;
+    }
+
+    // Load the initial document before entering raw mode, so piped stdin is
+    // consumed and load errors are still visible on the normal screen
+    let loaded = match &cli.input {
+        Some(InputSource::Stdin) => {
+            text::load_stdin(&app.load_options).map(|doc| app.apply_loaded_document(doc))
+        }
+        Some(InputSource::File(spec)) => app.open_initial_file(spec, cli.range),
+        None => Ok(()),
+    };
+    if let Err(e) = loaded {
+        let spec = match &cli.input {
+            Some(InputSource::File(spec)) => spec.as_str(),
+            _ => "standard input",
+        };
+        let error = SpeedyError::load(spec, e);
+        eprintln!("Error: {}", error_chain(&error));
+        if let Some(hint) = error.hint() {
+            eprintln!("{}", hint);
+        }
+        std::process::exit(1);
+    }
+
+    if let (Some(start), Some(state)) = (cli.start, app.reading_state.as_mut()) {
+        match start {
+            StartPosition::Percent(percent) => state.seek_fraction(percent / 100.0),
+            StartPosition::Word(word) => state.seek_to(word - 1),
+        }
+    }
+
     let mut tui = TuiManager::new()?;
 
     // Run the main TUI event loop
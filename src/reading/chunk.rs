@@ -0,0 +1,194 @@
+//! Grouping short words into chunks shown together
+//!
+//! Some readers take in a short phrase as easily as a word. In chunk mode
+//! [`Chunker`] groups consecutive words into display units of up to
+//! [`DEFAULT_MAX_WORDS`] words and [`DEFAULT_MAX_CHARS`] characters. A chunk
+//! ends at punctuation, a sentence start or an opening quote, and a function
+//! word ("the", "of", "zu") starts a new chunk after a content word so it is
+//! shown with the word it leads into: "the cat | on the mat".
+//!
+//! Chunks are worked out from the current word forward, so the reading
+//! position stays a word index and navigation is unchanged.
+
+use super::ovp::calculate_anchor_position;
+use super::store::TokenStore;
+
+/// Longest chunk, in characters including the spaces between words.
+pub const DEFAULT_MAX_CHARS: usize = 16;
+
+/// Most words in a chunk.
+pub const DEFAULT_MAX_WORDS: usize = 3;
+
+/// Articles, prepositions, conjunctions, pronouns and auxiliaries, lowercase.
+const ENGLISH: &[&str] = &[
+    "a", "an", "the", "and", "or", "but", "nor", "of", "in", "on", "at", "to", "by", "for", "with",
+    "from", "as", "into", "onto", "over", "under", "about", "than", "that", "this", "these",
+    "those", "is", "are", "was", "were", "be", "been", "am", "i", "you", "he", "she", "it", "we",
+    "they", "my", "your", "his", "her", "its", "our", "their", "not", "no", "so", "if", "do",
+    "does", "did", "has", "have", "had", "will", "would", "can", "could", "should", "may", "might",
+    "must",
+];
+
+const GERMAN: &[&str] = &[
+    "der", "die", "das", "den", "dem", "des", "ein", "eine", "einen", "einem", "einer", "eines",
+    "und", "oder", "aber", "in", "im", "an", "am", "auf", "aus", "bei", "mit", "nach", "von",
+    "vom", "zu", "zum", "zur", "für", "um", "über", "unter", "vor", "ist", "sind", "war", "ich",
+    "du", "er", "sie", "es", "wir", "ihr", "nicht",
+];
+
+const FRENCH: &[&str] = &[
+    "le", "la", "les", "un", "une", "des", "du", "de", "et", "ou", "mais", "à", "au", "aux", "en",
+    "dans", "sur", "sous", "par", "pour", "avec", "sans", "ce", "cette", "ces", "je", "tu", "il",
+    "elle", "nous", "vous", "ils", "elles", "ne", "pas", "est", "sont",
+];
+
+const SPANISH: &[&str] = &[
+    "el", "la", "los", "las", "un", "una", "unos", "unas", "y", "o", "pero", "de", "del", "a",
+    "al", "en", "con", "por", "para", "sin", "sobre", "que", "se", "lo", "le", "no", "es", "son",
+];
+
+/// Groups words into chunks for a document language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chunker {
+    function_words: &'static [&'static str],
+    max_chars: usize,
+    max_words: usize,
+}
+
+impl Chunker {
+    /// Function words for a language tag such as `de` or `fr-CA`; English
+    /// when the language is unknown.
+    pub fn for_language(tag: Option<&str>) -> Self {
+        let primary = tag
+            .and_then(|tag| tag.split(['-', '_']).next())
+            .map(str::to_ascii_lowercase);
+        Self {
+            function_words: match primary.as_deref() {
+                Some("de" | "deu" | "ger") => GERMAN,
+                Some("fr" | "fra" | "fre") => FRENCH,
+                Some("es" | "spa") => SPANISH,
+                _ => ENGLISH,
+            },
+            max_chars: DEFAULT_MAX_CHARS,
+            max_words: DEFAULT_MAX_WORDS,
+        }
+    }
+
+    pub fn with_limits(self, max_chars: usize, max_words: usize) -> Self {
+        Self {
+            max_chars,
+            max_words: max_words.max(1),
+            ..self
+        }
+    }
+
+    /// Index one past the last word of the chunk starting at `start`;
+    /// `start` itself if there is no word there.
+    pub fn chunk_end(&self, tokens: &TokenStore, start: usize) -> usize {
+        let Some(mut last) = tokens.get(start) else {
+            return start;
+        };
+        let mut end = start + 1;
+        let mut chars = last.text.chars().count();
+        while end - start < self.max_words {
+            let Some(next) = tokens.get(end) else {
+                break;
+            };
+            // Pauses, new sentences, quotations and line breaks end a chunk
+            if !last.punctuation.is_empty()
+                || last.text.is_empty()
+                || next.text.is_empty()
+                || next.is_sentence_start
+                || !next.leading.is_empty()
+            {
+                break;
+            }
+            let width = chars + 1 + next.text.chars().count();
+            if width > self.max_chars {
+                break;
+            }
+            // A function word leads into the next chunk instead
+            if !self.is_function_word(last.text) && self.is_function_word(next.text) {
+                break;
+            }
+            chars = width;
+            last = next;
+            end += 1;
+        }
+        end
+    }
+
+    fn is_function_word(&self, word: &str) -> bool {
+        self.function_words.contains(&word.to_lowercase().as_str())
+    }
+}
+
+impl Default for Chunker {
+    fn default() -> Self {
+        Self::for_language(None)
+    }
+}
+
+/// Anchor of a chunk shown as `words` joined by spaces: the anchor letter of
+/// its longest word, as a character index into the chunk.
+pub fn chunk_anchor_position(words: &[&str]) -> usize {
+    let mut offset = 0;
+    let mut best = (0, 0);
+    for word in words {
+        let len = word.chars().count();
+        if len > best.0 {
+            best = (len, offset + calculate_anchor_position(word));
+        }
+        offset += len + 1;
+    }
+    best.1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reading::tokenize_text;
+
+    fn chunks(chunker: &Chunker, text: &str) -> Vec<String> {
+        let tokens = TokenStore::from(tokenize_text(text));
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        while start < tokens.len() {
+            let end = chunker.chunk_end(&tokens, start);
+            let words: Vec<&str> = tokens.range(start..end).map(|t| t.text).collect();
+            chunks.push(words.join(" "));
+            start = end;
+        }
+        chunks
+    }
+
+    #[test]
+    fn test_function_words_lead_into_content_words() {
+        let english = Chunker::default();
+        assert_eq!(
+            chunks(&english, "The cat sat on the mat in the sun"),
+            ["The cat sat", "on the mat", "in the sun"]
+        );
+        assert_eq!(
+            chunks(&english, "She read books, then slept. \"Fine\" said Bob"),
+            ["She read books", "then slept", "Fine", "said Bob"]
+        );
+        assert_eq!(
+            chunks(&english, "an extraordinarily long word"),
+            ["an", "extraordinarily", "long word"]
+        );
+
+        let german = Chunker::for_language(Some("de"));
+        assert_eq!(
+            chunks(&german, "Wir fahren mit dem Zug nach Wien"),
+            ["Wir fahren", "mit dem Zug", "nach Wien"]
+        );
+    }
+
+    #[test]
+    fn test_chunk_anchor_is_on_longest_word() {
+        assert_eq!(chunk_anchor_position(&["on", "the", "mat"]), 4);
+        assert_eq!(chunk_anchor_position(&["in", "the", "garden"]), 9);
+        assert_eq!(chunk_anchor_position(&["word"]), 1);
+    }
+}
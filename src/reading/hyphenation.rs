@@ -0,0 +1,187 @@
+//! Splitting long words into display fragments
+//!
+//! A word longer than the centre column, a long compound or a URL is hard
+//! to take in during one flash. [`Hyphenator`] breaks such a word into
+//! fragments shown one after another, each anchored on its own. Inside a
+//! run of letters it breaks at hyphenation points found with the Liang
+//! patterns of the document language (embedded by the `hypher` crate), and
+//! those fragments are shown with a trailing hyphen. Compounds and URLs are
+//! broken after their own hyphens, slashes and dots first.
+
+use hypher::Lang;
+use std::ops::Range;
+
+/// Longest fragment shown, in characters including its hyphen.
+pub const DEFAULT_MAX_CHARS: usize = 13;
+
+/// Characters a word may be broken after without adding a hyphen.
+const SEPARATORS: &[char] = &['-', '/', '.', '_', '?', '&', '=', '#', ':', '@', '\u{2010}'];
+
+/// Part of a word shown on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fragment {
+    /// Byte range in the word.
+    pub range: Range<usize>,
+    /// The word continues after a hyphenation point: show a hyphen.
+    pub hyphen: bool,
+}
+
+/// Splits words longer than `max_chars` at hyphenation points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hyphenator {
+    lang: Lang,
+    max_chars: usize,
+}
+
+impl Hyphenator {
+    /// Patterns for a language tag such as `de` or `en-GB`; English when
+    /// the language is unknown or has no patterns.
+    pub fn for_language(tag: Option<&str>) -> Self {
+        let lang = tag
+            .and_then(|tag| tag.split(['-', '_']).next())
+            .and_then(|primary| <[u8; 2]>::try_from(primary.to_ascii_lowercase().as_bytes()).ok())
+            .and_then(Lang::from_iso)
+            .unwrap_or(Lang::English);
+        Self {
+            lang,
+            max_chars: DEFAULT_MAX_CHARS,
+        }
+    }
+
+    pub fn with_max_chars(self, max_chars: usize) -> Self {
+        Self {
+            max_chars: max_chars.max(4),
+            ..self
+        }
+    }
+
+    /// The fragments of `word` in order; a single fragment covering the
+    /// whole word if it fits.
+    pub fn fragments(&self, word: &str) -> Vec<Fragment> {
+        if word.chars().count() <= self.max_chars {
+            return vec![Fragment {
+                range: 0..word.len(),
+                hyphen: false,
+            }];
+        }
+
+        let breaks = self.break_points(word);
+        let mut fragments = Vec::new();
+        let mut start = 0;
+        while word[start..].chars().count() > self.max_chars {
+            let fits = |&&(end, hyphen): &&(usize, bool)| {
+                end > start
+                    && word[start..end].chars().count() + usize::from(hyphen) <= self.max_chars
+            };
+            // Prefer the word's own separators to added hyphens
+            let separator = breaks.iter().rev().filter(|(_, hyphen)| !hyphen).find(fits);
+            let (end, hyphen) = match separator.or_else(|| breaks.iter().rev().find(fits)) {
+                Some(&point) => point,
+                // No break point fits: cut the word, leaving room for a hyphen
+                None => {
+                    let (end, _) = word[start..]
+                        .char_indices()
+                        .nth(self.max_chars - 1)
+                        .expect("word longer than max_chars");
+                    (start + end, true)
+                }
+            };
+            fragments.push(Fragment {
+                range: start..end,
+                hyphen,
+            });
+            start = end;
+        }
+        fragments.push(Fragment {
+            range: start..word.len(),
+            hyphen: false,
+        });
+        fragments
+    }
+
+    /// Byte offsets the word may be broken at, with whether a hyphen is
+    /// needed there, in order.
+    fn break_points(&self, word: &str) -> Vec<(usize, bool)> {
+        let mut breaks = Vec::new();
+        let mut run_start = None;
+        for (i, c) in word.char_indices().chain([(word.len(), ' ')]) {
+            if c.is_alphabetic() {
+                run_start.get_or_insert(i);
+                continue;
+            }
+            if let Some(start) = run_start.take() {
+                let mut offset = start;
+                let syllables: Vec<&str> = hypher::hyphenate(&word[start..i], self.lang).collect();
+                for syllable in &syllables[..syllables.len() - 1] {
+                    offset += syllable.len();
+                    breaks.push((offset, true));
+                }
+            }
+            let after = i + c.len_utf8();
+            if SEPARATORS.contains(&c) && after < word.len() {
+                breaks.push((after, false));
+            }
+        }
+        breaks
+    }
+}
+
+impl Default for Hyphenator {
+    fn default() -> Self {
+        Self::for_language(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shown(hyphenator: &Hyphenator, word: &str) -> Vec<String> {
+        hyphenator
+            .fragments(word)
+            .into_iter()
+            .map(|f| format!("{}{}", &word[f.range], if f.hyphen { "-" } else { "" }))
+            .collect()
+    }
+
+    #[test]
+    fn test_long_words_break_at_hyphenation_points() {
+        let english = Hyphenator::default();
+        assert_eq!(shown(&english, "reading"), ["reading"]);
+        assert_eq!(
+            shown(&english, "incomprehensibilities"),
+            ["incomprehen-", "sibilities"]
+        );
+        for fragment in shown(&english, "Antidisestablishmentarianism") {
+            assert!(
+                fragment.chars().count() <= DEFAULT_MAX_CHARS,
+                "{}",
+                fragment
+            );
+        }
+
+        let german = Hyphenator::for_language(Some("de-DE"));
+        assert_eq!(
+            shown(&german, "Donaudampfschifffahrt").concat(),
+            "Donaudampf-schifffahrt"
+        );
+    }
+
+    #[test]
+    fn test_compounds_and_urls_break_at_separators() {
+        let english = Hyphenator::default();
+        assert_eq!(
+            shown(&english, "state-of-the-art-technology"),
+            ["state-of-the-", "art-", "technology"]
+        );
+        assert_eq!(
+            shown(&english, "https://example.com/articles/speed"),
+            ["https://", "example.com/", "articles/", "speed"]
+        );
+        // No separator or syllable break: cut with a hyphen
+        assert_eq!(
+            shown(&english.with_max_chars(6), "xxxxxxxxxxxx"),
+            ["xxxxx-", "xxxxx-", "xx"]
+        );
+    }
+}
@@ -1,9 +1,17 @@
+pub mod chunk;
+pub mod hyphenation;
 pub mod ovp;
+pub mod pacing;
+pub mod sentence;
 pub mod state;
+pub mod store;
+pub mod strategy;
 pub mod timing;
 pub mod token;
 
 pub use ovp::calculate_anchor_position;
 pub use state::ReadingState;
+pub use store::TokenStore;
+pub use strategy::{TimingContext, TimingStrategy};
 pub use timing::{detect_sentence_boundary, tokenize_text, wpm_to_milliseconds};
 pub use token::Token;
@@ -0,0 +1,244 @@
+//! Word complexity for pacing
+//!
+//! The default pacing only lengthens words past `long_word_threshold`. The
+//! frequency model instead scales each word by how hard it is to take in:
+//! words missing from a [`FrequencyTable`] (rare words), numbers, proper
+//! nouns and words of many syllables are shown longer, and the most common
+//! short words shorter. [`FrequencyStrategy`](super::strategy::FrequencyStrategy)
+//! divides the factors by their document average, so the reading speed
+//! still averages out to the chosen WPM.
+
+use super::store::TokenRef;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Embedded English word list, most frequent first.
+const ENGLISH_WORDS: &str = include_str!("../../assets/frequency/en.txt");
+
+/// Factor of a word missing from the frequency table.
+const RARE: f64 = 1.3;
+
+/// Extra factor of a capitalised word inside a sentence.
+const PROPER_NOUN: f64 = 1.25;
+
+/// Factors are kept within this range before averaging.
+const MIN_FACTOR: f64 = 0.6;
+const MAX_FACTOR: f64 = 2.5;
+
+lazy_static! {
+    static ref ENGLISH: FrequencyTable = FrequencyTable::parse(ENGLISH_WORDS);
+}
+
+/// Built-in timing strategy, chosen through `TimingConfig::pacing`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PacingModel {
+    /// Words past `long_word_threshold` get `long_word_penalty`.
+    #[default]
+    Length,
+    /// Words are scaled by frequency, digits, capitals and syllables.
+    Frequency,
+    /// Words are scaled by their syllables.
+    Syllable,
+    /// Every word is shown for the same time.
+    Constant,
+}
+
+impl PacingModel {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "length" | "weighted" => Some(PacingModel::Length),
+            "frequency" => Some(PacingModel::Frequency),
+            "syllable" | "syllables" => Some(PacingModel::Syllable),
+            "constant" => Some(PacingModel::Constant),
+            _ => None,
+        }
+    }
+}
+
+/// Frequency ranks of common words, 1 for the most common.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyTable {
+    ranks: Arc<HashMap<String, u32>>,
+}
+
+impl FrequencyTable {
+    /// The embedded English list.
+    pub fn english() -> Self {
+        ENGLISH.clone()
+    }
+
+    /// A table from words ordered most frequent first; repeats keep their
+    /// first rank.
+    pub fn from_ranked<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut ranks = HashMap::new();
+        for word in words {
+            let next = ranks.len() as u32 + 1;
+            ranks.entry(word.as_ref().to_lowercase()).or_insert(next);
+        }
+        Self {
+            ranks: Arc::new(ranks),
+        }
+    }
+
+    /// A table from a word list, one word per line, most frequent first.
+    /// Anything after the word (such as a count) is ignored, as are blank
+    /// lines and lines starting with `#`.
+    pub fn parse(text: &str) -> Self {
+        Self::from_ranked(
+            text.lines()
+                .filter(|line| !line.starts_with('#'))
+                .filter_map(|line| line.split_whitespace().next()),
+        )
+    }
+
+    pub fn rank(&self, word: &str) -> Option<u32> {
+        self.ranks.get(&word.to_lowercase()).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranks.is_empty()
+    }
+}
+
+impl Default for FrequencyTable {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+/// How much longer than average `token` should be shown under the
+/// frequency model, before averaging over the document.
+pub fn complexity(token: TokenRef, table: &FrequencyTable) -> f64 {
+    let word = token.text;
+    if word.is_empty() {
+        return 1.0;
+    }
+
+    let digits = word.chars().filter(char::is_ascii_digit).count();
+    let familiarity = if digits > 0 {
+        // Each digit has to be read
+        1.3 + 0.1 * digits as f64
+    } else {
+        match table.rank(word) {
+            Some(rank) => 0.7 + 0.15 * f64::from(rank).log10(),
+            None => RARE,
+        }
+    };
+
+    let capitalised = word.chars().next().is_some_and(char::is_uppercase);
+    let proper_noun = capitalised && !token.is_sentence_start && table.rank(word).is_none();
+    let familiarity = if proper_noun {
+        familiarity * PROPER_NOUN
+    } else {
+        familiarity
+    };
+
+    let extra_syllables = syllables(word).saturating_sub(2) as f64;
+    (familiarity * (1.0 + 0.1 * extra_syllables)).clamp(MIN_FACTOR, MAX_FACTOR)
+}
+
+/// Estimated syllables in `word`: groups of vowels, less a silent final
+/// "e"; at least one.
+pub fn syllables(word: &str) -> usize {
+    let lower = word.to_lowercase();
+    let mut count = 0;
+    let mut in_vowels = false;
+    for c in lower.chars() {
+        let vowel = is_vowel(c);
+        if vowel && !in_vowels {
+            count += 1;
+        }
+        in_vowels = vowel;
+    }
+    let silent_e =
+        lower.ends_with('e') && !lower.ends_with("le") && !lower.ends_with("ee") && count > 1;
+    (count - usize::from(silent_e)).max(1)
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(
+        c,
+        'a' | 'e'
+            | 'i'
+            | 'o'
+            | 'u'
+            | 'y'
+            | 'à'
+            | 'á'
+            | 'â'
+            | 'ä'
+            | 'è'
+            | 'é'
+            | 'ê'
+            | 'ë'
+            | 'ì'
+            | 'í'
+            | 'î'
+            | 'ï'
+            | 'ò'
+            | 'ó'
+            | 'ô'
+            | 'ö'
+            | 'ù'
+            | 'ú'
+            | 'û'
+            | 'ü'
+            | 'æ'
+            | 'œ'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reading::TokenStore;
+
+    fn factor(text: &str) -> f64 {
+        let tokens = TokenStore::from(crate::reading::tokenize_text(text));
+        complexity(
+            tokens.get(tokens.len() - 1).unwrap(),
+            &FrequencyTable::english(),
+        )
+    }
+
+    #[test]
+    fn test_rare_long_and_numeric_words_are_slower() {
+        assert!(factor("the") < 0.8);
+        assert!(factor("the") < factor("house"));
+        assert!(factor("house") < factor("quay"));
+        assert!(factor("quay") < factor("incomprehensibility"));
+        assert!(factor("in 1984") > factor("in year"));
+        // Capitalised inside a sentence, but not at its start
+        assert!(factor("met Anselm") > factor("Anselm"));
+    }
+
+    #[test]
+    fn test_syllables_and_tables() {
+        assert_eq!(syllables("the"), 1);
+        assert_eq!(syllables("make"), 1);
+        assert_eq!(syllables("table"), 2);
+        assert_eq!(syllables("reading"), 2);
+        assert_eq!(syllables("incomprehensibility"), 8);
+
+        let english = FrequencyTable::english();
+        assert_eq!(english.rank("The"), Some(1));
+        assert!(english.len() > 500);
+
+        let custom = FrequencyTable::parse("# rank list\nder 500\ndie 400\n\nder\n");
+        assert_eq!((custom.rank("Die"), custom.len()), (Some(2), 2));
+        assert_eq!(
+            PacingModel::from_name("Frequency"),
+            Some(PacingModel::Frequency)
+        );
+        assert_eq!(PacingModel::from_name("fast"), None);
+    }
+}
@@ -0,0 +1,188 @@
+//! Sentence segmentation
+//!
+//! Sentence boundaries come from the Unicode sentence rules (UAX #29), which
+//! handle closing quotes and brackets after a terminator and scripts without
+//! ASCII capitals. Three corrections are applied on top:
+//!
+//! - no boundary after a known abbreviation ("Dr.", "e.g.", "z.B.") or a
+//!   single-letter initial ("J. R. R. Tolkien"), unless the next word is a
+//!   common sentence opener ("Take plan A. Then run.");
+//! - no boundary before a lowercase word, as in `"Really?" she asked`;
+//! - a boundary after an ellipsis ("…") and before a number after a
+//!   terminator ("He left. 2 men came."), which UAX #29 does not count.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Abbreviations that do not end a sentence, lowercase, without the final
+/// period.
+const ENGLISH: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "st", "jr", "sr", "vs", "e.g", "i.e", "cf", "fig", "figs",
+    "eq", "approx", "al", "mt", "capt", "gen", "col", "lt", "sgt", "rev", "hon", "ca", "vol", "ch",
+    "sec", "pp",
+];
+
+const GERMAN: &[&str] = &[
+    "dr", "prof", "hr", "fr", "nr", "z.b", "d.h", "u.a", "z.t", "vgl", "bzw", "ca", "evtl", "ggf",
+    "bspw", "inkl", "sog", "str", "abb", "bd", "s.o", "s.u",
+];
+
+const FRENCH: &[&str] = &[
+    "mme", "mlle", "mm", "mgr", "dr", "pr", "cf", "p.ex", "env", "av", "bd", "vol", "chap",
+];
+
+const SPANISH: &[&str] = &[
+    "sr", "sra", "srta", "dr", "dra", "ud", "uds", "p.ej", "pág", "núm", "av", "avda", "cap",
+];
+
+const RUSSIAN: &[&str] = &[
+    "т.е", "т.к", "т.н", "ул", "стр", "проф", "акад", "напр", "гг", "им", "тов",
+];
+
+/// Words that usually start a sentence rather than follow an initial,
+/// lowercase.
+const SENTENCE_OPENERS: &[&str] = &[
+    "a", "after", "all", "also", "an", "and", "as", "at", "before", "but", "by", "for", "he",
+    "her", "his", "how", "i", "if", "in", "it", "its", "my", "no", "not", "now", "on", "one",
+    "our", "she", "so", "some", "that", "the", "their", "then", "there", "these", "they", "this",
+    "those", "to", "we", "what", "when", "where", "which", "who", "why", "yes", "you", "your",
+];
+
+/// Closing quotes and brackets that may follow a terminator.
+const CLOSERS: &[char] = &['"', '\'', '”', '’', '»', '›', ')', ']', '}'];
+
+/// Opening quotes, brackets and inverted marks that may precede a word.
+const OPENERS: &[char] = &['"', '\'', '“', '‘', '«', '‹', '(', '[', '{', '¿', '¡'];
+
+/// Abbreviations of a document language.
+#[derive(Debug, Clone, Copy)]
+pub struct Abbreviations(&'static [&'static str]);
+
+impl Abbreviations {
+    /// The list for a language tag such as `de` or `fr-CA`; English when the
+    /// language is unknown.
+    pub fn for_language(tag: Option<&str>) -> Self {
+        let primary = tag
+            .and_then(|tag| tag.split(['-', '_']).next())
+            .map(str::to_ascii_lowercase);
+        Self(match primary.as_deref() {
+            Some("de" | "deu" | "ger") => GERMAN,
+            Some("fr" | "fra" | "fre") => FRENCH,
+            Some("es" | "spa") => SPANISH,
+            Some("ru" | "rus") => RUSSIAN,
+            _ => ENGLISH,
+        })
+    }
+
+    /// Returns true if `word` (with its final period) is an abbreviation.
+    pub fn contains(&self, word: &str) -> bool {
+        word.strip_suffix('.')
+            .is_some_and(|stem| self.0.contains(&stem.to_lowercase().as_str()))
+    }
+}
+
+/// Returns true if `word` is a single-letter initial, judged by the `next`
+/// word: another initial or a name, but not a common sentence opener. "I."
+/// is never an initial.
+fn is_initial(word: &str, next: &str) -> bool {
+    let Some(stem) = word.strip_suffix('.') else {
+        return false;
+    };
+    let mut chars = stem.chars();
+    let single_letter = chars.next().is_some_and(char::is_alphabetic) && chars.next().is_none();
+    if !single_letter || stem == "I" {
+        return false;
+    }
+    let next = next
+        .trim_start_matches(OPENERS)
+        .trim_end_matches(|c: char| !c.is_alphanumeric());
+    !SENTENCE_OPENERS.contains(&next.to_lowercase().as_str())
+}
+
+/// Byte offsets in `text` at which a sentence starts, after the first one.
+pub fn sentence_starts(text: &str, abbreviations: &Abbreviations) -> Vec<usize> {
+    let mut candidates: Vec<usize> = text
+        .split_sentence_bound_indices()
+        .skip(1)
+        .map(|(offset, _)| offset)
+        .collect();
+
+    // UAX #29 does not end a sentence at "…" or before a number
+    let mut previous: Option<&str> = None;
+    for word in text.split_whitespace() {
+        if previous.is_some_and(ends_with_terminator) {
+            candidates.push(offset_in(text, word));
+        }
+        previous = Some(word);
+    }
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    candidates.retain(|&offset| is_boundary(text, offset, abbreviations));
+    candidates
+}
+
+/// Returns true if a sentence really ends before `offset`.
+fn is_boundary(text: &str, offset: usize, abbreviations: &Abbreviations) -> bool {
+    let Some(last) = text[..offset].split_whitespace().next_back() else {
+        return false;
+    };
+    let Some(next) = text[offset..].split_whitespace().next() else {
+        return false;
+    };
+
+    let starts_lowercase = next
+        .chars()
+        .find(|c| c.is_alphanumeric())
+        .is_some_and(char::is_lowercase);
+    let last = last.trim_start_matches(OPENERS).trim_end_matches(CLOSERS);
+    !starts_lowercase && !abbreviations.contains(last) && !is_initial(last, next)
+}
+
+fn ends_with_terminator(word: &str) -> bool {
+    word.trim_end_matches(CLOSERS)
+        .ends_with(['.', '?', '!', '…'])
+}
+
+/// Byte offset of `word`, a slice of `text`, within `text`.
+fn offset_in(text: &str, word: &str) -> usize {
+    word.as_ptr() as usize - text.as_ptr() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn starts(text: &str) -> Vec<&str> {
+        let english = Abbreviations::for_language(None);
+        sentence_starts(text, &english)
+            .into_iter()
+            .map(|offset| text[offset..].split_whitespace().next().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_quotes_brackets_and_scripts() {
+        assert_eq!(
+            starts("He said \"Stop.\" Then he left. (It was late.) «Да.» Привет."),
+            ["Then", "(It", "«Да.»", "Привет."]
+        );
+        assert_eq!(starts("\"Really?\" she asked. ¿Qué? Sí."), ["¿Qué?", "Sí."]);
+    }
+
+    #[test]
+    fn test_abbreviations_and_initials() {
+        assert!(starts("Dr. Smith met J. R. R. Tolkien, e.g. The Hobbit author.").is_empty());
+        assert_eq!(starts("He left. 2 men came. See fig. 3 now."), ["2", "See"]);
+        assert_eq!(starts("So did I. Then we left."), ["Then"]);
+        assert_eq!(
+            starts("Take plan A. Then run. Ask J. Smith."),
+            ["Then", "Ask"]
+        );
+
+        let german = Abbreviations::for_language(Some("de-AT"));
+        assert!(sentence_starts("Das ist z.B. Ein Test.", &german).is_empty());
+        assert!(german.contains("Nr."));
+        assert!(!german.contains("Nr"));
+        assert!(!Abbreviations::for_language(Some("en")).contains("z.B."));
+    }
+}
@@ -1,36 +1,138 @@
+use super::chunk::Chunker;
+use super::hyphenation::{Fragment, Hyphenator};
+use super::store::{TokenRef, TokenStore};
+use super::strategy::punctuation_multiplier;
+use super::{TimingContext, TimingStrategy};
 use crate::engine::config::TimingConfig;
-use crate::engine::{wpm_to_milliseconds, Token};
+use std::ops::Range;
+
+/// How far [`ReadingState::replace_tokens`] looks for the current word.
+const RELOCATE_WINDOW: usize = 200;
 
 pub struct ReadingState {
-    pub tokens: Vec<Token>,
+    pub tokens: TokenStore,
     pub current_index: usize,
     pub wpm: u32,
+    /// Fragment of the current word being shown, when it is split.
+    pub fragment: usize,
+    /// Splits long words into fragments; `None` shows every word whole.
+    hyphenator: Option<Hyphenator>,
+    /// Groups short words into chunks; `None` shows one word at a time.
+    chunker: Option<Chunker>,
     config: TimingConfig,
+    /// Decides how long each word is shown; from `config.pacing` unless
+    /// replaced with [`ReadingState::set_strategy`].
+    strategy: Box<dyn TimingStrategy>,
 }
 
 impl ReadingState {
-    pub fn new(tokens: Vec<Token>, wpm: u32, config: TimingConfig) -> Self {
+    pub fn new(tokens: impl Into<TokenStore>, wpm: u32, config: TimingConfig) -> Self {
+        let tokens = tokens.into();
+        let mut strategy = config.pacing.strategy();
+        strategy.prepare(&tokens, &config);
         Self {
+            strategy,
             tokens,
             current_index: 0,
             wpm,
+            fragment: 0,
+            hyphenator: None,
+            chunker: None,
             config,
         }
     }
 
-    pub fn new_with_default_config(tokens: Vec<Token>, wpm: u32) -> Self {
+    pub fn new_with_default_config(tokens: impl Into<TokenStore>, wpm: u32) -> Self {
         Self::new(tokens, wpm, TimingConfig::default())
     }
 
-    pub fn current_token(&self) -> Option<&Token> {
+    pub fn current_token(&self) -> Option<TokenRef<'_>> {
         self.tokens.get(self.current_index)
     }
 
+    /// Times words with `strategy` instead of the one `config.pacing`
+    /// picked.
+    pub fn set_strategy(&mut self, mut strategy: Box<dyn TimingStrategy>) {
+        strategy.prepare(&self.tokens, &self.config);
+        self.strategy = strategy;
+    }
+
+    /// Starts or stops splitting long words, back at the start of the
+    /// current word.
+    pub fn set_hyphenator(&mut self, hyphenator: Option<Hyphenator>) {
+        self.hyphenator = hyphenator;
+        self.fragment = 0;
+    }
+
+    /// Starts or stops showing short words in chunks.
+    pub fn set_chunker(&mut self, chunker: Option<Chunker>) {
+        self.chunker = chunker;
+        self.fragment = 0;
+    }
+
+    /// Indexes of the words shown together with the current one, starting
+    /// at it; just the current word unless a chunker is set.
+    pub fn current_chunk(&self) -> Range<usize> {
+        let start = self.current_index;
+        match &self.chunker {
+            Some(chunker) => start..chunker.chunk_end(&self.tokens, start),
+            None => start..(start + 1).min(self.tokens.len()),
+        }
+    }
+
+    /// The fragments the current word is shown in; one covering the whole
+    /// word unless it is long, shown alone and a hyphenator is set.
+    pub fn current_fragments(&self) -> Vec<Fragment> {
+        let Some(token) = self.current_token() else {
+            return Vec::new();
+        };
+        match &self.hyphenator {
+            Some(hyphenator) if self.current_chunk().len() <= 1 => hyphenator.fragments(token.text),
+            _ => vec![Fragment {
+                range: 0..token.text.len(),
+                hyphen: false,
+            }],
+        }
+    }
+
+    /// The part of the current word to show, and whether it is followed by
+    /// a hyphen.
+    pub fn current_fragment(&self) -> Option<(&str, bool)> {
+        let token = self.current_token()?;
+        let fragment = self.current_fragments().into_iter().nth(self.fragment)?;
+        Some((&token.text[fragment.range], fragment.hyphen))
+    }
+
+    /// Milliseconds to show the current word or fragment.
+    ///
+    /// A split word takes as long as it would whole, shared evenly between
+    /// its fragments; the punctuation pause comes after the last one.
     pub fn current_token_duration(&self) -> u64 {
-        match self.current_token() {
-            Some(token) => self.calculate_token_duration(token),
-            None => 0,
+        let Some(token) = self.current_token() else {
+            return 0;
+        };
+        let whole = self.token_duration(self.current_index);
+        let fragments = self.current_fragments().len() as u64;
+        if fragments <= 1 {
+            return whole;
+        }
+        let multiplier = punctuation_multiplier(token.punctuation, &self.config);
+        let share = (whole as f64 / multiplier / fragments as f64) as u64;
+        if (self.fragment as u64) + 1 < fragments {
+            share
+        } else {
+            whole - share * (fragments - 1)
+        }
+    }
+
+    /// Milliseconds to show what is on screen: the sum of the word
+    /// durations of a chunk, or the current word or fragment.
+    pub fn current_duration(&self) -> u64 {
+        let chunk = self.current_chunk();
+        if chunk.len() <= 1 {
+            return self.current_token_duration();
         }
+        chunk.map(|index| self.token_duration(index)).sum()
     }
 
     /// Returns the current WPM setting for timeout calculation.
@@ -48,41 +150,73 @@ impl ReadingState {
         ) as u32;
     }
 
-    fn calculate_token_duration(&self, token: &Token) -> u64 {
-        let base_delay_ms = wpm_to_milliseconds(self.wpm);
-
-        // PRD Section 3.2: Punctuation Multipliers with max stacking rule
-        let punctuation_multiplier = if token.punctuation.is_empty() {
-            1.0
-        } else {
-            token
-                .punctuation
-                .iter()
-                .map(|&p| match p {
-                    '.' => self.config.period_multiplier,
-                    '?' => self.config.question_multiplier,
-                    '!' => self.config.exclamation_multiplier,
-                    ',' => self.config.comma_multiplier,
-                    '\n' => self.config.newline_multiplier,
-                    _ => 1.0,
-                })
-                .fold(1.0, f64::max)
+    /// Milliseconds to show the word at `index`, from the strategy.
+    fn token_duration(&self, index: usize) -> u64 {
+        let Some(token) = self.tokens.get(index) else {
+            return 0;
+        };
+        let context = TimingContext {
+            wpm: self.wpm,
+            config: &self.config,
+            tokens: &self.tokens,
+            index,
         };
+        self.strategy.duration_ms(token, &context)
+    }
 
-        // PRD Section 3.2: Word Length Penalty
-        let word_length = token.text.chars().count();
-        let length_penalty = if word_length > self.config.long_word_threshold {
-            self.config.long_word_penalty
-        } else {
-            1.0
+    /// Moves to the next fragment of a split word, or to the word after
+    /// the current chunk.
+    pub fn advance(&mut self) {
+        let next = self.current_chunk().end;
+        if self.fragment + 1 < self.current_fragments().len() {
+            self.fragment += 1;
+        } else if next < self.tokens.len() {
+            self.current_index = next;
+            self.fragment = 0;
+        }
+    }
+
+    /// Swaps in a fuller extraction of the same document, keeping the reader
+    /// on the same word.
+    ///
+    /// Cleanup of earlier pages can change as more pages arrive, so the word
+    /// may have moved a little: it is looked for within [`RELOCATE_WINDOW`]
+    /// of its old index, together with the two words before it.
+    pub fn replace_tokens(&mut self, tokens: impl Into<TokenStore>) {
+        let tokens = tokens.into();
+        let index = self.current_index;
+        let context = |tokens: &TokenStore, i: usize| -> Vec<String> {
+            tokens
+                .range(i.saturating_sub(2)..i + 1)
+                .map(|t| t.text.to_string())
+                .collect()
         };
+        let found = self.tokens.get(index).and_then(|_| {
+            let target = context(&self.tokens, index);
+            (0..=RELOCATE_WINDOW)
+                .flat_map(|d| [Some(index + d), index.checked_sub(d)])
+                .flatten()
+                .filter(|&i| i < tokens.len())
+                .find(|&i| context(&tokens, i) == target)
+        });
+        self.strategy.prepare(&tokens, &self.config);
+        self.tokens = tokens;
+        self.seek_to(found.unwrap_or(index));
+    }
 
-        (base_delay_ms as f64 * punctuation_multiplier * length_penalty) as u64
+    /// Moves to the word at `index`, clamped to the last word.
+    pub fn seek_to(&mut self, index: usize) {
+        self.current_index = index.min(self.tokens.len().saturating_sub(1));
+        self.fragment = 0;
     }
 
-    pub fn advance(&mut self) {
-        if self.current_index < self.tokens.len().saturating_sub(1) {
-            self.current_index += 1;
+    /// Moves to the start of the sentence containing the word `fraction`
+    /// (0.0 to 1.0) of the way through the document.
+    pub fn seek_fraction(&mut self, fraction: f64) {
+        let target = (self.tokens.len() as f64 * fraction.clamp(0.0, 1.0)) as usize;
+        self.seek_to(target);
+        if !self.current_token().is_some_and(|t| t.is_sentence_start) {
+            self.jump_to_previous_sentence();
         }
     }
 
@@ -91,16 +225,13 @@ impl ReadingState {
         if start >= self.tokens.len() {
             return None;
         }
-        self.tokens[start..]
-            .iter()
-            .position(|token| token.is_sentence_start)
-            .map(|pos| pos + start)
+        (start..self.tokens.len()).find(|&i| self.tokens.is_sentence_start(i))
     }
 
     pub fn jump_to_next_sentence(&mut self) -> bool {
         match self.find_next_sentence_start() {
             Some(index) => {
-                self.current_index = index;
+                self.seek_to(index);
                 true
             }
             None => false,
@@ -113,19 +244,15 @@ impl ReadingState {
         }
 
         // Search backwards from current_index - 1
-        let end = self.current_index;
-        self.tokens[..end]
-            .iter()
-            .enumerate()
+        (0..self.current_index)
             .rev()
-            .find(|(_, token)| token.is_sentence_start)
-            .map(|(idx, _)| idx)
+            .find(|&i| self.tokens.is_sentence_start(i))
     }
 
     pub fn jump_to_previous_sentence(&mut self) -> bool {
         match self.find_previous_sentence_start() {
             Some(index) => {
-                self.current_index = index;
+                self.seek_to(index);
                 true
             }
             None => false,
@@ -136,12 +263,14 @@ impl ReadingState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::engine::Token;
+    use crate::reading::pacing::{FrequencyTable, PacingModel};
+    use crate::reading::tokenize_text;
 
     fn create_test_token(text: &str, is_sentence_start: bool) -> Token {
         Token {
-            text: text.to_string(),
-            punctuation: vec![],
             is_sentence_start,
+            ..Token::new(text)
         }
     }
 
@@ -241,9 +370,8 @@ mod tests {
     fn test_current_token_duration_long_word() {
         // Create a token with a long word (> 10 chars)
         let tokens = vec![Token {
-            text: "extraordinarily".to_string(),
-            punctuation: vec![],
             is_sentence_start: true,
+            ..Token::new("extraordinarily")
         }];
         let state = ReadingState::new_with_default_config(tokens, 300);
         // 300 WPM = 200ms per word * 1.15 (long word penalty) = 229ms (rounded)
@@ -253,15 +381,29 @@ mod tests {
     #[test]
     fn test_current_token_duration_with_punctuation() {
         let tokens = vec![Token {
-            text: "hello".to_string(),
             punctuation: vec!['.'],
             is_sentence_start: true,
+            ..Token::new("hello")
         }];
         let state = ReadingState::new_with_default_config(tokens, 300);
         // 300 WPM = 200ms per word * 3.0 (period multiplier) = 600ms
         assert_eq!(state.current_token_duration(), 600);
     }
 
+    #[test]
+    fn test_current_token_duration_with_extended_punctuation() {
+        let tokens = crate::engine::tokenize_text("a; b: c— d... e…” f.” g)");
+        let mut state = ReadingState::new_with_default_config(tokens, 300);
+        let mut durations = vec![state.current_token_duration()];
+        while state.current_index + 1 < state.tokens.len() {
+            state.advance();
+            durations.push(state.current_token_duration());
+        }
+        // 200ms base: semicolon, colon and dash 2x, ellipsis 3.5x (also
+        // before a closing quote), a period outweighs its quote, bracket 1.25x
+        assert_eq!(durations, [400, 400, 400, 700, 700, 600, 250]);
+    }
+
     #[test]
     fn test_adjust_wpm() {
         let tokens = vec![create_test_token("test", true)];
@@ -321,4 +463,225 @@ mod tests {
         let state = ReadingState::new_with_default_config(tokens, 450);
         assert_eq!(state.get_wpm(), 450);
     }
+
+    #[test]
+    fn test_seek_to_clamps_to_last_word() {
+        let tokens = vec![create_test_token("a", true), create_test_token("b", false)];
+        let mut state = ReadingState::new_with_default_config(tokens, 300);
+        state.seek_to(1);
+        assert_eq!(state.current_index, 1);
+        state.seek_to(10);
+        assert_eq!(state.current_index, 1);
+    }
+
+    #[test]
+    fn test_seek_fraction_snaps_to_sentence_start() {
+        let tokens = vec![
+            create_test_token("One", true),
+            create_test_token("two", false),
+            create_test_token("Three", true),
+            create_test_token("four", false),
+            create_test_token("five", false),
+            create_test_token("six", false),
+            create_test_token("Seven", true),
+            create_test_token("eight", false),
+            create_test_token("nine", false),
+            create_test_token("ten", false),
+        ];
+        let mut state = ReadingState::new_with_default_config(tokens, 300);
+
+        state.seek_fraction(0.5);
+        assert_eq!(state.current_index, 2);
+
+        state.seek_fraction(0.6);
+        assert_eq!(state.current_index, 6);
+
+        state.seek_fraction(0.0);
+        assert_eq!(state.current_index, 0);
+
+        state.seek_fraction(2.0);
+        assert_eq!(state.current_index, 6);
+    }
+
+    #[test]
+    fn test_replace_tokens_keeps_current_word() {
+        let words = |text: &str| -> Vec<Token> {
+            text.split(' ')
+                .map(|w| create_test_token(w, false))
+                .collect()
+        };
+        let mut state = ReadingState::new_with_default_config(words("h a b c d"), 300);
+        state.seek_to(3);
+
+        // More pages arrived
+        state.replace_tokens(words("h a b c d e f"));
+        assert_eq!((state.current_index, state.tokens.len()), (3, 7));
+
+        // A running header was removed once it was seen to repeat
+        state.replace_tokens(words("a b c d e f"));
+        assert_eq!(state.current_index, 2);
+
+        // The word is gone: stay at the same index
+        state.replace_tokens(words("x y z w"));
+        assert_eq!(state.current_index, 2);
+    }
+
+    #[test]
+    fn test_long_words_advance_by_fragment() {
+        let mut word = create_test_token("incomprehensibilities", false);
+        word.punctuation = vec!['.'];
+        let tokens = vec![word, create_test_token("Next", true)];
+        let mut state = ReadingState::new_with_default_config(tokens, 300);
+        assert_eq!(
+            state.current_fragment(),
+            Some(("incomprehensibilities", false))
+        );
+
+        state.set_hyphenator(Some(Hyphenator::default()));
+        // The whole word's 690 ms, with the period's pause at the end
+        assert_eq!(state.current_fragment(), Some(("incomprehen", true)));
+        assert_eq!(state.current_token_duration(), 115);
+
+        state.advance();
+        assert_eq!((state.current_index, state.fragment), (0, 1));
+        assert_eq!(state.current_fragment(), Some(("sibilities", false)));
+        assert_eq!(state.current_token_duration(), 575);
+
+        state.advance();
+        assert_eq!((state.current_index, state.fragment), (1, 0));
+        state.seek_to(0);
+        assert_eq!(state.fragment, 0);
+    }
+
+    #[test]
+    fn test_chunks_advance_together_and_stay_token_accurate() {
+        let tokens = tokenize_text("The cat sat on the mat. Then it rained");
+        let mut state = ReadingState::new_with_default_config(tokens, 300);
+        state.set_chunker(Some(Chunker::default()));
+
+        assert_eq!(state.current_chunk(), 0..3);
+        assert_eq!(state.current_duration(), 600);
+        state.advance();
+        assert_eq!(state.current_index, 3);
+        assert_eq!(state.current_chunk(), 3..6);
+        // Two plain words and one before a full stop
+        assert_eq!(state.current_duration(), 200 + 200 + 600);
+
+        // Navigation lands on words, and chunks start from there
+        state.seek_to(4);
+        assert_eq!(state.current_chunk(), 4..6);
+        assert!(state.jump_to_next_sentence());
+        assert_eq!(state.current_index, 6);
+        assert!(state.jump_to_previous_sentence());
+        assert_eq!(state.current_chunk(), 0..3);
+
+        state.set_chunker(None);
+        assert_eq!(state.current_chunk(), 0..1);
+    }
+
+    #[test]
+    fn test_frequency_pacing_keeps_the_average_wpm() {
+        let text = "The committee met in 1987 to discuss the extraordinarily \
+                    complicated proposal from Wilhelmina and the city";
+        let config = TimingConfig {
+            pacing: PacingModel::Frequency,
+            ..TimingConfig::default()
+        };
+        let mut state = ReadingState::new(tokenize_text(text), 300, config);
+
+        let mut durations = Vec::new();
+        for _ in 0..state.tokens.len() {
+            durations.push(state.current_token_duration());
+            state.advance();
+        }
+        let [the, committee, _, _, year, ..] = durations[..] else {
+            panic!("{:?}", durations);
+        };
+        assert!(
+            the < 200 && committee > 200 && year > 200,
+            "{:?}",
+            durations
+        );
+        assert!(
+            durations[12] > durations[13],
+            "proper noun slower than a common word"
+        );
+
+        let average = durations.iter().sum::<u64>() as f64 / durations.len() as f64;
+        assert!((average - 200.0).abs() < 2.0, "{}", average);
+    }
+
+    #[test]
+    fn test_split_words_keep_the_average_wpm() {
+        let text = "Counterrevolutionaries questioned the internationalization \
+                    of telecommunications and the characteristically \
+                    uncompromising photojournalism of the era";
+        let config = TimingConfig {
+            pacing: PacingModel::Frequency,
+            ..TimingConfig::default()
+        };
+        let mut state = ReadingState::new(tokenize_text(text), 300, config);
+        state.set_hyphenator(Some(Hyphenator::default()));
+
+        let words = state.tokens.len();
+        let mut total = 0;
+        let mut shown = 0;
+        loop {
+            total += state.current_token_duration();
+            shown += 1;
+            let before = (state.current_index, state.fragment);
+            state.advance();
+            if (state.current_index, state.fragment) == before {
+                break;
+            }
+        }
+        assert!(shown > words, "some words are split");
+        let average = total as f64 / words as f64;
+        assert!((average - 200.0).abs() < 2.0, "{}", average);
+    }
+
+    #[test]
+    fn test_frequency_table_is_swappable() {
+        let durations = |table| {
+            let config = TimingConfig {
+                pacing: PacingModel::Frequency,
+                frequency_table: table,
+                ..TimingConfig::default()
+            };
+            let mut state = ReadingState::new(tokenize_text("zebra the"), 300, config);
+            let first = state.current_token_duration();
+            state.advance();
+            (first, state.current_token_duration())
+        };
+        let (zebra, the) = durations(FrequencyTable::english());
+        assert!(zebra > the);
+        // A table where "zebra" is the most common word and "the" unknown
+        let (zebra, the) = durations(FrequencyTable::from_ranked(["zebra"]));
+        assert!(zebra < the);
+    }
+
+    #[test]
+    fn test_strategies_from_config_and_custom() {
+        let tokens = || tokenize_text("Stop. Go on");
+        let config = TimingConfig {
+            pacing: PacingModel::Constant,
+            ..TimingConfig::default()
+        };
+        let state = ReadingState::new(tokens(), 300, config);
+        assert_eq!(state.current_token_duration(), 200);
+
+        /// Longer for each word further into the document.
+        #[derive(Debug)]
+        struct Ramp;
+        impl TimingStrategy for Ramp {
+            fn duration_ms(&self, _token: TokenRef<'_>, context: &TimingContext<'_>) -> u64 {
+                100 * (context.index as u64 + 1)
+            }
+        }
+        let mut state = ReadingState::new_with_default_config(tokens(), 300);
+        assert_eq!(state.current_token_duration(), 600);
+        state.set_strategy(Box::new(Ramp));
+        state.advance();
+        assert_eq!(state.current_token_duration(), 200);
+    }
 }
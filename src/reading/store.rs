@@ -0,0 +1,375 @@
+//! Compact storage for the words being read
+//!
+//! A [`Token`] owns its text, two punctuation vectors and its note, which
+//! costs over a hundred bytes per word before the text itself. [`TokenStore`]
+//! keeps every word with its punctuation in one shared text buffer addressed
+//! by end offsets, packs the punctuation lengths and the sentence-start flag
+//! into two bytes, and keeps notes in a sparse side table. A million-word
+//! book takes about 11 MB.
+//!
+//! Words are read through [`TokenRef`], a borrowed view, so rendering a
+//! frame never copies the document. Loaders fill a store through
+//! [`TokenStoreBuilder`], so a whole document never exists as `Token`s.
+
+use super::timing::TokenSink;
+use super::token::Token;
+use std::collections::VecDeque;
+use std::ops::Range;
+use thiserror::Error;
+
+/// Bytes of trailing punctuation kept per word; further characters are
+/// dropped.
+pub const MAX_PUNCTUATION: usize = 0xff;
+
+/// Bytes of leading punctuation kept per word.
+pub const MAX_LEADING: usize = 0x7f;
+
+/// Flag bits holding the trailing punctuation length, in bytes.
+const TRAILING_BITS: u16 = 0xff;
+
+/// Shift of the leading punctuation length, in bytes.
+const LEADING_SHIFT: u16 = 8;
+
+/// Flag bit marking a sentence start, above the punctuation lengths.
+const SENTENCE_START: u16 = 1 << 15;
+
+/// Words a [`TokenStoreBuilder`] keeps open for changes before packing them.
+const OPEN_WORDS: usize = 64;
+
+/// The document's text does not fit the store's 32-bit offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("text exceeds 4 GiB")]
+pub struct StoreFull;
+
+/// Punctuation before or after a word, borrowed from the store's text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Punctuation<'a>(&'a str);
+
+impl<'a> Punctuation<'a> {
+    pub fn is_empty(self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(self, c: char) -> bool {
+        self.0.contains(c)
+    }
+
+    /// The characters in order.
+    pub fn chars(self) -> std::str::Chars<'a> {
+        self.0.chars()
+    }
+
+    pub fn as_str(self) -> &'a str {
+        self.0
+    }
+
+    /// Just the newline of a line-break token.
+    pub fn is_newline(self) -> bool {
+        self.0 == "\n"
+    }
+}
+
+/// A word borrowed from a [`TokenStore`]; fields mirror [`Token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenRef<'a> {
+    pub text: &'a str,
+    pub leading: Punctuation<'a>,
+    pub punctuation: Punctuation<'a>,
+    pub is_sentence_start: bool,
+    pub note: Option<&'a str>,
+}
+
+impl TokenRef<'_> {
+    /// An owned copy, e.g. for tests comparing against tokenizer output.
+    pub fn to_token(self) -> Token {
+        Token {
+            text: self.text.to_string(),
+            leading: self.leading.chars().collect(),
+            punctuation: self.punctuation.chars().collect(),
+            is_sentence_start: self.is_sentence_start,
+            note: self.note.map(str::to_string),
+        }
+    }
+}
+
+/// The words of a document in one compact buffer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenStore {
+    /// Each word with its leading and trailing punctuation, back to back.
+    text: String,
+    /// Byte offset in `text` where each word ends; it starts where the
+    /// previous one ended.
+    ends: Vec<u32>,
+    /// Punctuation lengths and [`SENTENCE_START`] per word.
+    flags: Vec<u16>,
+    /// Notes by word index, sorted.
+    notes: Vec<(u32, Box<str>)>,
+}
+
+impl TokenStore {
+    pub const fn new() -> Self {
+        Self {
+            text: String::new(),
+            ends: Vec::new(),
+            flags: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Appends a word; fails, storing nothing, if the text would pass the
+    /// 4 GiB its offsets can address.
+    pub fn push(&mut self, token: Token) -> Result<(), StoreFull> {
+        let index = self.ends.len() as u32;
+        let start = self.text.len();
+        let leading = push_chars(&mut self.text, &token.leading, MAX_LEADING);
+        self.text.push_str(&token.text);
+        let trailing = push_chars(&mut self.text, &token.punctuation, MAX_PUNCTUATION);
+        let Ok(end) = u32::try_from(self.text.len()) else {
+            self.text.truncate(start);
+            return Err(StoreFull);
+        };
+        self.ends.push(end);
+
+        let mut flags = (leading << LEADING_SHIFT) as u16 | trailing as u16;
+        if token.is_sentence_start {
+            flags |= SENTENCE_START;
+        }
+        self.flags.push(flags);
+
+        if let Some(note) = token.note {
+            self.notes.push((index, note.into_boxed_str()));
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<TokenRef<'_>> {
+        let end = *self.ends.get(index)? as usize;
+        let start = match index {
+            0 => 0,
+            _ => self.ends[index - 1] as usize,
+        };
+        let flags = self.flags[index];
+        let note = self
+            .notes
+            .binary_search_by_key(&(index as u32), |(i, _)| *i)
+            .ok()
+            .map(|found| &*self.notes[found].1);
+
+        let leading = ((flags & !SENTENCE_START) >> LEADING_SHIFT) as usize;
+        let trailing = (flags & TRAILING_BITS) as usize;
+        let word = &self.text[start..end];
+        Some(TokenRef {
+            text: &word[leading..word.len() - trailing],
+            leading: Punctuation(&word[..leading]),
+            punctuation: Punctuation(&word[word.len() - trailing..]),
+            is_sentence_start: flags & SENTENCE_START != 0,
+            note,
+        })
+    }
+
+    pub fn is_sentence_start(&self, index: usize) -> bool {
+        self.flags
+            .get(index)
+            .is_some_and(|flags| flags & SENTENCE_START != 0)
+    }
+
+    /// The words in `range`, clamped to the store.
+    pub fn range(
+        &self,
+        range: Range<usize>,
+    ) -> impl DoubleEndedIterator<Item = TokenRef<'_>> + ExactSizeIterator {
+        let end = range.end.min(self.len());
+        let start = range.start.min(end);
+        (start..end).map(move |i| self.get(i).expect("index within store"))
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = TokenRef<'_>> + ExactSizeIterator {
+        self.range(0..self.len())
+    }
+
+    /// Heap bytes held, for checking the footprint of large documents.
+    pub fn heap_size(&self) -> usize {
+        self.text.capacity()
+            + self.ends.capacity() * std::mem::size_of::<u32>()
+            + self.flags.capacity() * std::mem::size_of::<u16>()
+            + self.notes.capacity() * std::mem::size_of::<(u32, Box<str>)>()
+            + self.notes.iter().map(|(_, note)| note.len()).sum::<usize>()
+    }
+}
+
+/// Appends `chars` to `text`, stopping before `limit` bytes; returns the
+/// bytes written.
+fn push_chars(text: &mut String, chars: &[char], limit: usize) -> usize {
+    let before = text.len();
+    for &c in chars {
+        if text.len() - before + c.len_utf8() > limit {
+            break;
+        }
+        text.push(c);
+    }
+    text.len() - before
+}
+
+/// Packs words into a [`TokenStore`] as a loader produces them.
+///
+/// The last [`OPEN_WORDS`] words stay open, so the tokenizer and note
+/// attachment can still change them through [`TokenSink`].
+#[derive(Debug, Default)]
+pub struct TokenStoreBuilder {
+    store: TokenStore,
+    open: VecDeque<Token>,
+    full: bool,
+}
+
+impl TokenStoreBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Words pushed so far.
+    pub fn len(&self) -> usize {
+        self.store.len() + self.open.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Packs the open words and returns the store.
+    pub fn finish(mut self) -> Result<TokenStore, StoreFull> {
+        while let Some(token) = self.open.pop_front() {
+            self.pack(token);
+        }
+        if self.full {
+            Err(StoreFull)
+        } else {
+            Ok(self.store)
+        }
+    }
+
+    fn pack(&mut self, token: Token) {
+        if !self.full {
+            self.full = self.store.push(token).is_err();
+        }
+    }
+}
+
+impl TokenSink for TokenStoreBuilder {
+    fn push(&mut self, token: Token) {
+        self.open.push_back(token);
+        if self.open.len() > OPEN_WORDS {
+            let token = self.open.pop_front().expect("open words");
+            self.pack(token);
+        }
+    }
+
+    fn last(&self) -> Option<&Token> {
+        self.open.back()
+    }
+
+    fn last_word_mut(&mut self) -> Option<&mut Token> {
+        self.open.iter_mut().rev().find(|token| !token.is_newline())
+    }
+
+    fn last_mut(&mut self) -> Option<&mut Token> {
+        self.open.back_mut()
+    }
+}
+
+/// Panics if the words hold over 4 GiB of text; loaders use
+/// [`TokenStoreBuilder`], which reports that as [`StoreFull`].
+impl From<Vec<Token>> for TokenStore {
+    fn from(tokens: Vec<Token>) -> Self {
+        let mut store = Self::new();
+        store.text.reserve(
+            tokens
+                .iter()
+                .map(|t| t.leading.len() + t.text.len() + t.punctuation.len())
+                .sum(),
+        );
+        store.ends.reserve_exact(tokens.len());
+        store.flags.reserve_exact(tokens.len());
+        for token in tokens {
+            store.push(token).expect("token text fits in 4 GiB");
+        }
+        store
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reading::timing::tokenize_into;
+    use crate::reading::tokenize_text;
+
+    #[test]
+    fn test_round_trips_tokens() {
+        let mut tokens = tokenize_text("Hello, world!?\nIt is... done.");
+        tokens[1].note = Some("A note.".to_string());
+        let store = TokenStore::from(tokens.clone());
+
+        assert_eq!(store.len(), tokens.len());
+        let restored: Vec<Token> = store.iter().map(TokenRef::to_token).collect();
+        assert_eq!(restored, tokens);
+        assert_eq!(store.get(1).unwrap().note, Some("A note."));
+        assert_eq!(store.get(tokens.len()), None);
+        assert!(store.get(2).unwrap().punctuation.is_newline());
+    }
+
+    #[test]
+    fn test_leading_and_trailing_punctuation() {
+        let tokens = tokenize_text("«Quoi?» — (see: “this”…)");
+        let store = TokenStore::from(tokens.clone());
+        let restored: Vec<Token> = store.iter().map(TokenRef::to_token).collect();
+        assert_eq!(restored, tokens);
+
+        let quoi = store.get(0).unwrap();
+        assert_eq!(
+            (quoi.leading.as_str(), quoi.text, quoi.punctuation.as_str()),
+            ("«", "Quoi", "?»—")
+        );
+        assert!(store.get(2).unwrap().punctuation.contains('…'));
+        assert!(Punctuation::default().is_empty());
+    }
+
+    #[test]
+    fn test_builder_matches_tokenizer() {
+        let text = "Words come in. ".repeat(40) + "Then a break,\n\n— and “quoted” words…";
+        let mut builder = TokenStoreBuilder::new();
+        tokenize_into(&text, None, &mut builder);
+        assert_eq!(builder.len(), tokenize_text(&text).len());
+
+        let store = builder.finish().unwrap();
+        assert!(store.len() > OPEN_WORDS);
+        assert_eq!(store, TokenStore::from(tokenize_text(&text)));
+    }
+
+    #[test]
+    fn test_million_words_stay_small() {
+        let mut store = TokenStore::new();
+        for i in 0..1_000_000 {
+            store
+                .push(Token {
+                    punctuation: if i % 12 == 11 { vec!['.'] } else { vec![] },
+                    is_sentence_start: i % 12 == 0,
+                    ..Token::new("reading")
+                })
+                .unwrap();
+        }
+        assert!(
+            store.heap_size() < 32 * 1024 * 1024,
+            "{}",
+            store.heap_size()
+        );
+        assert!(store.is_sentence_start(12));
+        assert_eq!(store.range(999_998..2_000_000).len(), 2);
+    }
+}
@@ -0,0 +1,233 @@
+//! Pluggable word timing
+//!
+//! [`ReadingState`](super::ReadingState) asks a [`TimingStrategy`] how long
+//! to show each word. The built-in strategies are picked with
+//! [`TimingConfig::pacing`]; experiments can pass their own to
+//! [`ReadingState::set_strategy`](super::ReadingState::set_strategy).
+//!
+//! A strategy sees the word and a [`TimingContext`]: the reading speed, the
+//! timing configuration and the whole document, so it can look at the
+//! neighbouring words. [`TimingStrategy::prepare`] runs whenever the
+//! document changes, for strategies that keep per-document statistics.
+
+use super::pacing::{self, PacingModel};
+use super::store::{Punctuation, TokenRef, TokenStore};
+use super::wpm_to_milliseconds;
+use crate::engine::config::TimingConfig;
+use std::fmt::Debug;
+
+/// Words sampled, evenly spread, to average over a document.
+pub const SAMPLE_SIZE: usize = 20_000;
+
+/// What a strategy knows besides the word itself.
+#[derive(Debug, Clone, Copy)]
+pub struct TimingContext<'a> {
+    pub wpm: u32,
+    pub config: &'a TimingConfig,
+    /// The document being read.
+    pub tokens: &'a TokenStore,
+    /// Index of the word being timed in `tokens`.
+    pub index: usize,
+}
+
+impl TimingContext<'_> {
+    /// Milliseconds per word at the reading speed.
+    pub fn base_delay_ms(&self) -> f64 {
+        wpm_to_milliseconds(self.wpm) as f64
+    }
+}
+
+/// Decides how long each word is shown.
+pub trait TimingStrategy: Debug {
+    /// Milliseconds to show `token`.
+    fn duration_ms(&self, token: TokenRef<'_>, context: &TimingContext<'_>) -> u64;
+
+    /// Called with each new or extended document before it is timed.
+    fn prepare(&mut self, _tokens: &TokenStore, _config: &TimingConfig) {}
+}
+
+impl PacingModel {
+    /// The built-in strategy for this model.
+    pub fn strategy(self) -> Box<dyn TimingStrategy> {
+        match self {
+            PacingModel::Length => Box::new(WeightedStrategy),
+            PacingModel::Frequency => Box::new(FrequencyStrategy::default()),
+            PacingModel::Syllable => Box::new(SyllableStrategy::default()),
+            PacingModel::Constant => Box::new(ConstantStrategy),
+        }
+    }
+}
+
+/// The default: punctuation pauses and a penalty for long words
+/// (PRD Section 3.2).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WeightedStrategy;
+
+impl TimingStrategy for WeightedStrategy {
+    fn duration_ms(&self, token: TokenRef<'_>, context: &TimingContext<'_>) -> u64 {
+        let config = context.config;
+        let length_penalty = if token.text.chars().count() > config.long_word_threshold {
+            config.long_word_penalty
+        } else {
+            1.0
+        };
+        let multiplier = punctuation_multiplier(token.punctuation, config);
+        (context.base_delay_ms() * multiplier * length_penalty) as u64
+    }
+}
+
+/// Punctuation pauses, with words scaled by [`pacing::complexity`] against
+/// [`TimingConfig::frequency_table`] and divided by the document average.
+#[derive(Debug, Clone, Copy)]
+pub struct FrequencyStrategy {
+    scale: f64,
+}
+
+impl Default for FrequencyStrategy {
+    fn default() -> Self {
+        Self { scale: 1.0 }
+    }
+}
+
+impl TimingStrategy for FrequencyStrategy {
+    fn duration_ms(&self, token: TokenRef<'_>, context: &TimingContext<'_>) -> u64 {
+        let config = context.config;
+        let complexity = pacing::complexity(token, &config.frequency_table) * self.scale;
+        let multiplier = punctuation_multiplier(token.punctuation, config);
+        (context.base_delay_ms() * multiplier * complexity) as u64
+    }
+
+    fn prepare(&mut self, tokens: &TokenStore, config: &TimingConfig) {
+        let average = sample_average(tokens, |token| {
+            pacing::complexity(token, &config.frequency_table)
+        });
+        self.scale = 1.0 / average;
+    }
+}
+
+/// Punctuation pauses, with words shown in proportion to their syllables
+/// relative to the document average.
+#[derive(Debug, Clone, Copy)]
+pub struct SyllableStrategy {
+    average: f64,
+}
+
+impl Default for SyllableStrategy {
+    fn default() -> Self {
+        Self { average: 1.0 }
+    }
+}
+
+impl TimingStrategy for SyllableStrategy {
+    fn duration_ms(&self, token: TokenRef<'_>, context: &TimingContext<'_>) -> u64 {
+        let syllables = pacing::syllables(token.text) as f64;
+        let multiplier = punctuation_multiplier(token.punctuation, context.config);
+        (context.base_delay_ms() * multiplier * syllables / self.average) as u64
+    }
+
+    fn prepare(&mut self, tokens: &TokenStore, _config: &TimingConfig) {
+        self.average = sample_average(tokens, |token| pacing::syllables(token.text) as f64);
+    }
+}
+
+/// Every word for the same time, with no pauses for punctuation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConstantStrategy;
+
+impl TimingStrategy for ConstantStrategy {
+    fn duration_ms(&self, _token: TokenRef<'_>, context: &TimingContext<'_>) -> u64 {
+        context.base_delay_ms() as u64
+    }
+}
+
+/// PRD Section 3.2: Punctuation Multipliers with max stacking rule
+pub fn punctuation_multiplier(punctuation: Punctuation<'_>, config: &TimingConfig) -> f64 {
+    let punctuation = punctuation.as_str();
+    let is_ellipsis = punctuation.contains('…') || punctuation.contains("..");
+    punctuation
+        .chars()
+        .map(|p| match p {
+            '.' if is_ellipsis => config.ellipsis_multiplier,
+            '…' => config.ellipsis_multiplier,
+            '.' => config.period_multiplier,
+            '?' => config.question_multiplier,
+            '!' => config.exclamation_multiplier,
+            ',' => config.comma_multiplier,
+            ';' => config.semicolon_multiplier,
+            ':' => config.colon_multiplier,
+            '—' | '–' => config.dash_multiplier,
+            '"' | '\'' | '”' | '’' | '»' | '›' | ')' | ']' | '}' => {
+                config.closing_quote_multiplier
+            }
+            '\n' => config.newline_multiplier,
+            _ => 1.0,
+        })
+        .fold(1.0, f64::max)
+}
+
+/// Average of `measure` over up to [`SAMPLE_SIZE`] evenly spread words;
+/// 1 for an empty document.
+pub fn sample_average(tokens: &TokenStore, measure: impl Fn(TokenRef<'_>) -> f64) -> f64 {
+    if tokens.is_empty() {
+        return 1.0;
+    }
+    let step = tokens.len().div_ceil(SAMPLE_SIZE);
+    let (sum, count) = tokens
+        .iter()
+        .step_by(step)
+        .map(measure)
+        .fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    if sum > 0.0 {
+        sum / count as f64
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reading::tokenize_text;
+
+    fn durations(strategy: &mut dyn TimingStrategy, text: &str) -> Vec<u64> {
+        let tokens = TokenStore::from(tokenize_text(text));
+        let config = TimingConfig::default();
+        strategy.prepare(&tokens, &config);
+        tokens
+            .iter()
+            .enumerate()
+            .map(|(index, token)| {
+                let context = TimingContext {
+                    wpm: 300,
+                    config: &config,
+                    tokens: &tokens,
+                    index,
+                };
+                strategy.duration_ms(token, &context)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_built_in_strategies() {
+        let text = "An extraordinarily unusual idea, then silence.";
+        assert_eq!(
+            durations(&mut WeightedStrategy, text),
+            [200, 229, 200, 300, 200, 600]
+        );
+        assert_eq!(
+            durations(&mut ConstantStrategy, text),
+            [200, 200, 200, 200, 200, 200]
+        );
+
+        let syllables = durations(&mut SyllableStrategy::default(), text);
+        assert!(syllables[0] < syllables[1], "{:?}", syllables);
+        let plain: u64 = durations(
+            &mut SyllableStrategy::default(),
+            "an extraordinarily unusual idea",
+        )
+        .iter()
+        .sum();
+        assert!((plain as i64 - 800).abs() <= 4, "{}", plain);
+    }
+}
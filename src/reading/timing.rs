@@ -1,34 +1,98 @@
+use super::sentence::{sentence_starts, Abbreviations};
 use crate::engine::Token;
 
-fn extract_punctuation(word: &str) -> (String, Vec<char>) {
-    if word.is_empty() {
-        return (String::new(), Vec::new());
+/// Opening quotes, brackets and inverted marks peeled off the front of a
+/// word.
+const LEADING: &[char] = &[
+    '"', '\'', '“', '‘', '„', '‚', '«', '‹', '(', '[', '{', '¿', '¡',
+];
+
+/// Punctuation peeled off the end of a word: terminators, commas, colons,
+/// semicolons, dashes, ellipses and closing quotes and brackets.
+const TRAILING: &[char] = &[
+    '.', '?', '!', ',', ';', ':', '…', '—', '–', '"', '\'', '”', '’', '»', '›', ')', ']', '}',
+];
+
+/// Apostrophes, which are also single quotes.
+const APOSTROPHES: &[char] = &['\'', '‘', '’'];
+
+/// Words shortened at the front, kept with their apostrophe as in "'tis".
+const ELISIONS: &[&str] = &[
+    "tis", "twas", "twere", "em", "cause", "cos", "til", "n", "bout",
+];
+
+/// Splits a word into leading punctuation, the bare word and trailing
+/// punctuation. A word of punctuation only comes back as the bare word.
+///
+/// Apostrophes marking a possessive or elision stay part of the word: after
+/// a letter ("dogs'") unless the word opened with a single quote, and before
+/// a digit or a short elision ("'90s", "'tis").
+fn extract_punctuation(word: &str) -> (Vec<char>, String, Vec<char>) {
+    let bare = word.trim_start_matches(LEADING).trim_end_matches(TRAILING);
+    if bare.is_empty() {
+        return (Vec::new(), word.to_string(), Vec::new());
     }
 
-    let mut chars: Vec<char> = word.chars().collect();
-    let mut punctuation_chars = Vec::new();
-
-    // Collect all trailing punctuation characters
-    while let Some(&last_char) = chars.last() {
-        if is_sentence_terminator(last_char) || is_comma(last_char) {
-            punctuation_chars.push(chars.pop().unwrap());
-        } else {
-            break;
+    let mut start = bare.as_ptr() as usize - word.as_ptr() as usize;
+    let mut end = start + bare.len();
+    let leading_quote = word[..start]
+        .chars()
+        .next_back()
+        .filter(|c| APOSTROPHES.contains(c));
+    if let Some(quote) = leading_quote {
+        let elided = bare
+            .split(|c: char| !c.is_alphanumeric())
+            .next()
+            .is_some_and(|prefix| ELISIONS.contains(&prefix.to_lowercase().as_str()));
+        if bare.starts_with(|c: char| c.is_ascii_digit()) || elided {
+            start -= quote.len_utf8();
         }
     }
-
-    // Reverse to maintain original order
-    punctuation_chars.reverse();
-
-    (chars.into_iter().collect(), punctuation_chars)
+    let opens_quote = word[..start].contains(['\'', '‘']);
+    if let Some(quote) = word[end..]
+        .chars()
+        .next()
+        .filter(|c| APOSTROPHES.contains(c))
+    {
+        if !opens_quote && bare.ends_with(char::is_alphabetic) {
+            end += quote.len_utf8();
+        }
+    }
+    (
+        word[..start].chars().collect(),
+        word[start..end].to_string(),
+        word[end..].chars().collect(),
+    )
 }
 
-fn is_sentence_terminator(c: char) -> bool {
-    c == '.' || c == '?' || c == '!'
+/// Splits a word after an em-dash or ellipsis joining two words, as in
+/// "this—that" or "wait...what".
+fn split_embedded(word: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut chars = word.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let joins = match c {
+            '—' | '…' => true,
+            '.' => word[..i].ends_with('.') || word[i + 1..].starts_with('.'),
+            _ => false,
+        };
+        let next_is_word = chars
+            .peek()
+            .is_some_and(|&(_, next)| next.is_alphanumeric());
+        let after = i + c.len_utf8();
+        if joins && next_is_word && word[start..i].chars().any(char::is_alphanumeric) {
+            pieces.push(&word[start..after]);
+            start = after;
+        }
+    }
+    pieces.push(&word[start..]);
+    pieces
 }
 
-fn is_comma(c: char) -> bool {
-    c == ','
+fn is_punctuation_only(word: &str) -> bool {
+    word.chars()
+        .all(|c| LEADING.contains(&c) || TRAILING.contains(&c))
 }
 
 pub fn wpm_to_milliseconds(wpm: u32) -> u64 {
@@ -36,76 +100,121 @@ pub fn wpm_to_milliseconds(wpm: u32) -> u64 {
 }
 
 /// Detects if current word starts a new sentence based on previous token.
-/// MVP: Period/question/exclamation followed by capital letter A-Z, or newline.
-/// First token always returns true (PRD Section 3.3 requirement).
+/// A newline or a sentence end (see `reading::sentence`) before the word
+/// starts one; English abbreviations apply. First token always returns true
+/// (PRD Section 3.3 requirement).
 pub fn detect_sentence_boundary(prev_token: Option<&Token>, current_word: &str) -> bool {
-    if prev_token.is_none() {
+    let Some(prev) = prev_token else {
+        return true;
+    };
+    if prev.punctuation.contains(&'\n') {
         return true;
     }
+    if current_word.is_empty() {
+        return false;
+    }
+
+    let prev_word: String = prev
+        .leading
+        .iter()
+        .copied()
+        .chain(prev.text.chars())
+        .chain(prev.punctuation.iter().copied())
+        .collect();
+    let pair = format!("{} {}", prev_word, current_word);
+    sentence_starts(&pair, &Abbreviations::for_language(None)).contains(&(prev_word.len() + 1))
+}
 
-    let prev = prev_token.unwrap();
-    let has_newline = prev.punctuation.contains(&'\n');
+/// Where the tokenizer writes words: a `Vec<Token>`, or a
+/// [`TokenStoreBuilder`](super::store::TokenStoreBuilder) for a document.
+pub trait TokenSink {
+    fn push(&mut self, token: Token);
 
-    if has_newline {
-        return true;
+    /// The token pushed last.
+    fn last(&self) -> Option<&Token>;
+
+    fn last_mut(&mut self) -> Option<&mut Token>;
+
+    /// The last token that is not a line break, if it can still change.
+    fn last_word_mut(&mut self) -> Option<&mut Token>;
+}
+
+impl TokenSink for Vec<Token> {
+    fn push(&mut self, token: Token) {
+        Vec::push(self, token);
     }
 
-    let has_terminator = prev
-        .punctuation
-        .iter()
-        .any(|&p| p == '.' || p == '?' || p == '!');
+    fn last(&self) -> Option<&Token> {
+        self.as_slice().last()
+    }
 
-    if !has_terminator {
-        return false;
+    fn last_mut(&mut self) -> Option<&mut Token> {
+        self.as_mut_slice().last_mut()
     }
 
-    current_word
-        .chars()
-        .next()
-        .map(|c| c.is_ascii_uppercase())
-        .unwrap_or(false)
+    fn last_word_mut(&mut self) -> Option<&mut Token> {
+        self.iter_mut().rev().find(|token| !token.is_newline())
+    }
 }
 
 /// Tokenizes text line-by-line; PRD Section 3.2.
 /// Note: duration is calculated dynamically in ReadingState, not stored in Token.
 pub fn tokenize_text(text: &str) -> Vec<Token> {
+    tokenize_text_in(text, None)
+}
+
+/// Like [`tokenize_text`], with the abbreviations of the document language
+/// (a tag such as `de`; English if `None` or unknown).
+pub fn tokenize_text_in(text: &str, language: Option<&str>) -> Vec<Token> {
     let mut tokens = Vec::new();
+    tokenize_into(text, language, &mut tokens);
+    tokens
+}
 
-    for line in text.lines() {
-        // Process words in current line
-        for word in line.split_whitespace() {
-            if !word.is_empty() {
-                let (text, punctuation) = extract_punctuation(word);
-                let prev_token = tokens.last().cloned();
-                let is_start = detect_sentence_boundary(prev_token.as_ref(), &word);
-
-                tokens.push(Token {
-                    text,
-                    punctuation,
-                    is_sentence_start: is_start,
-                });
-            }
+/// Like [`tokenize_text_in`], writing the words to `sink`.
+pub fn tokenize_into(text: &str, language: Option<&str>, sink: &mut impl TokenSink) {
+    let abbreviations = Abbreviations::for_language(language);
+
+    for (index, line) in text.lines().enumerate() {
+        // A newline token between lines; none after the last
+        if index > 0 {
+            let is_start = detect_sentence_boundary(sink.last(), "");
+            sink.push(Token {
+                punctuation: vec!['\n'],
+                is_sentence_start: is_start,
+                ..Token::default()
+            });
         }
 
-        // Create newline token after each line (except if line was empty/whitespace only)
-        let prev_token = tokens.last().cloned();
-        let is_start = detect_sentence_boundary(prev_token.as_ref(), "");
-        tokens.push(Token {
-            text: String::new(),
-            punctuation: vec!['\n'],
-            is_sentence_start: is_start,
-        });
-    }
+        // Each line starts a sentence; within it, words at a sentence
+        // boundary do
+        let starts = sentence_starts(line, &abbreviations);
+        let mut line_has_word = false;
+        for word in line.split_whitespace().flat_map(split_embedded) {
+            // A free-standing dash or quote belongs to the word before it
+            if line_has_word && is_punctuation_only(word) {
+                if let Some(prev) = sink.last_mut() {
+                    prev.punctuation.extend(word.chars());
+                }
+                continue;
+            }
 
-    // Remove trailing newline token if it exists (last line doesn't need newline after it)
-    if tokens
-        .last()
-        .map_or(false, |t| t.punctuation == vec!['\n'] && t.text.is_empty())
-    {
-        tokens.pop();
+            let (leading, text, punctuation) = extract_punctuation(word);
+            let offset = word.as_ptr() as usize - line.as_ptr() as usize;
+            let is_start = sink
+                .last()
+                .is_none_or(|prev| prev.punctuation.contains(&'\n'))
+                || starts.binary_search(&offset).is_ok();
+
+            sink.push(Token {
+                leading,
+                punctuation,
+                is_sentence_start: is_start,
+                ..Token::new(text)
+            });
+            line_has_word = true;
+        }
     }
-
-    tokens
 }
 
 #[cfg(test)]
@@ -248,6 +357,55 @@ mod tests {
         assert_eq!(tokens[0].punctuation, vec![]);
     }
 
+    #[test]
+    fn test_tokenize_leading_and_trailing_punctuation() {
+        let tokens = tokenize_text("(“Hello,” she said: ¿qué?)");
+        assert_eq!(tokens[0].leading, vec!['(', '“']);
+        assert_eq!(tokens[0].text, "Hello");
+        assert_eq!(tokens[0].punctuation, vec![',', '”']);
+        assert_eq!(tokens[2].punctuation, vec![':']);
+        assert_eq!(tokens[3].leading, vec!['¿']);
+        assert_eq!(tokens[3].text, "qué");
+        assert_eq!(tokens[3].punctuation, vec!['?', ')']);
+    }
+
+    #[test]
+    fn test_tokenize_embedded_dashes_and_ellipses() {
+        let tokens = tokenize_text("this—that wait...what well-known — yes");
+        let words: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(words, ["this", "that", "wait", "what", "well-known", "yes"]);
+        assert_eq!(tokens[0].punctuation, vec!['—']);
+        assert_eq!(tokens[2].punctuation, vec!['.', '.', '.']);
+        // A free-standing dash joins the word before it
+        assert_eq!(tokens[4].punctuation, vec!['—']);
+
+        // Punctuation alone is kept as a word
+        assert_eq!(tokenize_text("...")[0].text, "...");
+    }
+
+    #[test]
+    fn test_tokenize_keeps_possessive_and_elision_apostrophes() {
+        let words = |text: &str| -> Vec<String> {
+            tokenize_text(text).into_iter().map(|t| t.text).collect()
+        };
+        assert_eq!(words("the dogs' bowls"), ["the", "dogs'", "bowls"]);
+        assert_eq!(words("students’ work"), ["students’", "work"]);
+        assert_eq!(words("back in '90s"), ["back", "in", "'90s"]);
+        assert_eq!(words("'Tis rock 'n' roll"), ["'Tis", "rock", "'n'", "roll"]);
+
+        // The possessive takes no closing-quote pause
+        let tokens = tokenize_text("the dogs'.");
+        assert_eq!(tokens[1].text, "dogs'");
+        assert_eq!(tokens[1].punctuation, vec!['.']);
+
+        // Single quotes around a word are still quotes
+        let tokens = tokenize_text("'Go home' and ‘stay’");
+        assert_eq!(tokens[0].leading, vec!['\'']);
+        assert_eq!(tokens[0].text, "Go");
+        assert_eq!(tokens[3].text, "stay");
+        assert_eq!(tokens[3].punctuation, vec!['’']);
+    }
+
     // Speedy-ui3: Tokenization Update tests
 
     #[test]
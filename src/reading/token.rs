@@ -1,9 +1,30 @@
 /// Token struct for RSVP reading
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
 pub struct Token {
+    /// The bare word, without surrounding punctuation.
     pub text: String,
+    /// Opening quotes and brackets before the word (e.g., ['(', '“'] for "(“word").
+    pub leading: Vec<char>,
     /// Trailing punctuation characters (e.g., ['?', '!'] for "word?!") per PRD Section 3.2 max stacking rule.
+    /// Includes closing quotes and brackets, colons, semicolons, dashes and ellipses.
     pub punctuation: Vec<char>,
     /// Indicates if this token starts a new sentence (PRD Section 3.3).
     pub is_sentence_start: bool,
+    /// Footnote or endnote text referenced from this word, kept out of the reading stream.
+    pub note: Option<String>,
+}
+
+impl Token {
+    /// A bare word with no punctuation, not starting a sentence.
+    pub fn new(text: impl Into<String>) -> Self {
+        Token {
+            text: text.into(),
+            ..Token::default()
+        }
+    }
+
+    /// Returns true for the token standing for a line break.
+    pub fn is_newline(&self) -> bool {
+        self.text.is_empty() && self.punctuation == ['\n']
+    }
 }
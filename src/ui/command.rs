@@ -3,8 +3,13 @@
 //! Parses user input in Command mode, supporting:
 //! - `:q` or `:quit` → Quit command
 //! - `:h` or `:help` → Help command
+//! - `:mes` or `:messages` → List recent notifications
 //! - `@filename.pdf` or `@filename.epub` → Load file command
+//! - `@paper.pdf#p12-30` or `@book.epub#ch3` → Load a page or chapter range
+//! - `@papers/` or `@notes/*.md` → Load a reading queue (see `input::queue`)
 //! - `@@` → Load clipboard
+//! - `@@primary` → Load the primary selection (highlighted text)
+//! - `@@watch` or `@@watch primary` → Read each newly copied passage
 //!
 //! ## Migration from REPL
 //!
@@ -13,14 +18,18 @@
 //! command deck instead of a separate REPL loop.
 
 use crate::app::AppEvent;
+use crate::input::clipboard::Selection;
 
 /// Commands that can be parsed from command deck input
 #[derive(Debug, Clone, PartialEq)]
 pub enum Command {
     Quit,
     Help,
+    Messages,
     LoadFile(String),
     LoadClipboard,
+    LoadPrimarySelection,
+    WatchClipboard(Selection),
     Unknown(String),
 }
 
@@ -29,8 +38,16 @@ pub enum Command {
 /// Supports:
 /// - `:q` or `:quit` → Quit command
 /// - `:h` or `:help` → Help command
+/// - `:mes` or `:messages` → Messages command
 /// - `@filename.pdf` or `@filename.epub` → Load file command
+/// - `@paper.pdf#p12-30` or `@book.epub#ch3` → Load file command; the range
+///   fragment is split off by the loader (see `input::range`)
+/// - `@papers/` or `@**/chapter.pdf` → Load file command; App expands
+///   directories and glob patterns into a reading queue
 /// - `@@` → Load clipboard
+/// - `@@primary` → Load primary selection
+/// - `@@watch` / `@@watch primary` → Watch clipboard or primary selection;
+///   repeating it stops watching
 /// - Unknown command → Error message
 pub fn parse_command(input: &str) -> Command {
     let input = input.trim();
@@ -45,14 +62,22 @@ pub fn parse_command(input: &str) -> Command {
         match cmd {
             "q" | "quit" => Command::Quit,
             "h" | "help" => Command::Help,
+            "mes" | "messages" => Command::Messages,
             _ => Command::Unknown(input.to_string()),
         }
     } else if let Some(rest) = input.strip_prefix('@') {
         let filename = rest.trim();
-        if filename.is_empty() || filename == "@" {
-            Command::LoadClipboard
-        } else {
-            Command::LoadFile(filename.to_string())
+        let clipboard_mode = filename
+            .strip_prefix('@')
+            .map(|mode| mode.split_whitespace().collect::<Vec<_>>().join(" "));
+        match clipboard_mode.as_deref() {
+            Some("") => Command::LoadClipboard,
+            Some("primary") => Command::LoadPrimarySelection,
+            Some("watch") => Command::WatchClipboard(Selection::Clipboard),
+            Some("watch primary") => Command::WatchClipboard(Selection::Primary),
+            Some(_) => Command::Unknown(input.to_string()),
+            None if filename.is_empty() => Command::LoadClipboard,
+            None => Command::LoadFile(filename.to_string()),
         }
     } else {
         // Unknown command pattern
@@ -67,8 +92,11 @@ pub fn command_to_app_event(command: Command) -> AppEvent {
     match command {
         Command::Quit => AppEvent::Quit,
         Command::Help => AppEvent::Help,
+        Command::Messages => AppEvent::ShowMessages,
         Command::LoadFile(path) => AppEvent::LoadFile(path),
         Command::LoadClipboard => AppEvent::LoadClipboard,
+        Command::LoadPrimarySelection => AppEvent::LoadPrimarySelection,
+        Command::WatchClipboard(selection) => AppEvent::WatchClipboard(selection),
         Command::Unknown(input) => AppEvent::InvalidCommand(input),
     }
 }
@@ -89,6 +117,16 @@ mod tests {
         assert_eq!(parse_command(":help"), Command::Help);
     }
 
+    #[test]
+    fn test_parse_messages_variants() {
+        assert_eq!(parse_command(":messages"), Command::Messages);
+        assert_eq!(parse_command(":mes"), Command::Messages);
+        assert_eq!(
+            command_to_app_event(Command::Messages),
+            AppEvent::ShowMessages
+        );
+    }
+
     #[test]
     fn test_parse_load_file() {
         assert_eq!(
@@ -97,6 +135,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_load_file_with_range() {
+        assert_eq!(
+            parse_command("@paper.pdf#p12-30"),
+            Command::LoadFile("paper.pdf#p12-30".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_clipboard_modes() {
+        assert_eq!(parse_command("@@primary"), Command::LoadPrimarySelection);
+        assert_eq!(
+            parse_command("@@watch"),
+            Command::WatchClipboard(Selection::Clipboard)
+        );
+        assert_eq!(
+            parse_command("@@ watch  primary"),
+            Command::WatchClipboard(Selection::Primary)
+        );
+        assert_eq!(
+            parse_command("@@bogus"),
+            Command::Unknown("@@bogus".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_load_queue_pattern() {
+        assert_eq!(
+            parse_command("@ notes/*.md"),
+            Command::LoadFile("notes/*.md".to_string())
+        );
+        assert_eq!(
+            parse_command("@**/chapter.pdf"),
+            Command::LoadFile("**/chapter.pdf".to_string())
+        );
+    }
+
     #[test]
     fn test_parse_load_file_with_spaces() {
         assert_eq!(
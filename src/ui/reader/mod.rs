@@ -1,2 +1,2 @@
 pub mod component;
-pub mod view;
\ No newline at end of file
+pub mod view;
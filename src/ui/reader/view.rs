@@ -1,17 +1,42 @@
-use crate::engine::Token;
+use crate::app::mode::AppMode;
+use crate::app::notify::{Notification, Severity};
+use crate::app::RenderState;
+use crate::reading::chunk::chunk_anchor_position;
+use crate::reading::TokenStore;
 use crate::ui::theme::colors;
 use ratatui::{
-    layout::Alignment,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::Style,
     text::{Line, Span},
-    widgets::Paragraph,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
 };
 
+/// Column the anchor letter of a single word is drawn at.
+const WORD_ANCHOR_COLUMN: usize = 3;
+
+/// Column the anchor letter of a chunk is drawn at, leaving room for the
+/// words before it.
+const CHUNK_ANCHOR_COLUMN: usize = 8;
+
 pub fn render_word_display(word: &str, anchor_pos: usize) -> Paragraph<'static> {
+    render_anchored(word, anchor_pos, WORD_ANCHOR_COLUMN)
+}
+
+/// Renders the words of a chunk on one line, anchored on its longest word.
+pub fn render_chunk_display(words: &[&str]) -> Paragraph<'static> {
+    render_anchored(
+        &words.join(" "),
+        chunk_anchor_position(words),
+        CHUNK_ANCHOR_COLUMN,
+    )
+}
+
+fn render_anchored(word: &str, anchor_pos: usize, column: usize) -> Paragraph<'static> {
     let chars: Vec<char> = word.chars().collect();
     let _word_len = chars.len();
 
-    let left_padding = 3_i32.saturating_sub(anchor_pos as i32) as usize;
+    let left_padding = column.saturating_sub(anchor_pos);
 
     let mut spans = Vec::new();
     for _ in 0..left_padding {
@@ -56,21 +81,19 @@ pub fn render_progress_bar(progress: (usize, usize)) -> Line<'static> {
     Line::from(spans).alignment(Alignment::Center)
 }
 
-pub fn render_context_left(tokens: &[Token], current: usize, window: usize) -> Paragraph<'static> {
+pub fn render_context_left(
+    tokens: &TokenStore,
+    current: usize,
+    window: usize,
+) -> Paragraph<'static> {
     let start = if current > window {
         current - window
     } else {
         0
     };
-    let context_words: Vec<String> = tokens[start..current]
-        .iter()
-        .map(|t| {
-            let mut text = t.text.clone();
-            for &p in &t.punctuation {
-                text.push(p);
-            }
-            text
-        })
+    let context_words: Vec<String> = tokens
+        .range(start..current)
+        .map(|t| [t.leading.as_str(), t.text, t.punctuation.as_str()].concat())
         .collect();
 
     let text = context_words.join(" ");
@@ -82,7 +105,11 @@ pub fn render_context_left(tokens: &[Token], current: usize, window: usize) -> P
     )
 }
 
-pub fn render_context_right(tokens: &[Token], current: usize, window: usize) -> Paragraph<'static> {
+pub fn render_context_right(
+    tokens: &TokenStore,
+    current: usize,
+    window: usize,
+) -> Paragraph<'static> {
     if tokens.is_empty() || current >= tokens.len() {
         return Paragraph::new("").alignment(Alignment::Left).style(
             Style::default()
@@ -92,15 +119,9 @@ pub fn render_context_right(tokens: &[Token], current: usize, window: usize) ->
     }
 
     let end = std::cmp::min(current + window + 1, tokens.len());
-    let context_words: Vec<String> = tokens[current + 1..end]
-        .iter()
-        .map(|t| {
-            let mut text = t.text.clone();
-            for &p in &t.punctuation {
-                text.push(p);
-            }
-            text
-        })
+    let context_words: Vec<String> = tokens
+        .range(current + 1..end)
+        .map(|t| [t.leading.as_str(), t.text, t.punctuation.as_str()].concat())
         .collect();
 
     let text = context_words.join(" ");
@@ -118,19 +139,102 @@ pub fn render_gutter_placeholder() -> Paragraph<'static> {
         .style(Style::default().fg(colors::text()).bg(colors::background()))
 }
 
-pub fn render_placeholder() -> Paragraph<'static> {
-    let text = "Type @filename to load a file\nOr @@ to load from clipboard\n:q to quit";
-    Paragraph::new(text)
+/// Render the title card flashed before a queued document's first word.
+pub fn render_title_card(title: &str) -> Paragraph<'static> {
+    Paragraph::new(Line::from(Span::styled(
+        title.to_string(),
+        Style::default()
+            .fg(colors::anchor())
+            .add_modifier(ratatui::style::Modifier::BOLD),
+    )))
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true })
+    .style(Style::default().bg(colors::background()))
+}
+
+/// Render a flashed footnote, wrapped and centered in the reading zone.
+pub fn render_note(note: &str) -> Paragraph<'static> {
+    let lines = vec![
+        Line::from(Span::styled(
+            "Note",
+            Style::default()
+                .fg(colors::anchor())
+                .add_modifier(ratatui::style::Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            note.to_string(),
+            Style::default().fg(colors::text()),
+        )),
+    ];
+
+    Paragraph::new(lines)
         .alignment(Alignment::Center)
-        .style(Style::default().fg(colors::dimmed()).bg(colors::background()))
+        .wrap(Wrap { trim: true })
+        .style(Style::default().bg(colors::background()))
 }
 
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::widgets::{Block, Borders, Clear};
-use ratatui::Frame;
-use crate::app::mode::AppMode;
+/// Render the `:messages` panel: recent notifications, newest at the
+/// bottom, each labelled with its severity.
+pub fn render_messages(messages: &[&Notification]) -> Paragraph<'static> {
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Messages",
+            Style::default()
+                .fg(colors::anchor())
+                .add_modifier(ratatui::style::Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    if messages.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No messages yet",
+            Style::default().fg(colors::dimmed()),
+        )));
+    }
+    for message in messages {
+        let label_color = match message.severity {
+            Severity::Info => colors::dimmed(),
+            Severity::Warning | Severity::Error => colors::anchor(),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:>7} ", message.severity.label()),
+                Style::default().fg(label_color),
+            ),
+            Span::styled(message.message.clone(), Style::default().fg(colors::text())),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Press any key to close",
+        Style::default().fg(colors::dimmed()),
+    )));
+
+    Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .style(Style::default().bg(colors::background()))
+}
 
-pub fn render_command_deck(frame: &mut Frame, area: Rect, mode: AppMode, command_buffer: &str) {
+pub fn render_placeholder() -> Paragraph<'static> {
+    let text = "Type @filename to load a file\nOr @@ to load from clipboard\n:q to quit";
+    Paragraph::new(text).alignment(Alignment::Center).style(
+        Style::default()
+            .fg(colors::dimmed())
+            .bg(colors::background()),
+    )
+}
+
+/// Render the command deck; the state's notice replaces the usage hint while
+/// the command buffer is empty, and its queue, clipboard watch and loading
+/// status add `[2/5]`, `[watch]` and `[loading 12/300 pages]` indicators.
+pub fn render_command_deck(
+    frame: &mut Frame,
+    area: Rect,
+    mode: AppMode,
+    command_buffer: &str,
+    state: &RenderState,
+) {
     // Clear the command area first
     frame.render_widget(Clear, area);
 
@@ -141,8 +245,8 @@ pub fn render_command_deck(frame: &mut Frame, area: Rect, mode: AppMode, command
         .split(area);
 
     // Left accent bar
-    let accent_bar = Paragraph::new("▌")
-        .style(Style::default().fg(colors::anchor()).bg(colors::surface()));
+    let accent_bar =
+        Paragraph::new("▌").style(Style::default().fg(colors::anchor()).bg(colors::surface()));
     frame.render_widget(accent_bar, layout[0]);
 
     // Command input area
@@ -154,19 +258,51 @@ pub fn render_command_deck(frame: &mut Frame, area: Rect, mode: AppMode, command
         AppMode::Quit => " QUIT ",
     };
 
-    let input_text = if command_buffer.is_empty() {
-        format!("{} Type @file.pdf, @@, or :q", mode_indicator)
-    } else {
-        format!("{} {}", mode_indicator, command_buffer)
+    let mode_indicator = match state.queue_position {
+        Some((position, total)) => format!("{}[{}/{}] ", mode_indicator, position, total),
+        None => mode_indicator.to_string(),
+    };
+    let mode_indicator = match state.section {
+        Some((section, total)) => format!("{}[§ {}/{}] ", mode_indicator, section, total),
+        None => mode_indicator,
     };
-    
+    let mode_indicator = match state.clipboard_watch {
+        Some(0) => format!("{}[watch] ", mode_indicator),
+        Some(pending) => format!("{}[watch +{}] ", mode_indicator, pending),
+        None => mode_indicator,
+    };
+    let mode_indicator = match state.loading {
+        Some(Some(p)) => format!(
+            "{}[loading {}/{} {}] ",
+            mode_indicator,
+            p.done,
+            p.total,
+            p.unit.name()
+        ),
+        Some(None) => format!("{}[loading] ", mode_indicator),
+        None => mode_indicator,
+    };
+
+    let notice = state.notice.filter(|_| command_buffer.is_empty());
+    let input_text = match (command_buffer.is_empty(), notice) {
+        (true, Some(notice)) => format!("{} {}", mode_indicator, notice.message),
+        (true, None) => format!("{} Type @file.pdf, @@, or :q", mode_indicator),
+        (false, _) => format!("{} {}", mode_indicator, command_buffer),
+    };
+    // Warnings and errors stand out from informational notices
+    let text_color = match notice.map(|n| n.severity) {
+        Some(Severity::Warning | Severity::Error) => colors::anchor(),
+        _ => colors::text(),
+    };
+
     let input_widget = Paragraph::new(input_text)
-        .block(Block::default()
-            .borders(Borders::TOP)
-            .border_style(Style::default().fg(colors::dimmed()))
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .border_style(Style::default().fg(colors::dimmed())),
         )
-        .style(Style::default().fg(colors::text()).bg(colors::surface()));
-    
+        .style(Style::default().fg(text_color).bg(colors::surface()));
+
     frame.render_widget(input_widget, layout[1]);
 }
 
@@ -206,18 +342,24 @@ mod tests {
 
     #[test]
     fn test_render_context_left_empty_tokens() {
-        let tokens: Vec<Token> = vec![];
+        let tokens = TokenStore::new();
         let paragraph = render_context_left(&tokens, 0, 3);
         let _ = paragraph;
     }
 
     #[test]
     fn test_render_context_right_empty_tokens() {
-        let tokens: Vec<Token> = vec![];
+        let tokens = TokenStore::new();
         let paragraph = render_context_right(&tokens, 0, 3);
         let _ = paragraph;
     }
 
+    #[test]
+    fn test_render_note_creates_paragraph() {
+        let paragraph = render_note("See the appendix.");
+        let _ = paragraph;
+    }
+
     #[test]
     fn test_render_gutter_placeholder_creates_paragraph() {
         let paragraph = render_gutter_placeholder();
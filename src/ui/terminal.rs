@@ -1,8 +1,10 @@
 use crate::app::{mode::AppMode, App};
-use crate::engine::wpm_to_milliseconds;
+use crate::input::clipboard;
+use crate::ui::command::{command_to_app_event, parse_command};
 use crate::ui::reader::view::{
-    render_command_deck, render_context_left, render_context_right, render_gutter_placeholder,
-    render_placeholder, render_progress_bar, render_word_display,
+    render_chunk_display, render_command_deck, render_context_left, render_context_right,
+    render_gutter_placeholder, render_messages, render_note, render_placeholder,
+    render_progress_bar, render_title_card, render_word_display,
 };
 use crossterm::{
     event::{self, Event, KeyCode},
@@ -11,7 +13,8 @@ use crossterm::{
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::Clear,
     Terminal,
 };
 use std::io::{self, Stdout};
@@ -30,12 +33,17 @@ impl TuiManager {
         let backend = CrosstermBackend::new(io::stdout());
         let terminal = Terminal::new(backend)?;
 
-        Ok(TuiManager { terminal, command_buffer: String::new() })
+        Ok(TuiManager {
+            terminal,
+            command_buffer: String::new(),
+        })
     }
 
     pub fn run_event_loop(&mut self, app: &mut App) -> io::Result<AppMode> {
         let mut last_tick = Instant::now();
         let render_tick = Duration::from_millis(1000 / 60);
+        let mut last_clipboard_poll = Instant::now();
+        let clipboard_poll = Duration::from_millis(clipboard::WATCH_INTERVAL_MS);
 
         loop {
             let current_mode = app.mode();
@@ -46,20 +54,38 @@ impl TuiManager {
             // Command mode shows the command deck for input
             // Reading and Paused modes show the RSVP display
 
-            let wpm = app.get_wpm();
-            let timeout_ms = wpm_to_milliseconds(wpm);
+            // Watched clipboard: pick up new passages, but not while the
+            // user is typing a command
+            if app.clipboard_watch.is_some()
+                && self.command_buffer.is_empty()
+                && last_clipboard_poll.elapsed() >= clipboard_poll
+            {
+                app.poll_clipboard();
+                last_clipboard_poll = Instant::now();
+            }
+
+            // Background load: progress, partial documents, the result
+            if app.load_job.is_some() {
+                app.poll_loading();
+            }
+
+            let timeout_ms = app.current_delay_ms();
             let poll_timeout = Duration::from_millis(timeout_ms);
 
             match event::poll(poll_timeout) {
                 Ok(true) => {
                     if let Event::Key(key) = event::read()? {
                         // Handle Ctrl+C to quit
-                        if key.code == KeyCode::Char('c') && key.modifiers.contains(event::KeyModifiers::CONTROL) {
+                        if key.code == KeyCode::Char('c')
+                            && key.modifiers.contains(event::KeyModifiers::CONTROL)
+                        {
                             app.set_mode(AppMode::Quit);
                             return Ok(AppMode::Quit);
                         }
-                        
+
                         match key.code {
+                            // Any key closes the :messages panel
+                            _ if app.close_messages() => {}
                             KeyCode::Char(c) => {
                                 if app.mode() == AppMode::Command {
                                     // In command mode, collect input
@@ -70,69 +96,17 @@ impl TuiManager {
                                 }
                             }
                             KeyCode::Enter => {
-                                if app.mode() == AppMode::Command && !self.command_buffer.is_empty() {
+                                if app.mode() == AppMode::Command && !self.command_buffer.is_empty()
+                                {
                                     // Execute the command
                                     let command = self.command_buffer.clone();
                                     self.command_buffer.clear();
-                                    
-                                    // Parse and execute
-                                    use crate::ui::command::{parse_command, Command};
-                                    match parse_command(&command) {
-                                        Command::LoadFile(path) => {
-                                            // Load the file using input module
-                                            use crate::input::pdf;
-                                            match pdf::load(&path) {
-                                                Ok(doc) => {
-                                                    let text: String = doc.tokens.iter()
-                                                        .map(|t| {
-                                                            let mut s = t.text.clone();
-                                                            for p in &t.punctuation {
-                                                                s.push(*p);
-                                                            }
-                                                            s
-                                                        })
-                                                        .collect::<Vec<_>>()
-                                                        .join(" ");
-                                                    app.start_reading(&text, 300);
-                                                }
-                                                Err(e) => {
-                                                    eprintln!("Failed to load file: {}", e);
-                                                }
-                                            }
-                                        }
-                                        Command::LoadClipboard => {
-                                            // Load from clipboard
-                                            use crate::input::clipboard;
-                                            match clipboard::load() {
-                                                Ok(doc) => {
-                                                    let text: String = doc.tokens.iter()
-                                                        .map(|t| {
-                                                            let mut s = t.text.clone();
-                                                            for p in &t.punctuation {
-                                                                s.push(*p);
-                                                            }
-                                                            s
-                                                        })
-                                                        .collect::<Vec<_>>()
-                                                        .join(" ");
-                                                    app.start_reading(&text, 300);
-                                                }
-                                                Err(e) => {
-                                                    eprintln!("Failed to load clipboard: {}", e);
-                                                }
-                                            }
-                                        }
-                                        Command::Quit => {
-                                            app.set_mode(AppMode::Quit);
-                                            return Ok(AppMode::Quit);
-                                        }
-                                        Command::Help => {
-                                            // Show help - for now just stay in command mode
-                                        }
-                                        Command::Unknown(_) => {
-                                            // Invalid command - could show error in UI
-                                            eprintln!("Unknown command: {}", command);
-                                        }
+
+                                    // Parse and execute through the App, which picks the
+                                    // loader by extension and keeps attached notes intact
+                                    app.handle_event(command_to_app_event(parse_command(&command)));
+                                    if app.mode() == AppMode::Quit {
+                                        return Ok(AppMode::Quit);
                                     }
                                 }
                             }
@@ -142,7 +116,12 @@ impl TuiManager {
                                 }
                             }
                             KeyCode::Esc => {
-                                if app.mode() == AppMode::Reading || app.mode() == AppMode::Paused {
+                                // Esc cancels a background load before anything else
+                                let cancelled = app.cancel_loading();
+                                if !cancelled
+                                    && (app.mode() == AppMode::Reading
+                                        || app.mode() == AppMode::Paused)
+                                {
                                     app.set_mode(AppMode::Command);
                                     self.command_buffer.clear();
                                 }
@@ -189,22 +168,28 @@ impl TuiManager {
             let reading_layout = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints([
-                    Constraint::Percentage(35),  // Left context
-                    Constraint::Percentage(30),  // Center word
-                    Constraint::Percentage(32),  // Right context
-                    Constraint::Percentage(3),   // Gutter
+                    Constraint::Percentage(35), // Left context
+                    Constraint::Percentage(30), // Center word
+                    Constraint::Percentage(32), // Right context
+                    Constraint::Percentage(3),  // Gutter
                 ])
                 .split(reading_area);
 
             // Render left context
             let left_context =
-                render_context_left(&render_state.tokens, render_state.current_index, 3);
+                render_context_left(render_state.tokens, render_state.current_index, 3);
             frame.render_widget(left_context, reading_layout[0]);
 
-            // Render center word with OVP anchoring
-            if let Some(word) = &render_state.current_word {
+            // Render center word with OVP anchoring, or the current chunk
+            if !render_state.chunk.is_empty() {
+                frame.render_widget(render_chunk_display(&render_state.chunk), reading_layout[1]);
+            } else if let Some(word) = &render_state.current_word {
                 let anchor_pos = crate::reading::calculate_anchor_position(word);
-                let word_display = render_word_display(word, anchor_pos);
+                let word_display = if render_state.hyphen {
+                    render_word_display(&format!("{}-", word), anchor_pos)
+                } else {
+                    render_word_display(word, anchor_pos)
+                };
                 frame.render_widget(word_display, reading_layout[1]);
             } else {
                 // Show placeholder when no content loaded
@@ -212,17 +197,59 @@ impl TuiManager {
                 frame.render_widget(placeholder, reading_layout[1]);
             }
 
-            // Render right context
-            let right_context =
-                render_context_right(&render_state.tokens, render_state.current_index, 3);
+            // Render right context, after the last word of a chunk
+            let last_shown =
+                render_state.current_index + render_state.chunk.len().saturating_sub(1);
+            let right_context = render_context_right(render_state.tokens, last_shown, 3);
             frame.render_widget(right_context, reading_layout[2]);
 
             // Render gutter
             let gutter = render_gutter_placeholder();
             frame.render_widget(gutter, reading_layout[3]);
 
+            // Flashed footnote covers the reading zone until the next key
+            if let Some(note) = &render_state.note {
+                let note_area = Rect {
+                    y: reading_area.y + reading_area.height / 3,
+                    height: reading_area.height - reading_area.height / 3,
+                    ..reading_area
+                };
+                frame.render_widget(Clear, reading_area);
+                frame.render_widget(render_note(note), note_area);
+            }
+
+            // Queued document titles flash between items
+            if let Some(title) = &render_state.title_card {
+                let title_area = Rect {
+                    y: reading_area.y + reading_area.height / 2,
+                    height: reading_area.height - reading_area.height / 2,
+                    ..reading_area
+                };
+                frame.render_widget(Clear, reading_area);
+                frame.render_widget(render_title_card(title), title_area);
+            }
+
+            // :messages lists recent notifications over the reading zone,
+            // newest at the bottom
+            if let Some(messages) = &render_state.messages {
+                let shown = messages
+                    .len()
+                    .min(reading_area.height.saturating_sub(4) as usize);
+                frame.render_widget(Clear, reading_area);
+                frame.render_widget(
+                    render_messages(&messages[messages.len() - shown..]),
+                    reading_area,
+                );
+            }
+
             // Command deck area
-            render_command_deck(frame, command_area, app.mode(), &self.command_buffer);
+            render_command_deck(
+                frame,
+                command_area,
+                app.mode(),
+                &self.command_buffer,
+                &render_state,
+            );
         })?;
 
         Ok(())
@@ -102,23 +102,44 @@ fn tui_workflow_reading_advance() {
     app.start_reading(content, 300);
 
     let initial_state = app.get_render_state();
-    assert_eq!(initial_state.current_word, Some("word1".to_string()));
+    assert_eq!(initial_state.current_word, Some("word1"));
 
     let advanced = app.advance_reading();
     assert!(advanced);
 
     let after_advance = app.get_render_state();
-    assert_eq!(after_advance.current_word, Some("word2".to_string()));
+    assert_eq!(after_advance.current_word, Some("word2"));
 
     app.advance_reading();
     app.advance_reading();
     let third_advance = app.get_render_state();
-    assert_eq!(third_advance.current_word, Some("word4".to_string()));
+    assert_eq!(third_advance.current_word, Some("word4"));
 
     app.advance_reading();
     let final_advance = app.get_render_state();
-    assert_eq!(final_advance.current_word, Some("word5".to_string()));
+    assert_eq!(final_advance.current_word, Some("word5"));
 
     let no_more = app.advance_reading();
     assert!(!no_more);
 }
+
+#[test]
+fn pdf_page_range_loading() {
+    use speedy::input::{pdf, LoadError, LoadOptions, LoadRange};
+
+    let path = "tests/test_files/lorem-ipsum.pdf";
+    let options = |spec: &str| LoadOptions {
+        range: LoadRange::parse(spec),
+        ..LoadOptions::default()
+    };
+
+    let doc = pdf::load_with_options(path, &options("p1")).expect("first page should load");
+    assert!(!doc.tokens.is_empty());
+    assert!(doc.source.ends_with("#p1"));
+
+    let past_end = pdf::load_with_options(path, &options("p500-600"));
+    assert!(matches!(past_end, Err(LoadError::InvalidRange(_))));
+
+    let chapters = pdf::load_with_options(path, &options("ch1"));
+    assert!(matches!(chapters, Err(LoadError::InvalidRange(_))));
+}
@@ -0,0 +1,59 @@
+//! Sentence segmentation regression corpus (tests/test_files/sentences.txt)
+
+use speedy::reading::timing::tokenize_text_in;
+
+/// Splits a corpus line into its language tag and text, and the indices of
+/// the words marked with `|` as sentence starts.
+fn parse_case(line: &str) -> (Option<&str>, String, Vec<usize>) {
+    let (language, text) = match line.strip_prefix('[') {
+        Some(rest) => {
+            let (tag, text) = rest.split_once(']').expect("closing ] after language");
+            (Some(tag), text.trim())
+        }
+        None => (None, line),
+    };
+
+    let mut words = Vec::new();
+    let mut starts = vec![0];
+    for (index, word) in text.split_whitespace().enumerate() {
+        match word.strip_prefix('|') {
+            Some(word) => {
+                starts.push(index);
+                words.push(word);
+            }
+            None => words.push(word),
+        }
+    }
+    (language, words.join(" "), starts)
+}
+
+#[test]
+fn sentence_corpus() {
+    let corpus = std::fs::read_to_string("tests/test_files/sentences.txt").unwrap();
+    let mut failures = Vec::new();
+    let mut cases = 0;
+
+    for line in corpus.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        cases += 1;
+        let (language, text, expected) = parse_case(line);
+        let starts: Vec<usize> = tokenize_text_in(&text, language)
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| token.is_sentence_start)
+            .map(|(index, _)| index)
+            .collect();
+        if starts != expected {
+            failures.push(format!(
+                "{}\n  expected {:?}, got {:?}",
+                line, expected, starts
+            ));
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    assert!(cases >= 20, "corpus has only {} cases", cases);
+}